@@ -0,0 +1,109 @@
+use criterion::{ criterion_group, criterion_main, BenchmarkId, Criterion };
+use bracket_lib::prelude::Point;
+use rust_rl::*;
+use specs::prelude::*;
+use std::time::{ Duration, Instant };
+
+const WIDTH: i32 = 80;
+const HEIGHT: i32 = 50;
+
+/// Builds an open level with a player in the middle, and n monsters scattered around
+/// it - a third chasing the player, a third approaching them, and a third fleeing.
+fn setup(n: usize) -> (World, Entity, Vec<(Entity, Position)>) {
+    let mut world = World::new();
+    System::setup(&mut spatial::MapIndexingSystem {}, &mut world);
+    System::setup(&mut ai::FlowFieldSystem {}, &mut world);
+    System::setup(&mut ai::ApproachAI {}, &mut world);
+    System::setup(&mut ai::FleeAI {}, &mut world);
+    System::setup(&mut ai::ChaseAI {}, &mut world);
+    let mut map = Map::new(false, 1, WIDTH, HEIGHT, 0, "Bench", "B", 0);
+    for y in 1..HEIGHT - 1 {
+        for x in 1..WIDTH - 1 {
+            let idx = map.xy_idx(x, y);
+            map.tiles[idx] = TileType::Floor;
+        }
+    }
+    world.insert(map);
+    world.insert(ai::FlowFields::new());
+    let player = world
+        .create_entity()
+        .with(Position { x: WIDTH / 2, y: HEIGHT / 2 })
+        .with(BlocksTile {})
+        .build();
+    let mut monsters = Vec::new();
+    for i in 0..n {
+        let pos = Position {
+            x: 2 + (((i * 7) as i32) % (WIDTH - 4)),
+            y: 2 + (((i * 3) as i32) % (HEIGHT - 4)),
+        };
+        let monster = world.create_entity().with(pos.clone()).with(BlocksTile {}).build();
+        monsters.push((monster, pos));
+    }
+    (world, player, monsters)
+}
+
+/// Puts everyone back where they started, and gives them all a turn. Every monster
+/// can see the player, so nobody chasing them loses track of them and gives up.
+fn reset(world: &mut World, player: Entity, monsters: &[(Entity, Position)]) {
+    let player_idx = world.fetch::<Map>().xy_idx(WIDTH / 2, HEIGHT / 2);
+    {
+        let mut positions = world.write_storage::<Position>();
+        let mut viewsheds = world.write_storage::<Viewshed>();
+        let mut turns = world.write_storage::<TakingTurn>();
+        let mut chasing = world.write_storage::<Chasing>();
+        let mut approach = world.write_storage::<WantsToApproach>();
+        let mut flee = world.write_storage::<WantsToFlee>();
+        for (i, (monster, pos)) in monsters.iter().enumerate() {
+            positions.insert(*monster, pos.clone()).expect("Unable to insert Position");
+            viewsheds
+                .insert(*monster, Viewshed {
+                    visible_tiles: vec![Point::new(WIDTH / 2, HEIGHT / 2)],
+                    range: 8,
+                    dirty: false,
+                })
+                .expect("Unable to insert Viewshed");
+            turns.insert(*monster, TakingTurn {}).expect("Unable to insert TakingTurn");
+            if i % 3 == 0 {
+                chasing.insert(*monster, Chasing { target: player }).expect("Unable to insert Chasing");
+            } else if i % 3 == 1 {
+                approach
+                    .insert(*monster, WantsToApproach { idx: player_idx as i32 })
+                    .expect("Unable to insert WantsToApproach");
+            } else if i % 3 == 2 {
+                flee
+                    .insert(*monster, WantsToFlee { indices: vec![player_idx] })
+                    .expect("Unable to insert WantsToFlee");
+            }
+        }
+    }
+    spatial::MapIndexingSystem {}.run_now(world);
+    world.maintain();
+}
+
+/// Measures how long the movement AI takes for a single turn as the number of monsters
+/// on a level grows. The flow fields persist between turns, as they would in game.
+fn ai_turn_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ai turn");
+    for n in [10, 50, 100, 200] {
+        let (mut world, player, monsters) = setup(n);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    reset(&mut world, player, &monsters);
+                    let start = Instant::now();
+                    ai::FlowFieldSystem {}.run_now(&world);
+                    ai::ApproachAI {}.run_now(&world);
+                    ai::FleeAI {}.run_now(&world);
+                    ai::ChaseAI {}.run_now(&world);
+                    total += start.elapsed();
+                }
+                total
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, ai_turn_benchmark);
+criterion_main!(benches);
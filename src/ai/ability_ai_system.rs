@@ -0,0 +1,207 @@
+use crate::{
+    effects::{ add_effect, cone_tiles, EffectType, Targets },
+    raws::{ self, Reaction },
+    Abilities,
+    AbilityEffect,
+    Faction,
+    Fleeing,
+    HasAncestry,
+    Map,
+    Pools,
+    Position,
+    TakingTurn,
+    Viewshed,
+};
+use crate::data::entity::ABILITY_HEAL_HP_PERCENT;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Decides when mobs use their abilities. Each ability is considered in the order it's
+/// listed in the raws, and the first one that's ready, affordable, and worth using right
+/// now is used in place of the mob's turn.
+pub struct AbilityAI {}
+
+impl<'a> System<'a> for AbilityAI {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, TakingTurn>,
+        WriteStorage<'a, Abilities>,
+        WriteStorage<'a, Pools>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Viewshed>,
+        ReadStorage<'a, Faction>,
+        ReadStorage<'a, HasAncestry>,
+        ReadStorage<'a, Fleeing>,
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, Entity>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut turns,
+            mut abilities,
+            mut pools,
+            positions,
+            viewsheds,
+            factions,
+            ancestries,
+            fleeing,
+            map,
+            player,
+        ) = data;
+
+        let mut turn_done: Vec<Entity> = Vec::new();
+        let mut mana_spent: Vec<(Entity, i32)> = Vec::new();
+        let raws = &raws::RAWS.lock().unwrap();
+        for (entity, _turn, abilities, pos, viewshed) in (
+            &entities,
+            &turns,
+            &mut abilities,
+            &positions,
+            &viewsheds,
+        ).join() {
+            if entity == *player {
+                continue;
+            }
+            for ability in abilities.abilities.iter_mut() {
+                if ability.ready_in > 0 {
+                    ability.ready_in -= 1;
+                }
+            }
+            let mana = pools.get(entity).map_or(0, |pool| pool.mana.current);
+            let here = Point::new(pos.x, pos.y);
+            let my_faction = factions.get(entity).map(|f| f.name.clone());
+            // Everyone in sight worth fighting or helping, nearest first.
+            let mut hostiles: Vec<(Entity, Point, f32)> = Vec::new();
+            let mut friends: Vec<(Entity, Point, f32)> = vec![(entity, here, 0.0)];
+            for tile in viewshed.visible_tiles.iter() {
+                crate::spatial::for_each_tile_content(map.xy_idx(tile.x, tile.y), |other| {
+                    if other == entity || pools.get(other).is_none() {
+                        return;
+                    }
+                    let distance = DistanceAlg::Pythagoras.distance2d(here, *tile);
+                    let reaction = raws::get_reactions(
+                        entity,
+                        other,
+                        &factions,
+                        &ancestries,
+                        raws
+                    );
+                    if reaction == Reaction::Attack {
+                        hostiles.push((other, *tile, distance));
+                    } else if factions.get(other).map(|f| f.name.clone()) == my_faction {
+                        friends.push((other, *tile, distance));
+                    }
+                });
+            }
+            hostiles.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+            friends.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+            let routed = fleeing.get(entity).is_some();
+            let nearest = hostiles.first();
+
+            let mut chosen: Option<(usize, Targets)> = None;
+            for (i, ability) in abilities.abilities.iter().enumerate() {
+                if ability.ready_in > 0 || ability.mana > mana {
+                    continue;
+                }
+                let range = ability.range as f32;
+                let target = match &ability.effect {
+                    AbilityEffect::Heal { .. } =>
+                        friends
+                            .iter()
+                            .find(|(friend, _, distance)| {
+                                *distance <= range &&
+                                    pools.get(*friend).map_or(false, |pool| {
+                                        pool.hit_points.current * 100 <=
+                                            pool.hit_points.max * ABILITY_HEAL_HP_PERCENT
+                                    })
+                            })
+                            .map(|(friend, _, _)| Targets::Entity { target: *friend }),
+                    // Get out of reach, if something's too close, or if we're running anyway.
+                    AbilityEffect::Blink => {
+                        let cornered = nearest.map_or(false, |(_, _, distance)| *distance < 1.5);
+                        if cornered || (routed && nearest.is_some()) {
+                            blink_destination(&map, here, viewshed, &hostiles, range).map(
+                                |target| Targets::Tile { target }
+                            )
+                        } else {
+                            None
+                        }
+                    }
+                    // Anything else is an attack, and a mob that's lost its nerve won't.
+                    _ if routed => None,
+                    AbilityEffect::Summon { .. } =>
+                        nearest.map(|_| Targets::Tile { target: map.xy_idx(pos.x, pos.y) }),
+                    AbilityEffect::Bolt { .. } =>
+                        nearest
+                            .filter(|(_, _, distance)| *distance <= range)
+                            .map(|(victim, _, _)| Targets::Entity { target: *victim }),
+                    AbilityEffect::Breath { .. } =>
+                        nearest
+                            .filter(|(_, _, distance)| *distance <= range)
+                            .map(|(_, point, _)| cone_tiles(&map, here, *point, ability.range))
+                            .filter(|tiles| {
+                                // Don't breathe on friends.
+                                !friends
+                                    .iter()
+                                    .any(|(_, p, _)| tiles.contains(&map.xy_idx(p.x, p.y)))
+                            })
+                            .map(|targets| Targets::TileList { targets }),
+                };
+                if let Some(target) = target {
+                    chosen = Some((i, target));
+                    break;
+                }
+            }
+            if let Some((i, target)) = chosen {
+                let ability = &mut abilities.abilities[i];
+                ability.ready_in = ability.cooldown;
+                mana_spent.push((entity, ability.mana));
+                add_effect(Some(entity), EffectType::AbilityUse { ability: i }, target);
+                turn_done.push(entity);
+            }
+        }
+        for (entity, mana) in mana_spent {
+            if let Some(pool) = pools.get_mut(entity) {
+                pool.mana.current -= mana;
+            }
+        }
+        for done in turn_done.iter() {
+            turns.remove(*done);
+        }
+    }
+}
+
+/// The free tile in sight and in range that's furthest from anything hostile, if it's any
+/// further than where we are now.
+fn blink_destination(
+    map: &Map,
+    here: Point,
+    viewshed: &Viewshed,
+    hostiles: &[(Entity, Point, f32)],
+    range: f32
+) -> Option<usize> {
+    let danger = |point: Point| {
+        hostiles
+            .iter()
+            .map(|(_, hostile, _)| DistanceAlg::Pythagoras.distance2d(point, *hostile))
+            .fold(f32::MAX, f32::min)
+    };
+    let mut best: Option<(usize, f32)> = None;
+    for tile in viewshed.visible_tiles.iter() {
+        let idx = map.xy_idx(tile.x, tile.y);
+        if
+            DistanceAlg::Pythagoras.distance2d(here, *tile) > range ||
+            crate::spatial::is_blocked(idx)
+        {
+            continue;
+        }
+        let distance = danger(*tile);
+        if best.map_or(true, |(_, furthest)| distance > furthest) {
+            best = Some((idx, distance));
+        }
+    }
+    best.filter(|(_, distance)| *distance > danger(here)).map(|(idx, _)| idx)
+}
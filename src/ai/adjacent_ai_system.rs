@@ -1,4 +1,13 @@
-use crate::{ raws::Reaction, Faction, HasAncestry, Map, Position, TakingTurn, WantsToMelee };
+use crate::{
+    raws::Reaction,
+    Faction,
+    Fleeing,
+    HasAncestry,
+    Map,
+    Position,
+    TakingTurn,
+    WantsToMelee,
+};
 use specs::prelude::*;
 
 pub struct AdjacentAI {}
@@ -14,14 +23,26 @@ impl<'a> System<'a> for AdjacentAI {
         WriteStorage<'a, WantsToMelee>,
         Entities<'a>,
         ReadExpect<'a, Entity>,
+        ReadStorage<'a, Fleeing>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut turns, factions, ancestries, positions, map, mut want_melee, entities, player) = data;
+        let (
+            mut turns,
+            factions,
+            ancestries,
+            positions,
+            map,
+            mut want_melee,
+            entities,
+            player,
+            fleeing,
+        ) = data;
 
         let mut turn_done: Vec<Entity> = Vec::new();
         for (entity, _turn, pos) in (&entities, &turns, &positions).join() {
-            if entity != *player {
+            // Fleeing mobs only fight back if they're cornered - which is up to FleeAI.
+            if entity != *player && fleeing.get(entity).is_none() {
                 let mut reactions: Vec<(Entity, Reaction)> = Vec::new();
                 let idx = map.xy_idx(pos.x, pos.y);
                 let w = map.width;
@@ -0,0 +1,177 @@
+use crate::{
+    Ally,
+    AllyCommand,
+    Chasing,
+    EntityMoved,
+    EntryTrigger,
+    Faction,
+    Map,
+    Position,
+    TakingTurn,
+    Telepath,
+    Viewshed,
+    WantsToApproach,
+    WantsToMelee,
+};
+use crate::data::entity::{ ALLY_FOLLOW_DISTANCE, ALLY_LEASH_DISTANCE };
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+use std::collections::HashMap;
+use super::flow_fields::FlowFields;
+
+/// Runs after VisibleAI, so any following ally with a turn has already picked out
+/// something hostile to go after. This decides whether they're allowed to stray that
+/// far from their owner, and carries out any other orders they've been given.
+pub struct AllyAI {}
+
+impl<'a> System<'a> for AllyAI {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'a, TakingTurn>,
+        WriteStorage<'a, Ally>,
+        WriteStorage<'a, Chasing>,
+        WriteStorage<'a, WantsToApproach>,
+        WriteStorage<'a, WantsToMelee>,
+        WriteStorage<'a, Position>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, FlowFields>,
+        WriteStorage<'a, Viewshed>,
+        WriteStorage<'a, Telepath>,
+        WriteStorage<'a, EntityMoved>,
+        ReadStorage<'a, EntryTrigger>,
+        ReadStorage<'a, Faction>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut turns,
+            mut allies,
+            mut chasing,
+            mut wants_to_approach,
+            mut wants_to_melee,
+            mut positions,
+            map,
+            mut flow_fields,
+            mut viewsheds,
+            mut telepaths,
+            mut entity_moved,
+            entry_triggers,
+            factions,
+            entities,
+        ) = data;
+        // Look up where everyone's owner and target are first, so that Position
+        // doesn't need to be read while it's being joined over mutably below.
+        let mut owner_positions: HashMap<Entity, (Point, String)> = HashMap::new();
+        let mut target_positions: HashMap<Entity, (Entity, Point)> = HashMap::new();
+        for (entity, _turn, ally) in (&entities, &turns, &allies).join() {
+            let owner = (positions.get(ally.owner), factions.get(ally.owner));
+            if let (Some(pos), Some(faction)) = owner {
+                owner_positions.insert(entity, (Point::new(pos.x, pos.y), faction.name.clone()));
+            }
+            if ally.command == AllyCommand::Attack {
+                if let Some(chase) = chasing.get(entity) {
+                    if let Some(pos) = positions.get(chase.target) {
+                        target_positions.insert(entity, (chase.target, Point::new(pos.x, pos.y)));
+                    }
+                }
+            }
+        }
+        let mut turn_done: Vec<Entity> = Vec::new();
+        for (entity, _turn, ally, mut pos, mut viewshed) in (
+            &entities,
+            &turns,
+            &mut allies,
+            &mut positions,
+            &mut viewsheds,
+        ).join() {
+            let here = Point::new(pos.x, pos.y);
+            match ally.command {
+                AllyCommand::Stay => {
+                    // AdjacentAI has already had them fight anything next to them,
+                    // so all that's left is to hold position.
+                    turn_done.push(entity);
+                }
+                AllyCommand::Attack => {
+                    if let Some((target, target_pos)) = target_positions.get(&entity) {
+                        wants_to_approach.remove(entity);
+                        if DistanceAlg::Pythagoras.distance2d(here, *target_pos) < 1.5 {
+                            wants_to_melee
+                                .insert(entity, WantsToMelee { target: *target })
+                                .expect("Unable to insert WantsToMelee");
+                            turn_done.push(entity);
+                        } else {
+                            // Leave the turn for ChaseAI to close the distance.
+                            chasing
+                                .insert(entity, Chasing { target: *target })
+                                .expect("Unable to insert Chasing");
+                        }
+                    } else {
+                        // The target is dead, or gone - go back to following.
+                        ally.command = AllyCommand::Follow;
+                        wants_to_approach.remove(entity);
+                        chasing.remove(entity);
+                        turn_done.push(entity);
+                    }
+                }
+                AllyCommand::Follow => {
+                    let (owner_pos, owner_side) = if let Some(owner) = owner_positions.get(&entity) {
+                        owner.clone()
+                    } else {
+                        continue;
+                    };
+                    let distance = DistanceAlg::Pythagoras.distance2d(here, owner_pos);
+                    let fighting =
+                        chasing.get(entity).is_some() || wants_to_approach.get(entity).is_some();
+                    if fighting && distance <= ALLY_LEASH_DISTANCE {
+                        continue;
+                    }
+                    wants_to_approach.remove(entity);
+                    chasing.remove(entity);
+                    turn_done.push(entity);
+                    if distance <= ALLY_FOLLOW_DISTANCE {
+                        continue;
+                    }
+                    // Owners are the only members of their side - the player's - so following
+                    // it leads straight to them, sharing the field with anything after them.
+                    let idx = map.xy_idx(pos.x, pos.y);
+                    let new_idx = if
+                        let Some(step) = flow_fields.step_towards_faction(&map, idx, &owner_side)
+                    {
+                        step
+                    } else {
+                        continue;
+                    };
+                    if is_trapped(new_idx, &entry_triggers) {
+                        continue;
+                    }
+                    pos.x = (new_idx as i32) % map.width;
+                    pos.y = (new_idx as i32) / map.width;
+                    entity_moved
+                        .insert(entity, EntityMoved { from: idx })
+                        .expect("Unable to insert EntityMoved");
+                    crate::spatial::move_entity(entity, idx, new_idx);
+                    viewshed.dirty = true;
+                    if let Some(telepath) = telepaths.get_mut(entity) {
+                        telepath.dirty = true;
+                    }
+                }
+            }
+        }
+        for done in turn_done.iter() {
+            turns.remove(*done);
+        }
+    }
+}
+
+/// Returns true if there's a trap (or anything else that goes off when stepped on) at idx.
+/// Allies won't step onto these, even if their owner can't see them.
+pub fn is_trapped(idx: usize, entry_triggers: &ReadStorage<EntryTrigger>) -> bool {
+    let mut trapped = false;
+    crate::spatial::for_each_tile_content(idx, |entity| {
+        if entry_triggers.get(entity).is_some() {
+            trapped = true;
+        }
+    });
+    return trapped;
+}
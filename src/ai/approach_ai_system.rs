@@ -1,5 +1,16 @@
-use crate::{ EntityMoved, Map, Position, TakingTurn, Telepath, Viewshed, WantsToApproach };
-use bracket_lib::prelude::*;
+use crate::{
+    Ally,
+    EntityMoved,
+    EntryTrigger,
+    Map,
+    Position,
+    TakingTurn,
+    Telepath,
+    Viewshed,
+    WantsToApproach,
+};
+use super::ally_ai_system::is_trapped;
+use super::flow_fields::FlowFields;
 use specs::prelude::*;
 
 pub struct ApproachAI {}
@@ -10,10 +21,13 @@ impl<'a> System<'a> for ApproachAI {
         WriteStorage<'a, TakingTurn>,
         WriteStorage<'a, WantsToApproach>,
         WriteStorage<'a, Position>,
-        WriteExpect<'a, Map>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, FlowFields>,
         WriteStorage<'a, Viewshed>,
         WriteStorage<'a, Telepath>,
         WriteStorage<'a, EntityMoved>,
+        ReadStorage<'a, Ally>,
+        ReadStorage<'a, EntryTrigger>,
         Entities<'a>,
     );
 
@@ -22,10 +36,13 @@ impl<'a> System<'a> for ApproachAI {
             mut turns,
             mut wants_to_approach,
             mut positions,
-            mut map,
+            map,
+            mut flow_fields,
             mut viewsheds,
             mut telepaths,
             mut entity_moved,
+            allies,
+            entry_triggers,
             entities,
         ) = data;
         let mut turn_done: Vec<Entity> = Vec::new();
@@ -37,42 +54,25 @@ impl<'a> System<'a> for ApproachAI {
             &turns,
         ).join() {
             turn_done.push(entity);
-            let target_idxs = if
-                let Some(paths) = get_adjacent_unblocked(&map, approach.idx as usize)
-            {
-                paths
-            } else {
-                continue;
-            };
-            let mut path: Option<NavigationPath> = None;
             let idx = map.xy_idx(pos.x, pos.y);
-            for tar_idx in target_idxs {
-                let potential_path = a_star_search(idx, tar_idx, &mut *map);
-                if potential_path.success && potential_path.steps.len() > 1 {
-                    if
-                        path.is_none() ||
-                        potential_path.steps.len() < path.as_ref().unwrap().steps.len()
-                    {
-                        path = Some(potential_path);
-                    }
-                }
-            }
-            let path = if path.is_some() {
-                path.unwrap()
+            // Follow the shared field towards the tile. If it's occupied, this stops next to it.
+            let target = [approach.idx as usize];
+            let step = if let Some(step) = flow_fields.step_towards(&map, idx, &target) {
+                step
             } else {
                 continue;
             };
-            if path.success && path.steps.len() > 1 {
-                let idx = map.xy_idx(pos.x, pos.y);
-                pos.x = (path.steps[1] as i32) % map.width;
-                pos.y = (path.steps[1] as i32) / map.width;
-                entity_moved.insert(entity, EntityMoved {}).expect("Unable to insert EntityMoved");
-                let new_idx = map.xy_idx(pos.x, pos.y);
-                crate::spatial::move_entity(entity, idx, new_idx);
-                viewshed.dirty = true;
-                if let Some(telepath) = telepaths.get_mut(entity) {
-                    telepath.dirty = true;
-                }
+            // Allies won't walk into traps, even to get at something.
+            if allies.get(entity).is_some() && is_trapped(step, &entry_triggers) {
+                continue;
+            }
+            pos.x = (step as i32) % map.width;
+            pos.y = (step as i32) / map.width;
+            entity_moved.insert(entity, EntityMoved { from: idx }).expect("Unable to insert EntityMoved");
+            crate::spatial::move_entity(entity, idx, step);
+            viewshed.dirty = true;
+            if let Some(telepath) = telepaths.get_mut(entity) {
+                telepath.dirty = true;
             }
         }
         wants_to_approach.clear();
@@ -81,25 +81,3 @@ impl<'a> System<'a> for ApproachAI {
         }
     }
 }
-
-/// Try to get an unblocked index within one tile of a given idx, or None.
-pub fn get_adjacent_unblocked(map: &WriteExpect<Map>, idx: usize) -> Option<Vec<usize>> {
-    let mut adjacent = Vec::new();
-    let x = (idx as i32) % map.width;
-    let y = (idx as i32) / map.width;
-    for i in -1..2 {
-        for j in -1..2 {
-            if i == 0 && j == 0 {
-                continue;
-            }
-            let new_idx = (x + i + (y + j) * map.width) as usize;
-            if !crate::spatial::is_blocked(new_idx) {
-                adjacent.push(new_idx);
-            }
-        }
-    }
-    if adjacent.is_empty() {
-        return None;
-    }
-    return Some(adjacent);
-}
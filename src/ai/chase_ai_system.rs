@@ -1,8 +1,19 @@
-use crate::{ Chasing, EntityMoved, Map, Position, TakingTurn, Telepath, Viewshed };
+use crate::{
+    Ally,
+    Chasing,
+    EntityMoved,
+    EntryTrigger,
+    Map,
+    Position,
+    TakingTurn,
+    Telepath,
+    Viewshed,
+};
 use bracket_lib::prelude::*;
 use specs::prelude::*;
 use std::collections::HashMap;
-use super::approach_ai_system::get_adjacent_unblocked;
+use super::flow_fields::FlowFields;
+use super::ally_ai_system::is_trapped;
 
 // If the target is beyond this distance, they're no longer being detected,
 // so stop following them. This is essentially a combined value of the sound
@@ -18,10 +29,13 @@ impl<'a> System<'a> for ChaseAI {
         WriteStorage<'a, TakingTurn>,
         WriteStorage<'a, Chasing>,
         WriteStorage<'a, Position>,
-        WriteExpect<'a, Map>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, FlowFields>,
         WriteStorage<'a, Viewshed>,
         WriteStorage<'a, Telepath>,
         WriteStorage<'a, EntityMoved>,
+        ReadStorage<'a, Ally>,
+        ReadStorage<'a, EntryTrigger>,
         Entities<'a>,
     );
 
@@ -30,10 +44,13 @@ impl<'a> System<'a> for ChaseAI {
             mut turns,
             mut chasing,
             mut positions,
-            mut map,
+            map,
+            mut flow_fields,
             mut viewsheds,
             mut telepaths,
             mut entity_moved,
+            allies,
+            entry_triggers,
             entities,
         ) = data;
         let mut targets: HashMap<Entity, (i32, i32)> = HashMap::new();
@@ -41,21 +58,32 @@ impl<'a> System<'a> for ChaseAI {
         // For every chasing entity with a turn, look for a valid target position,
         // and if found, store that position in a temporary HashMap. This gets around
         // needing to read Position twice - that would cause borrowchecker issues.
-        // If there's no valid target found, remove the chasing component.
+        // If there's no valid target found, or the target's been lost track of, remove
+        // the chasing component - anything that remembers where it was will go looking.
         for (entity, _turn, chasing) in (&entities, &turns, &chasing).join() {
             if let Some(target_pos) = positions.get(chasing.target) {
-                targets.insert(entity, (target_pos.x, target_pos.y));
-            } else {
-                end_chase.push(entity);
+                let target_point = Point::new(target_pos.x, target_pos.y);
+                let seen = viewsheds
+                    .get(entity)
+                    .map_or(false, |v| v.visible_tiles.contains(&target_point));
+                let sensed = telepaths
+                    .get(entity)
+                    .map_or(false, |t| t.telepath_tiles.contains(&target_point));
+                // Allies are always told where their target is.
+                if seen || sensed || allies.get(entity).is_some() {
+                    targets.insert(entity, (target_pos.x, target_pos.y));
+                    continue;
+                }
             }
+            end_chase.push(entity);
         }
         for done in end_chase.iter() {
             chasing.remove(*done);
         }
         end_chase.clear();
-        // Iterate over everyone who is *still* chasing, and path to the target
-        // stored in the HashMap. If successful, follow the path. If not, remove
-        // the chasing component.
+        // Iterate over everyone who is *still* chasing, and follow the shared flow
+        // field towards the target stored in the HashMap. If the target's too far
+        // away to follow, remove the chasing component.
         let mut turn_done: Vec<Entity> = Vec::new();
         for (entity, _turn, mut pos, _chase, mut viewshed) in (
             &entities,
@@ -66,44 +94,28 @@ impl<'a> System<'a> for ChaseAI {
         ).join() {
             turn_done.push(entity);
             let target_pos = targets[&entity];
-            let target_idx = map.xy_idx(target_pos.0, target_pos.1);
-            let target_idxs = if let Some(paths) = get_adjacent_unblocked(&map, target_idx) {
-                paths
-            } else {
-                continue;
-            };
-            let mut path: Option<NavigationPath> = None;
+            let target = [map.xy_idx(target_pos.0, target_pos.1)];
             let idx = map.xy_idx(pos.x, pos.y);
-            for tar_idx in target_idxs {
-                let potential_path = a_star_search(idx, tar_idx, &mut *map);
-                if potential_path.success && potential_path.steps.len() > 1 {
-                    if
-                        path.is_none() ||
-                        potential_path.steps.len() < path.as_ref().unwrap().steps.len()
-                    {
-                        path = Some(potential_path);
-                    }
-                }
+            if flow_fields.distance(&map, idx, &target) >= (MAX_CHASE_DISTANCE as f32) {
+                end_chase.push(entity);
+                continue;
             }
-            let path = if path.is_some() {
-                path.unwrap()
+            let step = if let Some(step) = flow_fields.step_towards(&map, idx, &target) {
+                step
             } else {
                 continue;
             };
-            if path.success && path.steps.len() > 1 && path.steps.len() < MAX_CHASE_DISTANCE {
-                let idx = map.xy_idx(pos.x, pos.y);
-                pos.x = (path.steps[1] as i32) % map.width;
-                pos.y = (path.steps[1] as i32) / map.width;
-                entity_moved.insert(entity, EntityMoved {}).expect("Failed to insert EntityMoved");
-                let new_idx = map.xy_idx(pos.x, pos.y);
-                crate::spatial::move_entity(entity, idx, new_idx);
-                viewshed.dirty = true;
-                if let Some(is_telepath) = telepaths.get_mut(entity) {
-                    is_telepath.dirty = true;
-                }
-                turn_done.push(entity);
-            } else {
-                end_chase.push(entity);
+            // Allies won't walk into traps, even to get at something.
+            if allies.get(entity).is_some() && is_trapped(step, &entry_triggers) {
+                continue;
+            }
+            pos.x = (step as i32) % map.width;
+            pos.y = (step as i32) / map.width;
+            entity_moved.insert(entity, EntityMoved { from: idx }).expect("Failed to insert EntityMoved");
+            crate::spatial::move_entity(entity, idx, step);
+            viewshed.dirty = true;
+            if let Some(is_telepath) = telepaths.get_mut(entity) {
+                is_telepath.dirty = true;
             }
         }
         for done in end_chase.iter() {
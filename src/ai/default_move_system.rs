@@ -9,6 +9,7 @@ use crate::{
     Telepath,
     Viewshed,
 };
+use super::flow_fields::FlowFields;
 use specs::prelude::*;
 use bracket_lib::prelude::*;
 
@@ -19,11 +20,13 @@ const CHANCE_OF_REMAINING_STATIONARY: i32 = 8;
 pub struct DefaultAI {}
 
 impl<'a> System<'a> for DefaultAI {
+    #[allow(clippy::type_complexity)]
     type SystemData = (
         WriteStorage<'a, TakingTurn>,
         WriteStorage<'a, MoveMode>,
         WriteStorage<'a, Position>,
-        WriteExpect<'a, Map>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, FlowFields>,
         WriteStorage<'a, Viewshed>,
         WriteStorage<'a, Telepath>,
         WriteStorage<'a, EntityMoved>,
@@ -36,7 +39,8 @@ impl<'a> System<'a> for DefaultAI {
             mut turns,
             mut move_mode,
             mut positions,
-            mut map,
+            map,
+            mut flow_fields,
             mut viewsheds,
             mut telepaths,
             mut entity_moved,
@@ -97,7 +101,7 @@ impl<'a> System<'a> for DefaultAI {
                             pos.x = x;
                             pos.y = y;
                             entity_moved
-                                .insert(entity, EntityMoved {})
+                                .insert(entity, EntityMoved { from: idx })
                                 .expect("Unable to insert EntityMoved");
                             crate::spatial::move_entity(entity, idx, dest_idx);
                             viewshed.dirty = true;
@@ -107,58 +111,33 @@ impl<'a> System<'a> for DefaultAI {
                         }
                     }
                 }
-                Movement::RandomWaypoint { path } => {
-                    if let Some(path) = path {
-                        // We have a path - follow it
+                Movement::RandomWaypoint { target } => {
+                    if let Some(waypoint) = *target {
+                        // Follow the shared field to the waypoint. Once it's reached, or
+                        // there's no way to get any closer, pick somewhere new next turn.
                         let idx = map.xy_idx(pos.x, pos.y);
-                        if path.len() > 1 {
-                            if !crate::spatial::is_blocked(path[1] as usize) {
-                                pos.x = (path[1] as i32) % map.width;
-                                pos.y = (path[1] as i32) / map.width;
-                                entity_moved
-                                    .insert(entity, EntityMoved {})
-                                    .expect("Unable to insert EntityMoved");
-                                let new_idx = map.xy_idx(pos.x, pos.y);
-                                crate::spatial::move_entity(entity, idx, new_idx);
-                                viewshed.dirty = true;
-                                if let Some(is_telepath) = telepaths.get_mut(entity) {
-                                    is_telepath.dirty = true;
-                                }
-                                path.remove(0);
-                            } else {
-                                // If the path is blocked, recalculate a new path to the same waypoint.
-                                let path = a_star_search(
-                                    map.xy_idx(pos.x, pos.y) as i32,
-                                    map.xy_idx(
-                                        (path[path.len() - 1] as i32) % map.width,
-                                        (path[path.len() - 1] as i32) / map.width
-                                    ) as i32,
-                                    &mut *map
-                                );
-                                if path.success && path.steps.len() > 1 {
-                                    move_mode.mode = Movement::RandomWaypoint {
-                                        path: Some(path.steps),
-                                    };
-                                }
+                        let step = flow_fields.step_towards(&map, idx, &[waypoint]);
+                        if step.is_none() || step == Some(waypoint) {
+                            *target = None;
+                        }
+                        if let Some(step) = step {
+                            pos.x = (step as i32) % map.width;
+                            pos.y = (step as i32) / map.width;
+                            entity_moved
+                                .insert(entity, EntityMoved { from: idx })
+                                .expect("Unable to insert EntityMoved");
+                            crate::spatial::move_entity(entity, idx, step);
+                            viewshed.dirty = true;
+                            if let Some(is_telepath) = telepaths.get_mut(entity) {
+                                is_telepath.dirty = true;
                             }
-                        } else {
-                            move_mode.mode = Movement::RandomWaypoint { path: None };
                         }
                     } else {
                         let target_x = rng.roll_dice(1, map.width - 2);
                         let target_y = rng.roll_dice(1, map.height - 2);
                         let idx = map.xy_idx(target_x, target_y);
                         if tile_walkable(map.tiles[idx]) {
-                            let path = a_star_search(
-                                map.xy_idx(pos.x, pos.y) as i32,
-                                map.xy_idx(target_x, target_y) as i32,
-                                &mut *map
-                            );
-                            if path.success && path.steps.len() > 1 {
-                                move_mode.mode = Movement::RandomWaypoint {
-                                    path: Some(path.steps),
-                                };
-                            }
+                            *target = Some(idx);
                         }
                     }
                 }
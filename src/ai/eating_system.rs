@@ -0,0 +1,69 @@
+use crate::{
+    effects::{ add_effect, EffectType, Targets },
+    gamelog,
+    Eating,
+    InBackpack,
+    Name,
+    TakingTurn,
+};
+use specs::prelude::*;
+
+/// Anyone in the middle of eating something spends their turn eating it. Once they've
+/// spent enough turns on it, the food is used, and its nutrition is applied.
+pub struct EatingSystem {}
+
+impl<'a> System<'a> for EatingSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteStorage<'a, Eating>,
+        WriteStorage<'a, TakingTurn>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Name>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, player_entity, mut eating, mut turns, backpack, names) = data;
+        let mut not_my_turn: Vec<Entity> = Vec::new();
+        let mut stopped_eating: Vec<Entity> = Vec::new();
+        let mut finished: Vec<(Entity, Entity)> = Vec::new();
+        for (entity, eats, _t) in (&entities, &mut eating, &turns).join() {
+            // If the food has gone somewhere, there's nothing left to eat.
+            if backpack.get(eats.food).map(|b| b.owner) != Some(entity) {
+                stopped_eating.push(entity);
+                if entity == *player_entity {
+                    gamelog::Logger::new().append("You stop eating.").log();
+                }
+                continue;
+            }
+            not_my_turn.push(entity);
+            eats.turns -= 1;
+            if eats.turns < 1 {
+                finished.push((entity, eats.food));
+            }
+        }
+        for e in not_my_turn {
+            turns.remove(e);
+        }
+        for e in stopped_eating {
+            eating.remove(e);
+        }
+        for (entity, food) in finished {
+            eating.remove(entity);
+            if entity == *player_entity {
+                if let Some(name) = names.get(food) {
+                    gamelog::Logger
+                        ::new()
+                        .append("You finish eating the")
+                        .append_n(&name.name)
+                        .period()
+                        .log();
+                }
+            }
+            add_effect(Some(entity), EffectType::ItemUse { item: food }, Targets::Entity {
+                target: entity,
+            });
+        }
+    }
+}
@@ -1,4 +1,17 @@
-use crate::{ gamelog, Attributes, Burden, EquipmentChanged, Equipped, InBackpack, Item, Pools };
+use crate::{
+    gamelog,
+    gui::quantity,
+    Attributes,
+    Burden,
+    Container,
+    EquipmentChanged,
+    Equipped,
+    InBackpack,
+    InContainer,
+    Item,
+    Pools,
+    Stackable,
+};
 use specs::prelude::*;
 use std::collections::HashMap;
 use crate::data::entity::CARRY_CAPACITY_PER_STRENGTH;
@@ -17,10 +30,26 @@ impl<'a> System<'a> for EncumbranceSystem {
         ReadStorage<'a, Attributes>,
         ReadExpect<'a, Entity>,
         WriteStorage<'a, Burden>,
+        ReadStorage<'a, Container>,
+        ReadStorage<'a, InContainer>,
+        ReadStorage<'a, Stackable>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut equip_dirty, entities, items, backpacks, wielded, mut pools, attributes, player, mut burdened) = data;
+        let (
+            mut equip_dirty,
+            entities,
+            items,
+            backpacks,
+            wielded,
+            mut pools,
+            attributes,
+            player,
+            mut burdened,
+            containers,
+            in_container,
+            stackables,
+        ) = data;
         if equip_dirty.is_empty() {
             return;
         }
@@ -31,17 +60,19 @@ impl<'a> System<'a> for EncumbranceSystem {
         }
         equip_dirty.clear();
         // Total up equipped items
-        for (item, equipped) in (&items, &wielded).join() {
+        for (entity, item, equipped) in (&entities, &items, &wielded).join() {
             if to_update.contains_key(&equipped.owner) {
                 let totals = to_update.get_mut(&equipped.owner).unwrap();
-                *totals += item.weight;
+                *totals += item.weight * (quantity(entity, &stackables) as f32);
             }
         }
         // Total carried items
-        for (item, carried) in (&items, &backpacks).join() {
+        for (entity, item, carried) in (&entities, &items, &backpacks).join() {
             if to_update.contains_key(&carried.owner) {
                 let totals = to_update.get_mut(&carried.owner).unwrap();
-                *totals += item.weight;
+                *totals +=
+                    item.weight * (quantity(entity, &stackables) as f32) +
+                    contents_weight(entity, &entities, &items, &containers, &in_container, &stackables);
             }
         }
         // Apply to pools
@@ -83,3 +114,29 @@ impl<'a> System<'a> for EncumbranceSystem {
         }
     }
 }
+
+/// Returns the weight a container adds on top of its own, including anything nested
+/// inside it, after applying its weight multiplier. Zero for anything that isn't a container.
+pub fn contents_weight(
+    container: Entity,
+    entities: &Entities,
+    items: &ReadStorage<Item>,
+    containers: &ReadStorage<Container>,
+    in_container: &ReadStorage<InContainer>,
+    stackables: &ReadStorage<Stackable>
+) -> f32 {
+    let multiplier = if let Some(c) = containers.get(container) {
+        c.weight_multiplier
+    } else {
+        return 0.0;
+    };
+    let mut total = 0.0;
+    for (entity, item, inside) in (entities, items, in_container).join() {
+        if inside.owner == container {
+            total +=
+                item.weight * (quantity(entity, stackables) as f32) +
+                contents_weight(entity, entities, items, containers, in_container, stackables);
+        }
+    }
+    total * multiplier
+}
@@ -1,5 +1,7 @@
 use crate::data::entity::*;
 use crate::{
+    tile_deep,
+    Aquatic,
     Burden,
     BurdenLevel,
     Clock,
@@ -38,6 +40,7 @@ impl<'a> System<'a> for EnergySystem {
         ReadExpect<'a, Point>,
         ReadStorage<'a, Confusion>,
         ReadStorage<'a, Intrinsics>,
+        ReadStorage<'a, Aquatic>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -56,6 +59,7 @@ impl<'a> System<'a> for EnergySystem {
             player_pos,
             confusion,
             intrinsics,
+            aquatic,
         ) = data;
         // If not ticking, do nothing.
         if *runstate != RunState::Ticking {
@@ -93,11 +97,13 @@ impl<'a> System<'a> for EnergySystem {
             let burden_modifier = get_burden_modifier(&burdens, entity);
             let overmap_mod = get_overmap_modifier(&map);
             let intrinsic_speed = get_intrinsic_speed(&intrinsics, entity);
+            let swimming_mod = get_swimming_modifier(&map, &aquatic, entity, pos);
             // Every entity has a POTENTIAL equal to their speed.
             let mut energy_potential: i32 = ((energy.speed as f32) *
                 burden_modifier *
                 overmap_mod *
-                intrinsic_speed) as i32;
+                intrinsic_speed *
+                swimming_mod) as i32;
             // Increment current energy by NORMAL_SPEED for every
             // whole number of NORMAL_SPEEDS in their POTENTIAL.
             while energy_potential >= NORMAL_SPEED {
@@ -160,6 +166,17 @@ fn get_overmap_modifier(map: &ReadExpect<Map>) -> f32 {
     return if map.overmap { SPEED_MOD_OVERMAP_TRAVEL } else { 1.0 };
 }
 
+/// Swimming is slow going, for anything that isn't at home in the water.
+fn get_swimming_modifier(
+    map: &ReadExpect<Map>,
+    aquatic: &ReadStorage<Aquatic>,
+    entity: Entity,
+    pos: &Position
+) -> f32 {
+    let deep = tile_deep(map.tiles[map.xy_idx(pos.x, pos.y)]);
+    return if deep && aquatic.get(entity).is_none() { SPEED_MOD_SWIMMING } else { 1.0 };
+}
+
 fn cull_turn_by_distance(player_pos: &Point, pos: &Position) -> bool {
     let distance = DistanceAlg::Pythagoras.distance2d(*player_pos, Point::new(pos.x, pos.y));
     if distance > 20.0 {
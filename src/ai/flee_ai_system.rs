@@ -1,4 +1,18 @@
-use crate::{ EntityMoved, Map, Position, TakingTurn, Telepath, Viewshed, WantsToFlee };
+use crate::{
+    EntityMoved,
+    Faction,
+    Fleeing,
+    Map,
+    Pools,
+    Position,
+    TakingTurn,
+    Telepath,
+    TileType,
+    Viewshed,
+    WantsToFlee,
+    WantsToMelee,
+};
+use super::flow_fields::FlowFields;
 use bracket_lib::prelude::*;
 use specs::prelude::*;
 
@@ -10,11 +24,16 @@ impl<'a> System<'a> for FleeAI {
         WriteStorage<'a, TakingTurn>,
         WriteStorage<'a, WantsToFlee>,
         WriteStorage<'a, Position>,
-        WriteExpect<'a, Map>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, FlowFields>,
         WriteStorage<'a, Viewshed>,
         WriteStorage<'a, Telepath>,
         WriteStorage<'a, EntityMoved>,
         Entities<'a>,
+        ReadStorage<'a, Faction>,
+        ReadStorage<'a, Fleeing>,
+        ReadStorage<'a, Pools>,
+        WriteStorage<'a, WantsToMelee>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -22,12 +41,30 @@ impl<'a> System<'a> for FleeAI {
             mut turns,
             mut wants_to_flee,
             mut positions,
-            mut map,
+            map,
+            mut flow_fields,
             mut viewsheds,
             mut telepaths,
             mut entity_moved,
             entities,
+            factions,
+            fleeing_storage,
+            pools,
+            mut wants_to_melee,
         ) = data;
+        // Somewhere to run to: the stairs, or friends who are still standing their ground.
+        let stairs: Vec<usize> = map.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| matches!(tile, TileType::DownStair | TileType::UpStair))
+            .map(|(idx, _)| idx)
+            .collect();
+        let mut rallying_points: Vec<(String, Point)> = Vec::new();
+        for (entity, faction, pos, _pools) in (&entities, &factions, &positions, &pools).join() {
+            if wants_to_flee.get(entity).is_none() && fleeing_storage.get(entity).is_none() {
+                rallying_points.push((faction.name.clone(), Point::new(pos.x, pos.y)));
+            }
+        }
         let mut turn_done: Vec<Entity> = Vec::new();
         for (entity, _turn, mut pos, fleeing, mut viewshed) in (
             &entities,
@@ -38,27 +75,71 @@ impl<'a> System<'a> for FleeAI {
         ).join() {
             turn_done.push(entity);
             let my_idx = map.xy_idx(pos.x, pos.y);
-            map.populate_blocked();
-            let flee_map = DijkstraMap::new(
-                map.width as usize,
-                map.height as usize,
-                &fleeing.indices,
-                &*map,
-                100.0
-            );
-            let flee_target = DijkstraMap::find_highest_exit(&flee_map, my_idx, &*map);
-            if let Some(flee_target) = flee_target {
-                if !crate::spatial::is_blocked(flee_target as usize) {
-                    crate::spatial::move_entity(entity, my_idx, flee_target);
-                    viewshed.dirty = true;
-                    if let Some(is_telepath) = telepaths.get_mut(entity) {
-                        is_telepath.dirty = true;
+            let mut refuges = stairs.clone();
+            if let Some(faction) = factions.get(entity) {
+                for (name, point) in rallying_points.iter() {
+                    if *name == faction.name && viewshed.visible_tiles.contains(point) {
+                        refuges.push(map.xy_idx(point.x, point.y));
+                    }
+                }
+            }
+            // Never step towards danger. Of the steps that don't, take the one that gets
+            // furthest from it and closest to safety.
+            let exits: Vec<usize> = map
+                .get_available_exits(my_idx)
+                .iter()
+                .map(|(exit, _cost)| *exit)
+                .collect();
+            let flee_map = flow_fields.field(&map, &fleeing.indices);
+            let danger_here = flee_map.map[my_idx];
+            let mut options: Vec<(usize, f32)> = exits
+                .iter()
+                .filter(|exit| flee_map.map[**exit] >= danger_here)
+                .map(|exit| (*exit, flee_map.map[*exit]))
+                .collect();
+            if !refuges.is_empty() {
+                let refuge_map = flow_fields.field(&map, &refuges);
+                for (exit, score) in options.iter_mut() {
+                    if refuge_map.map[*exit] < f32::MAX {
+                        *score -= refuge_map.map[*exit];
+                    }
+                }
+            }
+            let mut flee_target: Option<(usize, f32)> = None;
+            for (exit, score) in options {
+                if flee_target.map_or(true, |(_, best)| score > best) {
+                    flee_target = Some((exit, score));
+                }
+            }
+            if let Some((flee_target, _)) = flee_target {
+                crate::spatial::move_entity(entity, my_idx, flee_target);
+                viewshed.dirty = true;
+                if let Some(is_telepath) = telepaths.get_mut(entity) {
+                    is_telepath.dirty = true;
+                }
+                pos.x = (flee_target as i32) % map.width;
+                pos.y = (flee_target as i32) / map.width;
+                entity_moved.insert(entity, EntityMoved { from: my_idx }).expect("Unable to insert EntityMoved");
+            } else {
+                // Cornered, so turn and fight whatever's closest.
+                let here = Point::new(pos.x, pos.y);
+                for idx in fleeing.indices.iter() {
+                    let there = Point::new((*idx as i32) % map.width, (*idx as i32) / map.width);
+                    if DistanceAlg::Pythagoras.distance2d(here, there) >= 1.5 {
+                        continue;
+                    }
+                    let mut target: Option<Entity> = None;
+                    crate::spatial::for_each_tile_content(*idx, |other| {
+                        if pools.get(other).is_some() {
+                            target = Some(other);
+                        }
+                    });
+                    if let Some(target) = target {
+                        wants_to_melee
+                            .insert(entity, WantsToMelee { target })
+                            .expect("Unable to insert WantsToMelee");
+                        break;
                     }
-                    pos.x = (flee_target as i32) % map.width;
-                    pos.y = (flee_target as i32) / map.width;
-                    entity_moved
-                        .insert(entity, EntityMoved {})
-                        .expect("Unable to insert EntityMoved");
                 }
             }
         }
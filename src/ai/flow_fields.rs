@@ -0,0 +1,197 @@
+use crate::{ tile_cost, tile_passable, BlocksTile, Faction, Map, Pools, Position };
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+use std::collections::{ HashMap, HashSet };
+
+/// How far out a flow field is calculated. Anything further away than this
+/// can't be reached by following it.
+const MAX_DEPTH: f32 = 100.0;
+
+/// Shared Dijkstra maps for monster pathing. Every field flows towards a set of tiles,
+/// and only takes the terrain into account - walls, and anything that blocks a tile
+/// but isn't a mob, like doors or furniture. Mobs move around every turn, so they're
+/// stepped around when following a field, rather than being baked into it. That way,
+/// one field can be shared by everything heading for the same place, and it only has
+/// to be rebuilt when the map or its static blockers change. Each faction also has a
+/// field flowing towards wherever its members are, for anything after a whole side.
+pub struct FlowFields {
+    width: usize,
+    height: usize,
+    blocked: Vec<bool>,
+    fields: HashMap<Vec<usize>, DijkstraMap>,
+    used: HashSet<Vec<usize>>,
+    factions: HashMap<String, Vec<usize>>,
+}
+
+impl FlowFields {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> FlowFields {
+        FlowFields {
+            width: 0,
+            height: 0,
+            blocked: Vec::new(),
+            fields: HashMap::new(),
+            used: HashSet::new(),
+            factions: HashMap::new(),
+        }
+    }
+
+    /// Checks the terrain against the one the cached fields were built for. If anything's
+    /// changed, every field is thrown away. Otherwise, only fields that nobody used since
+    /// the last refresh are - their targets have most likely moved on. Faction members
+    /// are recorded afresh every time, as they move around every turn.
+    pub fn refresh(
+        &mut self,
+        map: &Map,
+        static_blockers: &[usize],
+        factions: HashMap<String, Vec<usize>>
+    ) {
+        let mut blocked: Vec<bool> = map.tiles
+            .iter()
+            .map(|tile| !tile_passable(*tile))
+            .collect();
+        for idx in static_blockers.iter() {
+            if *idx < blocked.len() {
+                blocked[*idx] = true;
+            }
+        }
+        let (width, height) = (map.width as usize, map.height as usize);
+        if width != self.width || height != self.height || blocked != self.blocked {
+            self.width = width;
+            self.height = height;
+            self.blocked = blocked;
+            self.fields.clear();
+        } else {
+            let used = &self.used;
+            self.fields.retain(|targets, _| used.contains(targets));
+        }
+        self.used.clear();
+        self.factions = factions;
+    }
+
+    /// Returns the field flowing towards targets, building it if it isn't cached.
+    pub fn field(&mut self, map: &Map, targets: &[usize]) -> &DijkstraMap {
+        let mut key = targets.to_vec();
+        key.sort_unstable();
+        key.dedup();
+        self.used.insert(key.clone());
+        if !self.fields.contains_key(&key) {
+            let terrain = Terrain { map, blocked: &self.blocked };
+            let field = DijkstraMap::new(self.width, self.height, &key, &terrain, MAX_DEPTH);
+            self.fields.insert(key.clone(), field);
+        }
+        &self.fields[&key]
+    }
+
+    /// The next step from idx towards the nearest of targets, or None if every step that
+    /// would get closer is blocked - or there's no way there at all.
+    pub fn step_towards(&mut self, map: &Map, idx: usize, targets: &[usize]) -> Option<usize> {
+        let exits = map.get_available_exits(idx);
+        let field = self.field(map, targets);
+        let mut best: Option<(usize, f32)> = None;
+        for (exit, _cost) in exits.iter() {
+            let value = field.map[*exit];
+            if value < field.map[idx] && best.map_or(true, |(_, lowest)| value < lowest) {
+                best = Some((*exit, value));
+            }
+        }
+        best.map(|(exit, _)| exit)
+    }
+
+    /// How far idx is from the nearest of targets, following the field.
+    pub fn distance(&mut self, map: &Map, idx: usize, targets: &[usize]) -> f32 {
+        self.field(map, targets).map[idx]
+    }
+
+    /// The next step from idx towards the nearest member of faction, or None if there
+    /// aren't any, or there's no way to get closer to them.
+    pub fn step_towards_faction(&mut self, map: &Map, idx: usize, faction: &str) -> Option<usize> {
+        let targets = self.factions.get(faction)?.clone();
+        self.step_towards(map, idx, &targets)
+    }
+}
+
+/// The map as the flow fields see it: tiles are only blocked by walls and static blockers.
+/// Deep water is open, but it costs enough to swim that it's only crossed to save a long walk.
+struct Terrain<'a> {
+    map: &'a Map,
+    blocked: &'a [bool],
+}
+
+impl<'a> Terrain<'a> {
+    fn is_exit_valid(&self, x: i32, y: i32) -> bool {
+        if x < 1 || x > self.map.width - 1 || y < 1 || y > self.map.height - 1 {
+            return false;
+        }
+        !self.blocked[self.map.xy_idx(x, y)]
+    }
+}
+
+impl<'a> Algorithm2D for Terrain<'a> {
+    fn dimensions(&self) -> Point {
+        Point::new(self.map.width, self.map.height)
+    }
+}
+
+impl<'a> BaseMap for Terrain<'a> {
+    fn get_pathing_distance(&self, idx1: usize, idx2: usize) -> f32 {
+        self.map.get_pathing_distance(idx1, idx2)
+    }
+
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        let mut exits = SmallVec::new();
+        let x = (idx as i32) % self.map.width;
+        let y = (idx as i32) / self.map.width;
+        let w = self.map.width as usize;
+        let cost = tile_cost(self.map.tiles[idx]);
+        for (dx, dy, step) in [
+            (-1, 0, idx.wrapping_sub(1)),
+            (1, 0, idx + 1),
+            (0, -1, idx.wrapping_sub(w)),
+            (0, 1, idx + w),
+        ] {
+            if self.is_exit_valid(x + dx, y + dy) {
+                exits.push((step, cost));
+            }
+        }
+        for (dx, dy, step) in [
+            (-1, -1, idx.wrapping_sub(w + 1)),
+            (1, -1, idx.wrapping_sub(w).wrapping_add(1)),
+            (-1, 1, (idx + w).wrapping_sub(1)),
+            (1, 1, idx + w + 1),
+        ] {
+            if self.is_exit_valid(x + dx, y + dy) {
+                exits.push((step, cost * 1.45));
+            }
+        }
+        exits
+    }
+}
+
+/// Keeps the shared flow fields in step with the map. Runs ahead of the AI systems
+/// that read from them.
+pub struct FlowFieldSystem {}
+
+impl<'a> System<'a> for FlowFieldSystem {
+    type SystemData = (
+        WriteExpect<'a, FlowFields>,
+        ReadExpect<'a, Map>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, BlocksTile>,
+        ReadStorage<'a, Pools>,
+        ReadStorage<'a, Faction>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut flow_fields, map, positions, blockers, pools, factions) = data;
+        let static_blockers: Vec<usize> = (&positions, &blockers, !&pools)
+            .join()
+            .map(|(pos, _, _)| map.xy_idx(pos.x, pos.y))
+            .collect();
+        let mut members: HashMap<String, Vec<usize>> = HashMap::new();
+        for (pos, faction, _pools) in (&positions, &factions, &pools).join() {
+            members.entry(faction.name.clone()).or_default().push(map.xy_idx(pos.x, pos.y));
+        }
+        flow_fields.refresh(&map, &static_blockers, members);
+    }
+}
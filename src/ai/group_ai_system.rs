@@ -0,0 +1,277 @@
+use crate::{
+    raws::{ self, Reaction },
+    Ally,
+    Chasing,
+    Faction,
+    Fleeing,
+    GroupMember,
+    Leader,
+    Map,
+    Mind,
+    Name,
+    Player,
+    Pools,
+    Position,
+    Renderable,
+    TakingTurn,
+    WantsToApproach,
+};
+use super::morale_system::log_morale;
+use crate::data::entity::*;
+use crate::data::messages::*;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+use std::collections::{ HashMap, HashSet };
+
+/// Coordinates groups. Until a group has gathered around its leader, nobody charges in -
+/// the leader waits, and everyone else falls in. Once it has, they all go after the
+/// same target, and spread out to surround it rather than queuing up behind each other.
+pub struct GroupAI {}
+
+impl<'a> System<'a> for GroupAI {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, GroupMember>,
+        WriteStorage<'a, Leader>,
+        WriteStorage<'a, TakingTurn>,
+        WriteStorage<'a, WantsToApproach>,
+        WriteStorage<'a, Chasing>,
+        ReadStorage<'a, Fleeing>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Renderable>,
+        ReadExpect<'a, Map>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            members,
+            mut leaders,
+            mut turns,
+            mut wants_to_approach,
+            mut chasing,
+            fleeing,
+            positions,
+            names,
+            renderables,
+            map,
+        ) = data;
+
+        let mut groups: HashMap<Entity, Vec<Entity>> = HashMap::new();
+        for (entity, member) in (&entities, &members).join() {
+            if entities.is_alive(member.leader) && leaders.get(member.leader).is_some() {
+                groups.entry(member.leader).or_insert_with(Vec::new).push(entity);
+            }
+        }
+        for (leader_entity, group) in groups.iter() {
+            let leader_pos = if let Some(pos) = positions.get(*leader_entity) {
+                Point::new(pos.x, pos.y)
+            } else {
+                continue;
+            };
+            // The group's after whatever its leader is, or failing that, whatever one of
+            // them has spotted. If nobody's after anything, they stand down.
+            let target = chasing
+                .get(*leader_entity)
+                .map(|c| c.target)
+                .or_else(|| group.iter().find_map(|m| chasing.get(*m).map(|c| c.target)));
+            let leader = leaders.get_mut(*leader_entity).unwrap();
+            let target = if let Some(target) = target {
+                target
+            } else {
+                leader.engaged = false;
+                continue;
+            };
+            if !leader.engaged {
+                let gathered = group
+                    .iter()
+                    .filter(|m| {
+                        positions.get(**m).map_or(false, |pos| {
+                            let distance = DistanceAlg::Pythagoras.distance2d(
+                                leader_pos,
+                                Point::new(pos.x, pos.y)
+                            );
+                            distance <= GROUP_GATHER_RADIUS
+                        })
+                    })
+                    .count();
+                if gathered * 2 >= group.len() {
+                    leader.engaged = true;
+                    log_morale(
+                        &map,
+                        &positions,
+                        &names,
+                        &renderables,
+                        *leader_entity,
+                        GROUP_ENGAGED
+                    );
+                }
+            }
+            if !leader.engaged {
+                // Hold back: the leader waits where they are, and the rest fall in.
+                if turns.get(*leader_entity).is_some() {
+                    wants_to_approach.remove(*leader_entity);
+                    turns.remove(*leader_entity);
+                }
+                let leader_idx = map.xy_idx(leader_pos.x, leader_pos.y) as i32;
+                for member in group.iter() {
+                    if turns.get(*member).is_some() && fleeing.get(*member).is_none() {
+                        wants_to_approach
+                            .insert(*member, WantsToApproach { idx: leader_idx })
+                            .expect("Unable to insert WantsToApproach");
+                    }
+                }
+                continue;
+            }
+            let target_pos = if let Some(pos) = positions.get(target) {
+                Point::new(pos.x, pos.y)
+            } else {
+                continue;
+            };
+            // Everyone's after the same thing now, and they each take a different side.
+            let mut flanks: Vec<usize> = Vec::new();
+            for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                let (x, y) = (target_pos.x + dx, target_pos.y + dy);
+                if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 {
+                    continue;
+                }
+                let idx = map.xy_idx(x, y);
+                if !crate::spatial::is_blocked(idx) {
+                    flanks.push(idx);
+                }
+            }
+            let mut claimed: HashSet<usize> = HashSet::new();
+            for entity in std::iter::once(leader_entity).chain(group.iter()) {
+                if fleeing.get(*entity).is_some() {
+                    continue;
+                }
+                if chasing.get(*entity).is_none() {
+                    chasing.insert(*entity, Chasing { target }).expect("Unable to insert Chasing");
+                }
+                let pos = if let Some(pos) = positions.get(*entity) {
+                    Point::new(pos.x, pos.y)
+                } else {
+                    continue;
+                };
+                if
+                    turns.get(*entity).is_none() ||
+                    DistanceAlg::Pythagoras.distance2d(pos, target_pos) < 1.5
+                {
+                    continue;
+                }
+                let flank = flanks
+                    .iter()
+                    .filter(|idx| !claimed.contains(*idx))
+                    .min_by_key(|idx| {
+                        let flank_pos = Point::new(
+                            (**idx as i32) % map.width,
+                            (**idx as i32) / map.width
+                        );
+                        DistanceAlg::PythagorasSquared.distance2d(pos, flank_pos) as i32
+                    });
+                if let Some(flank) = flank {
+                    claimed.insert(*flank);
+                    wants_to_approach
+                        .insert(*entity, WantsToApproach { idx: *flank as i32 })
+                        .expect("Unable to insert WantsToApproach");
+                }
+            }
+        }
+    }
+}
+
+/// Sorts any newly spawned hostile mobs into groups. Leaders take charge of anyone of their
+/// faction nearby, and mobs that spawned alongside others of their kind form a pack around
+/// whichever of them is strongest.
+pub fn form_groups(ecs: &mut World) {
+    let (new_leaders, new_members) = {
+        let raws = &raws::RAWS.lock().unwrap();
+        let entities = ecs.entities();
+        let factions = ecs.read_storage::<Faction>();
+        let positions = ecs.read_storage::<Position>();
+        let names = ecs.read_storage::<Name>();
+        let pools = ecs.read_storage::<Pools>();
+        let minds = ecs.read_storage::<Mind>();
+        let leaders = ecs.read_storage::<Leader>();
+        let members = ecs.read_storage::<GroupMember>();
+        let allies = ecs.read_storage::<Ally>();
+        let players = ecs.read_storage::<Player>();
+
+        let mut candidates: Vec<(Entity, String, String, Point, i32)> = Vec::new();
+        for (entity, faction, pos, name, pool, _mind, _m, _a, _p) in (
+            &entities,
+            &factions,
+            &positions,
+            &names,
+            &pools,
+            &minds,
+            !&members,
+            !&allies,
+            !&players,
+        ).join() {
+            if raws::faction_reaction(&faction.name, "player", raws) == Reaction::Attack {
+                candidates.push((
+                    entity,
+                    faction.name.clone(),
+                    name.name.clone(),
+                    Point::new(pos.x, pos.y),
+                    pool.level,
+                ));
+            }
+        }
+        let near = |a: Point, b: Point| {
+            DistanceAlg::Pythagoras.distance2d(a, b) <= GROUP_FORM_RADIUS
+        };
+        let mut assigned: HashSet<Entity> = HashSet::new();
+        let mut new_members: Vec<(Entity, Entity)> = Vec::new();
+        let mut new_leaders: Vec<Entity> = Vec::new();
+        for (leader, faction, _, leader_pos, _) in candidates.iter() {
+            if leaders.get(*leader).is_none() {
+                continue;
+            }
+            assigned.insert(*leader);
+            for (other, other_faction, _, other_pos, _) in candidates.iter() {
+                if
+                    !assigned.contains(other) &&
+                    leaders.get(*other).is_none() &&
+                    other_faction == faction &&
+                    near(*leader_pos, *other_pos)
+                {
+                    assigned.insert(*other);
+                    new_members.push((*other, *leader));
+                }
+            }
+        }
+        for (entity, _, name, pos, _) in candidates.iter() {
+            if assigned.contains(entity) {
+                continue;
+            }
+            let pack: Vec<&(Entity, String, String, Point, i32)> = candidates
+                .iter()
+                .filter(|c| !assigned.contains(&c.0) && c.2 == *name && near(*pos, c.3))
+                .collect();
+            if pack.len() < 2 {
+                continue;
+            }
+            let leader = pack.iter().max_by_key(|c| c.4).unwrap().0;
+            new_leaders.push(leader);
+            for member in pack.iter() {
+                assigned.insert(member.0);
+                if member.0 != leader {
+                    new_members.push((member.0, leader));
+                }
+            }
+        }
+        (new_leaders, new_members)
+    };
+    let mut leaders = ecs.write_storage::<Leader>();
+    for leader in new_leaders {
+        leaders.insert(leader, Leader { engaged: false }).expect("Unable to insert Leader");
+    }
+    let mut members = ecs.write_storage::<GroupMember>();
+    for (member, leader) in new_members {
+        members.insert(member, GroupMember { leader }).expect("Unable to insert GroupMember");
+    }
+}
@@ -2,20 +2,36 @@ mod energy_system;
 pub use energy_system::EnergySystem;
 mod turn_status_system;
 pub use turn_status_system::TurnStatusSystem;
+mod eating_system;
+pub use eating_system::EatingSystem;
+mod swimming_system;
+pub use swimming_system::SwimmingSystem;
 mod quip_system;
 pub use quip_system::QuipSystem;
 mod regen_system;
 pub use regen_system::RegenSystem;
 mod encumbrance_system;
 pub use encumbrance_system::EncumbranceSystem;
+mod flow_fields;
+pub use flow_fields::{ FlowFieldSystem, FlowFields };
+mod morale_system;
+pub use morale_system::{ rout_followers, MoraleSystem };
+mod ability_ai_system;
+pub use ability_ai_system::AbilityAI;
 mod adjacent_ai_system;
 pub use adjacent_ai_system::AdjacentAI;
 mod visible_ai_system;
 pub use visible_ai_system::VisibleAI;
+mod group_ai_system;
+pub use group_ai_system::{ form_groups, GroupAI };
 mod approach_ai_system;
 pub use approach_ai_system::ApproachAI;
 mod chase_ai_system;
 pub use chase_ai_system::ChaseAI;
+mod ally_ai_system;
+pub use ally_ai_system::{ is_trapped, AllyAI };
+mod search_ai_system;
+pub use search_ai_system::{ hear_fighting, SearchAI };
 mod flee_ai_system;
 pub use flee_ai_system::FleeAI;
 mod default_move_system;
@@ -0,0 +1,226 @@
+use crate::{
+    gamelog,
+    gui::renderable_colour,
+    raws::Reaction,
+    Ally,
+    Chasing,
+    Faction,
+    Fleeing,
+    GroupMember,
+    HasAncestry,
+    Leader,
+    Map,
+    Mind,
+    Name,
+    Pools,
+    Position,
+    Renderable,
+    TakingTurn,
+    Viewshed,
+};
+use crate::data::entity::*;
+use crate::data::messages::*;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Decides who loses their nerve - mobs with a mind flee when they're badly hurt or
+/// outnumbered, and rally once they've been healed up and the odds are better.
+pub struct MoraleSystem {}
+
+impl<'a> System<'a> for MoraleSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, TakingTurn>,
+        ReadStorage<'a, Mind>,
+        ReadStorage<'a, Pools>,
+        ReadStorage<'a, Viewshed>,
+        ReadStorage<'a, Faction>,
+        ReadStorage<'a, HasAncestry>,
+        ReadStorage<'a, Ally>,
+        WriteStorage<'a, Fleeing>,
+        WriteStorage<'a, Chasing>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Renderable>,
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, Entity>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            turns,
+            minds,
+            pools,
+            viewsheds,
+            factions,
+            ancestries,
+            allies,
+            mut fleeing,
+            mut chasing,
+            positions,
+            names,
+            renderables,
+            map,
+            player,
+        ) = data;
+
+        let mut routed: Vec<Entity> = Vec::new();
+        let mut rallied: Vec<Entity> = Vec::new();
+        for (entity, _turn, _mind, pool, viewshed, faction) in (
+            &entities,
+            &turns,
+            &minds,
+            &pools,
+            &viewsheds,
+            &factions,
+        ).join() {
+            // Allies stick to their orders.
+            if entity == *player || allies.get(entity).is_some() {
+                continue;
+            }
+            let (mut hostiles, mut friends) = (0, 0);
+            {
+                let raws = &crate::raws::RAWS.lock().unwrap();
+                for tile in viewshed.visible_tiles.iter() {
+                    crate::spatial::for_each_tile_content(map.xy_idx(tile.x, tile.y), |other| {
+                        if other == entity || pools.get(other).is_none() {
+                            return;
+                        }
+                        let reaction = crate::raws::get_reactions(
+                            entity,
+                            other,
+                            &factions,
+                            &ancestries,
+                            raws
+                        );
+                        if reaction == Reaction::Attack {
+                            hostiles += 1;
+                        } else if
+                            factions.get(other).map_or(false, |f| f.name == faction.name) &&
+                            fleeing.get(other).is_none()
+                        {
+                            friends += 1;
+                        }
+                    });
+                }
+            }
+            let hp = &pool.hit_points;
+            let hurt = hp.current * 100 <= hp.max * MORALE_FLEE_HP_PERCENT;
+            let healed = hp.current * 100 >= hp.max * MORALE_RALLY_HP_PERCENT;
+            let outnumbered = hostiles > 0 && hostiles >= (friends + 1) * MORALE_OUTNUMBERED_RATIO;
+            if let Some(fleeing) = fleeing.get_mut(entity) {
+                if fleeing.turns > 0 {
+                    fleeing.turns -= 1;
+                } else if healed && !outnumbered {
+                    rallied.push(entity);
+                }
+            } else if hostiles > 0 && (hurt || outnumbered) {
+                routed.push(entity);
+            }
+        }
+
+        for entity in rallied {
+            fleeing.remove(entity);
+            log_morale(&map, &positions, &names, &renderables, entity, MORALE_RALLY);
+        }
+        for entity in routed {
+            fleeing.insert(entity, Fleeing { turns: 0 }).expect("Unable to insert Fleeing");
+            chasing.remove(entity);
+            log_morale(&map, &positions, &names, &renderables, entity, MORALE_FLEE);
+        }
+    }
+}
+
+/// If a leader just died, everyone of its faction who saw it happen loses their nerve,
+/// and the group it led scatters.
+pub fn rout_followers(ecs: &World, leader: Entity) {
+    if ecs.read_storage::<Leader>().get(leader).is_none() {
+        return;
+    }
+    let (faction, leader_pos) = {
+        let factions = ecs.read_storage::<Faction>();
+        let positions = ecs.read_storage::<Position>();
+        match (factions.get(leader), positions.get(leader)) {
+            (Some(faction), Some(pos)) => (faction.name.clone(), Point::new(pos.x, pos.y)),
+            _ => {
+                return;
+            }
+        }
+    };
+    let player_entity = *ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let factions = ecs.read_storage::<Faction>();
+    let viewsheds = ecs.read_storage::<Viewshed>();
+    let minds = ecs.read_storage::<Mind>();
+    let allies = ecs.read_storage::<Ally>();
+    let mut members = ecs.write_storage::<GroupMember>();
+    let mut routed: Vec<Entity> = Vec::new();
+    for (entity, member) in (&entities, &members).join() {
+        if member.leader == leader {
+            routed.push(entity);
+        }
+    }
+    for entity in routed.iter() {
+        members.remove(*entity);
+    }
+    for (entity, their_faction, viewshed, _mind) in (
+        &entities,
+        &factions,
+        &viewsheds,
+        &minds,
+    ).join() {
+        if
+            entity == leader ||
+            entity == player_entity ||
+            allies.get(entity).is_some() ||
+            their_faction.name != faction
+        {
+            continue;
+        }
+        if viewshed.visible_tiles.contains(&leader_pos) && !routed.contains(&entity) {
+            routed.push(entity);
+        }
+    }
+    let map = ecs.fetch::<Map>();
+    let positions = ecs.read_storage::<Position>();
+    let names = ecs.read_storage::<Name>();
+    let renderables = ecs.read_storage::<Renderable>();
+    let mut fleeing = ecs.write_storage::<Fleeing>();
+    let mut chasing = ecs.write_storage::<Chasing>();
+    for entity in routed {
+        let already_fleeing = fleeing.get(entity).is_some();
+        fleeing
+            .insert(entity, Fleeing { turns: MORALE_LEADER_ROUT_TURNS })
+            .expect("Unable to insert Fleeing");
+        chasing.remove(entity);
+        if !already_fleeing {
+            log_morale(&map, &positions, &names, &renderables, entity, MORALE_FLEE);
+        }
+    }
+}
+
+/// Lets the player know about a change of heart, if they can see it happen.
+pub(super) fn log_morale(
+    map: &Map,
+    positions: &ReadStorage<Position>,
+    names: &ReadStorage<Name>,
+    renderables: &ReadStorage<Renderable>,
+    entity: Entity,
+    message: &str
+) {
+    if let (Some(pos), Some(name)) = (positions.get(entity), names.get(entity)) {
+        if map.visible_tiles[map.xy_idx(pos.x, pos.y)] {
+            gamelog::Logger
+                ::new()
+                .append("The")
+                .colour(renderable_colour(renderables, entity))
+                .append(&name.name)
+                .colour(WHITE)
+                .append_n(message)
+                .period()
+                .log();
+        }
+    }
+}
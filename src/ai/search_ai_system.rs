@@ -0,0 +1,170 @@
+use crate::{
+    raws::{ self, Reaction },
+    Ally,
+    Chasing,
+    EntityMoved,
+    Faction,
+    HasAncestry,
+    LastSeen,
+    Map,
+    Mind,
+    Position,
+    TakingTurn,
+    Telepath,
+    Viewshed,
+};
+use super::flow_fields::FlowFields;
+use crate::data::entity::*;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Mobs that have lost track of their quarry head for wherever they last knew it to be,
+/// and then wander around there looking for it. If they don't find it, they give up.
+pub struct SearchAI {}
+
+impl<'a> System<'a> for SearchAI {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'a, TakingTurn>,
+        WriteStorage<'a, LastSeen>,
+        ReadStorage<'a, Chasing>,
+        ReadStorage<'a, Ally>,
+        WriteStorage<'a, Position>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, FlowFields>,
+        WriteStorage<'a, Viewshed>,
+        WriteStorage<'a, Telepath>,
+        WriteStorage<'a, EntityMoved>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut turns,
+            mut last_seen,
+            chasing,
+            allies,
+            mut positions,
+            map,
+            mut flow_fields,
+            mut viewsheds,
+            mut telepaths,
+            mut entity_moved,
+            mut rng,
+            entities,
+        ) = data;
+        let mut turn_done: Vec<Entity> = Vec::new();
+        let mut gave_up: Vec<Entity> = Vec::new();
+        for (entity, _turn, mut pos, memory, mut viewshed, _c, _a) in (
+            &entities,
+            &turns,
+            &mut positions,
+            &mut last_seen,
+            &mut viewsheds,
+            !&chasing,
+            !&allies,
+        ).join() {
+            if memory.turns <= 0 {
+                gave_up.push(entity);
+                continue;
+            }
+            turn_done.push(entity);
+            let idx = map.xy_idx(pos.x, pos.y);
+            let step = if !memory.searching {
+                // Head for where they were. If we can't get any closer, start looking here.
+                let step = flow_fields.step_towards(&map, idx, &[memory.idx]);
+                if step.is_none() || step == Some(memory.idx) {
+                    memory.searching = true;
+                }
+                step
+            } else {
+                // Wander around, but don't stray too far from where they were last seen.
+                memory.turns -= 1;
+                let last_pos = Point::new(
+                    (memory.idx as i32) % map.width,
+                    (memory.idx as i32) / map.width
+                );
+                let exits: Vec<usize> = map
+                    .get_available_exits(idx)
+                    .iter()
+                    .map(|(exit, _cost)| *exit)
+                    .filter(|exit| {
+                        let exit_pos = Point::new(
+                            (*exit as i32) % map.width,
+                            (*exit as i32) / map.width
+                        );
+                        DistanceAlg::Pythagoras.distance2d(exit_pos, last_pos) <=
+                            MEMORY_SEARCH_RADIUS
+                    })
+                    .collect();
+                if exits.is_empty() {
+                    None
+                } else {
+                    Some(exits[(rng.roll_dice(1, exits.len() as i32) - 1) as usize])
+                }
+            };
+            if let Some(step) = step {
+                pos.x = (step as i32) % map.width;
+                pos.y = (step as i32) / map.width;
+                entity_moved.insert(entity, EntityMoved { from: idx }).expect("Unable to insert EntityMoved");
+                crate::spatial::move_entity(entity, idx, step);
+                viewshed.dirty = true;
+                if let Some(is_telepath) = telepaths.get_mut(entity) {
+                    is_telepath.dirty = true;
+                }
+            }
+        }
+        for entity in gave_up.iter() {
+            last_seen.remove(*entity);
+        }
+        for done in turn_done.iter() {
+            turns.remove(*done);
+        }
+    }
+}
+
+/// A fight makes a racket. Anything nearby that isn't already after someone, and would
+/// attack either side of it, comes to see what's going on.
+pub fn hear_fighting(ecs: &World, source: Entity, target: Entity) {
+    let map = ecs.fetch::<Map>();
+    let noise_pos = if let Some(pos) = ecs.read_storage::<Position>().get(target) {
+        Point::new(pos.x, pos.y)
+    } else {
+        return;
+    };
+    let idx = map.xy_idx(noise_pos.x, noise_pos.y);
+    let entities = ecs.entities();
+    let positions = ecs.read_storage::<Position>();
+    let minds = ecs.read_storage::<Mind>();
+    let chasing = ecs.read_storage::<Chasing>();
+    let allies = ecs.read_storage::<Ally>();
+    let factions = ecs.read_storage::<Faction>();
+    let ancestries = ecs.read_storage::<HasAncestry>();
+    let mut last_seen = ecs.write_storage::<LastSeen>();
+    let player = *ecs.fetch::<Entity>();
+    let raws = &raws::RAWS.lock().unwrap();
+    for (entity, pos, _mind, _c, _a) in (
+        &entities,
+        &positions,
+        &minds,
+        !&chasing,
+        !&allies,
+    ).join() {
+        if entity == player || entity == source || entity == target {
+            continue;
+        }
+        let distance = DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), noise_pos);
+        if distance > NOISE_COMBAT_RADIUS {
+            continue;
+        }
+        let interested = [source, target].iter().any(|other| {
+            raws::get_reactions(entity, *other, &factions, &ancestries, raws) == Reaction::Attack
+        });
+        if interested {
+            last_seen
+                .insert(entity, LastSeen { idx, turns: MEMORY_SEARCH_TURNS, searching: false })
+                .expect("Unable to insert LastSeen");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    effects::{ add_effect, EffectType, Targets },
+    gamelog,
+    tile_deep,
+    Aquatic,
+    Burden,
+    BurdenLevel,
+    DamageType,
+    InBackpack,
+    Map,
+    Position,
+    TakingTurn,
+    WantsToDropItem,
+    WantsToRemoveKey,
+};
+use crate::data::entity::*;
+use crate::data::messages::SWIM_GO_UNDER;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Anyone swimming in deep water with too much on them risks going under. Going under
+/// costs them their turn and some health, and something they're carrying slips away
+/// into the water. Aquatic mobs, and anyone travelling light, swim without any trouble.
+pub struct SwimmingSystem {}
+
+impl<'a> System<'a> for SwimmingSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, Map>,
+        WriteStorage<'a, TakingTurn>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Burden>,
+        ReadStorage<'a, Aquatic>,
+        ReadStorage<'a, InBackpack>,
+        WriteStorage<'a, WantsToDropItem>,
+        WriteStorage<'a, WantsToRemoveKey>,
+        WriteExpect<'a, RandomNumberGenerator>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            map,
+            mut turns,
+            positions,
+            burdens,
+            aquatic,
+            backpack,
+            mut wants_drop,
+            mut wants_remove_key,
+            mut rng,
+        ) = data;
+        let mut went_under: Vec<Entity> = Vec::new();
+        for (entity, _t, pos, burden, _a) in (
+            &entities,
+            &turns,
+            &positions,
+            &burdens,
+            !&aquatic,
+        ).join() {
+            if !tile_deep(map.tiles[map.xy_idx(pos.x, pos.y)]) {
+                continue;
+            }
+            let one_in = match burden.level {
+                BurdenLevel::Burdened => SWIM_BURDENED_ONE_IN,
+                BurdenLevel::Strained => SWIM_STRAINED_ONE_IN,
+                BurdenLevel::Overloaded => SWIM_OVERLOADED_ONE_IN,
+            };
+            if rng.roll_dice(1, one_in) == 1 {
+                went_under.push(entity);
+            }
+        }
+        for entity in went_under {
+            turns.remove(entity);
+            add_effect(
+                None,
+                EffectType::Damage {
+                    amount: rng.roll_dice(1, DROWNING_DAMAGE_DIE),
+                    damage_type: DamageType::Forced,
+                },
+                Targets::Entity { target: entity }
+            );
+            if entity == *player_entity {
+                gamelog::Logger::new().append(SWIM_GO_UNDER).log();
+            }
+            // Let go of something to stay afloat. It sinks where it was dropped.
+            let carried: Vec<Entity> = (&entities, &backpack)
+                .join()
+                .filter(|(_, pack)| pack.owner == entity)
+                .map(|(item, _)| item)
+                .collect();
+            if carried.is_empty() || wants_drop.get(entity).is_some() {
+                continue;
+            }
+            let item = carried[(rng.roll_dice(1, carried.len() as i32) - 1) as usize];
+            wants_remove_key.insert(item, WantsToRemoveKey {}).expect("Unable to insert WantsToRemoveKey");
+            wants_drop.insert(entity, WantsToDropItem { item }).expect("Unable to insert WantsToDropItem");
+        }
+    }
+}
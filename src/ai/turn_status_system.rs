@@ -4,6 +4,7 @@ use crate::{
     gui::renderable_colour,
     Clock,
     Confusion,
+    Fainted,
     Name,
     Renderable,
     TakingTurn,
@@ -28,6 +29,7 @@ impl<'a> System<'a> for TurnStatusSystem {
         ReadStorage<'a, Renderable>,
         ReadStorage<'a, Item>,
         ReadStorage<'a, Prop>,
+        WriteStorage<'a, Fainted>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -41,6 +43,7 @@ impl<'a> System<'a> for TurnStatusSystem {
             renderables,
             items,
             props,
+            mut fainted,
         ) = data;
         let mut clock_tick = false;
         for (_e, _c, _t) in (&entities, &clock, &turns).join() {
@@ -118,6 +121,29 @@ impl<'a> System<'a> for TurnStatusSystem {
                 );
             }
         }
+        let mut not_fainted: Vec<Entity> = Vec::new();
+        for (entity, faint, name) in (&entities, &mut fainted, &names).join() {
+            faint.turns -= 1;
+            if faint.turns < 1 {
+                not_fainted.push(entity);
+                if entity == *player_entity {
+                    logger = logger
+                        .append("You")
+                        .append("regain consciousness.");
+                    log = true;
+                } else {
+                    logger = logger
+                        .append("The")
+                        .colour(renderable_colour(&renderables, entity))
+                        .append(&name.name)
+                        .colour(WHITE)
+                        .append("regains consciousness.");
+                    log = true;
+                }
+            } else {
+                not_my_turn.push(entity);
+            }
+        }
         if log {
             logger.log();
         }
@@ -127,5 +153,8 @@ impl<'a> System<'a> for TurnStatusSystem {
         for e in not_confused {
             confusion.remove(e);
         }
+        for e in not_fainted {
+            fainted.remove(e);
+        }
     }
 }
@@ -1,8 +1,12 @@
 use crate::{
     raws::Reaction,
+    Ally,
+    AllyCommand,
     Chasing,
     Faction,
+    Fleeing,
     HasAncestry,
+    LastSeen,
     Map,
     Mind,
     Position,
@@ -12,6 +16,7 @@ use crate::{
     WantsToApproach,
     WantsToFlee,
 };
+use crate::data::entity::MEMORY_SEARCH_TURNS;
 use bracket_lib::prelude::*;
 use specs::prelude::*;
 use std::collections::HashSet;
@@ -34,6 +39,9 @@ impl<'a> System<'a> for VisibleAI {
         ReadStorage<'a, Telepath>,
         ReadStorage<'a, Mind>,
         WriteStorage<'a, Chasing>,
+        ReadStorage<'a, Ally>,
+        ReadStorage<'a, Fleeing>,
+        WriteStorage<'a, LastSeen>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -51,12 +59,21 @@ impl<'a> System<'a> for VisibleAI {
             telepaths,
             minds,
             mut chasing,
+            allies,
+            fleeing,
+            mut last_seen,
         ) = data;
 
         for (entity, _turn, pos, viewshed) in (&entities, &turns, &positions, &viewsheds).join() {
             if entity == *player {
                 continue;
             }
+            // Allies who've been told to stay, or to attack something, stick to their orders.
+            if let Some(ally) = allies.get(entity) {
+                if ally.command != AllyCommand::Follow {
+                    continue;
+                }
+            }
             let this_idx = map.xy_idx(pos.x, pos.y);
             let mut reactions: Vec<(usize, Reaction, Entity)> = Vec::new();
             let mut flee: Vec<usize> = Vec::new();
@@ -95,8 +112,15 @@ impl<'a> System<'a> for VisibleAI {
                 return dist_b_estimate.cmp(&dist_a_estimate);
             });
             let mut found_flee = false;
+            let routed = fleeing.get(entity).is_some();
             for reaction in reactions.iter() {
-                match reaction.1 {
+                // Mobs that have lost their nerve run from anything they'd otherwise fight.
+                let response = if routed && reaction.1 == Reaction::Attack {
+                    Reaction::Flee
+                } else {
+                    reaction.1
+                };
+                match response {
                     Reaction::Attack => {
                         if !found_flee {
                             wants_to_approach
@@ -105,6 +129,13 @@ impl<'a> System<'a> for VisibleAI {
                             chasing
                                 .insert(entity, Chasing { target: reaction.2 })
                                 .expect("Unable to insert Chasing");
+                            last_seen
+                                .insert(entity, LastSeen {
+                                    idx: reaction.0,
+                                    turns: MEMORY_SEARCH_TURNS,
+                                    searching: false,
+                                })
+                                .expect("Unable to insert LastSeen");
                             continue;
                         }
                     }
@@ -1,5 +1,6 @@
 use crate::gui::Ancestry;
 use crate::gui::Class;
+use crate::data::entity::{ CORPSE_OLD_AGE, CORPSE_ROTTEN_AGE };
 use bracket_lib::prelude::*;
 use serde::{ Deserialize, Serialize };
 use specs::error::NoError;
@@ -23,6 +24,8 @@ pub struct DMSerializationHelper {
     pub log: Vec<Vec<crate::gamelog::LogFragment>>,
     pub event_counts: HashMap<String, i32>,
     pub events: HashMap<u32, Vec<String>>,
+    pub quests: crate::quests::QuestLog,
+    pub reputation: HashMap<String, i32>,
 }
 
 #[derive(Component, ConvertSaveload, Clone)]
@@ -59,12 +62,44 @@ pub struct Faction {
     pub name: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
+pub enum AllyCommand {
+    Follow,
+    Stay,
+    Attack, // The target is held in the ally's Chasing component.
+}
+
+impl AllyCommand {
+    pub fn string(&self) -> &str {
+        match self {
+            AllyCommand::Follow => "following",
+            AllyCommand::Stay => "staying",
+            AllyCommand::Attack => "attacking",
+        }
+    }
+}
+
+/// A pet, tamed animal, or hireling fighting on someone's side.
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Ally {
+    pub owner: Entity,
+    pub command: AllyCommand,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Tameable {}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Hireling {
+    pub cost: i32,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub enum Movement {
     Static,
     Random,
     RandomWaypoint {
-        path: Option<Vec<usize>>,
+        target: Option<usize>,
     },
 }
 
@@ -99,6 +134,12 @@ pub struct Quips {
     pub available: Vec<String>,
 }
 
+/// The id of the dialogue tree (in dialogue.json) this entity uses when talked to.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Dialogue {
+    pub tree: String,
+}
+
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct Mind {}
 
@@ -150,7 +191,61 @@ pub struct HungerClock {
 }
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
-pub struct ProvidesNutrition {}
+pub struct ProvidesNutrition {
+    pub amount: i32,
+    pub turns: i32, // How many turns it takes to eat.
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Eating {
+    pub food: Entity,
+    pub turns: i32,
+}
+
+/// Attached to mobs which leave behind a corpse when they die.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct LeavesCorpse {
+    pub weight: f32,
+    pub poisonous: bool,
+    pub tainted: bool, // Spawns already rotten, e.g. zombies.
+    pub intrinsic: Option<Intrinsic>,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum RotState {
+    Fresh,
+    Old,
+    Rotten,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Corpse {
+    pub mob: String,
+    pub age: i32,
+    pub poisonous: bool,
+    pub intrinsic: Option<Intrinsic>,
+}
+
+impl Corpse {
+    pub fn rot_state(&self) -> RotState {
+        if self.age >= CORPSE_ROTTEN_AGE {
+            RotState::Rotten
+        } else if self.age >= CORPSE_OLD_AGE {
+            RotState::Old
+        } else {
+            RotState::Fresh
+        }
+    }
+    /// Returns the name and plural of the corpse, given its current state of rot.
+    pub fn name(&self) -> (String, String) {
+        let prefix = match self.rot_state() {
+            RotState::Fresh => "",
+            RotState::Old => "old ",
+            RotState::Rotten => "rotten ",
+        };
+        (format!("{}{} corpse", prefix, self.mob), format!("{}{} corpses", prefix, self.mob))
+    }
+}
 
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct HasAncestry {
@@ -162,6 +257,61 @@ pub struct HasClass {
     pub name: Class,
 }
 
+/// Class-specific bonuses, one of which is picked every level-up.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+pub enum Perk {
+    Toughness,
+    WeaponTraining,
+    Guarded,
+    FleetFooted,
+    ArcaneReserves,
+    SpellFocus,
+}
+
+impl Perk {
+    pub fn string(&self) -> &str {
+        match self {
+            Perk::Toughness => "toughness",
+            Perk::WeaponTraining => "weapon training",
+            Perk::Guarded => "guarded",
+            Perk::FleetFooted => "fleet-footed",
+            Perk::ArcaneReserves => "arcane reserves",
+            Perk::SpellFocus => "spell focus",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            Perk::Toughness => "more maximum hit points",
+            Perk::WeaponTraining => "+1 to melee skill",
+            Perk::Guarded => "+1 to defence skill",
+            Perk::FleetFooted => "a permanent increase in speed",
+            Perk::ArcaneReserves => "more maximum mana",
+            Perk::SpellFocus => "+1 to magic skill",
+        }
+    }
+
+    /// Perks which can be picked more than once.
+    pub fn repeatable(&self) -> bool {
+        !matches!(self, Perk::FleetFooted)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LevelUpRecord {
+    pub level: i32,
+    pub perk: Perk,
+    pub attribute: Option<String>,
+}
+
+/// Every choice made on levelling up, for the morgue file.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct LevelHistory {
+    pub level_ups: Vec<LevelUpRecord>,
+    /// Levels gained that haven't had their choices made yet.
+    pub pending: i32,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Pool {
     pub max: i32,
@@ -186,16 +336,61 @@ pub struct Attribute {
     pub bonus: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Skill {
     Melee,
     Defence,
     Magic,
+    ShortBlade,
+    LongBlade,
+    Axe,
+    Polearm,
+    Shield,
+    Unarmed,
+}
+
+impl Skill {
+    /// Every weapon skill, in the order they're displayed.
+    pub const WEAPON_SKILLS: [Skill; 6] = [
+        Skill::ShortBlade,
+        Skill::LongBlade,
+        Skill::Axe,
+        Skill::Polearm,
+        Skill::Shield,
+        Skill::Unarmed,
+    ];
+
+    pub fn string(&self) -> &str {
+        match self {
+            Skill::Melee => "melee",
+            Skill::Defence => "defence",
+            Skill::Magic => "magic",
+            Skill::ShortBlade => "short blade",
+            Skill::LongBlade => "long blade",
+            Skill::Axe => "axe",
+            Skill::Polearm => "polearm",
+            Skill::Shield => "shield",
+            Skill::Unarmed => "unarmed",
+        }
+    }
+
+    /// Weapon skills are trained by use, rather than fixed at creation.
+    pub fn is_weapon_skill(&self) -> bool {
+        Skill::WEAPON_SKILLS.contains(self)
+    }
 }
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct Skills {
     pub skills: HashMap<Skill, i32>,
+    /// Progress towards the next level of each weapon skill.
+    pub training: HashMap<Skill, i32>,
+}
+
+/// The weapon skill used, and trained, when fighting with this item.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct WeaponSkill {
+    pub skill: Skill,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -258,6 +453,12 @@ pub struct Beatitude {
     pub known: bool,
 }
 
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Enchantment {
+    pub amount: i32,
+    pub known: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
 pub enum ItemType {
     Amulet,
@@ -269,6 +470,8 @@ pub enum ItemType {
     Potion,
     Ring,
     Wand,
+    Currency,
+    Tool,
 }
 
 impl ItemType {
@@ -283,6 +486,8 @@ impl ItemType {
             ItemType::Potion => "Potions",
             ItemType::Ring => "Rings",
             ItemType::Wand => "Wands",
+            ItemType::Currency => "Currency",
+            ItemType::Tool => "Tools",
         }
     }
 }
@@ -344,6 +549,16 @@ pub enum EquipmentSlot {
     Feet,
     Neck,
     Back,
+    OffHand,
+    LeftRing,
+    RightRing,
+}
+
+impl EquipmentSlot {
+    /// Slots which hold a weapon, rather than something worn.
+    pub fn holds_weapon(&self) -> bool {
+        matches!(self, EquipmentSlot::Melee | EquipmentSlot::OffHand)
+    }
 }
 
 #[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
@@ -378,6 +593,50 @@ pub struct NaturalAttacks {
     pub attacks: Vec<NaturalAttack>,
 }
 
+/// What a mob's ability does when it's used.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum AbilityEffect {
+    Bolt {
+        damage_type: DamageType,
+        n_dice: i32,
+        sides: i32,
+        modifier: i32,
+    },
+    Breath {
+        damage_type: DamageType,
+        n_dice: i32,
+        sides: i32,
+        modifier: i32,
+    },
+    Heal {
+        n_dice: i32,
+        sides: i32,
+        modifier: i32,
+    },
+    Summon {
+        id: String,
+        count: i32,
+    },
+    Blink,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Ability {
+    pub name: String,
+    pub effect: AbilityEffect,
+    pub range: i32,
+    pub cooldown: i32,
+    pub mana: i32,
+    pub colour: RGB,
+    pub ready_in: i32,
+}
+
+/// Spells and special attacks a mob can use, instead of moving or attacking in melee.
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Abilities {
+    pub abilities: Vec<Ability>,
+}
+
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct ArmourClassBonus {
     pub amount: i32,
@@ -393,6 +652,14 @@ pub struct Equippable {
     pub slot: EquipmentSlot,
 }
 
+/// Occupies both hands when wielded, leaving no room for a shield or off-hand weapon.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct TwoHanded {}
+
+/// Light enough to be wielded in the off-hand alongside a main weapon.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct OffHandable {}
+
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct Equipped {
     pub owner: Entity,
@@ -528,9 +795,22 @@ pub struct Confusion {
     pub turns: i32,
 }
 
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Fainted {
+    pub turns: i32,
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct Blind {}
 
+/// Swims as easily as it walks, and can't drown.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Aquatic {}
+
+/// Floats above the ground, so never falls through holes in it.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Levitating {}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct MagicMapper {}
 
@@ -576,10 +856,46 @@ pub struct Chasing {
     pub target: Entity,
 }
 
+/// Where this mob last saw, heard, or sensed its quarry. Once it loses track of them,
+/// it heads there, and then searches around for a while before giving up.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct LastSeen {
+    pub idx: usize,
+    pub turns: i32,
+    pub searching: bool,
+}
+
+/// This mob's nerve has broken, and it's running from anything hostile. If turns is
+/// above 0, it was routed by its leader dying, and won't rally until it's counted down.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Fleeing {
+    pub turns: i32,
+}
+
+/// Others of this mob's faction lose their nerve if they see it die. If it leads a
+/// group, it holds back until enough of them have gathered, and is then engaged.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Leader {
+    pub engaged: bool,
+}
+
+/// This mob fights as part of a group, under the given leader.
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct GroupMember {
+    pub leader: Entity,
+}
+
+/// There's only ever one of this mob. The id is its key in the raws, which the
+/// MasterDungeonMap uses to keep track of whether it's been met, and if it's been slain.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Unique {
+    pub id: String,
+}
+
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct Consumable {}
 
-#[derive(Component, Debug, ConvertSaveload)]
+#[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct Charges {
     pub uses: i32,
     pub max_uses: i32,
@@ -623,6 +939,12 @@ pub struct ProvidesRemoveCurse {}
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct ProvidesIdentify {}
 
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct ProvidesEnchantWeapon {}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct ProvidesEnchantArmour {}
+
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct Digger {}
 
@@ -643,14 +965,26 @@ pub struct ParticleLifetime {
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct EntryTrigger {}
 
+/// Marks something that moved this turn. from is the tile it moved from, so
+/// anything it moves onto can tell which way it was going.
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
-pub struct EntityMoved {}
+pub struct EntityMoved {
+    pub from: usize,
+}
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct MultiAttack {}
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
-pub struct Stackable {}
+pub struct Stackable {
+    pub quantity: i32,
+}
+
+/// The key in the raws that an item was spawned from, so more of it can be made.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct RawItem {
+    pub id: String,
+}
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct WantsToRemoveKey {}
@@ -665,3 +999,65 @@ pub struct Key {
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct WantsToAssignKey {}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Purse {
+    pub gold: i32,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Currency {
+    pub amount: i32,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Shopkeeper {}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct ForSale {
+    pub shopkeeper: Entity,
+}
+
+#[derive(Component, Debug, ConvertSaveload)]
+pub struct WantsToBuy {
+    pub shopkeeper: Entity,
+    pub item: Entity,
+}
+
+#[derive(Component, Debug, ConvertSaveload)]
+pub struct WantsToSell {
+    pub shopkeeper: Entity,
+    pub item: Entity,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Container {
+    pub weight_multiplier: f32, // applied to the weight of the contents
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct InContainer {
+    pub owner: Entity,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Locked {}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Trapped {}
+
+/// Drops whatever sets it off through to the level below.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Trapdoor {}
+
+#[derive(Component, Debug, ConvertSaveload)]
+pub struct WantsToPutInContainer {
+    pub container: Entity,
+    pub item: Entity,
+}
+
+#[derive(Component, Debug, ConvertSaveload)]
+pub struct WantsToTakeFromContainer {
+    pub container: Entity,
+    pub item: Entity,
+}
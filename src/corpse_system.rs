@@ -0,0 +1,75 @@
+use super::{
+    Clock,
+    Corpse,
+    InBackpack,
+    Name,
+    Player,
+    TakingTurn,
+    WantsToDelete,
+    WantsToRemoveKey,
+};
+use specs::prelude::*;
+use crate::data::entity::CORPSE_DECAY_AGE;
+
+/// CorpseSystem ages every corpse by one turn every time the turn clock ticks,
+/// renaming them as they rot, and deleting them once they've rotted away.
+pub struct CorpseSystem {}
+
+impl<'a> System<'a> for CorpseSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Corpse>,
+        WriteStorage<'a, Name>,
+        ReadStorage<'a, Clock>,
+        ReadStorage<'a, TakingTurn>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, WantsToRemoveKey>,
+        WriteStorage<'a, WantsToDelete>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut corpses,
+            mut names,
+            turn_clock,
+            turns,
+            backpack,
+            player,
+            mut remove_keys,
+            mut delete,
+        ) = data;
+
+        // If the turn clock isn't taking a turn this tick, corpses don't age.
+        let mut ticked = false;
+        for (_e, _c, _t) in (&entities, &turn_clock, &turns).join() {
+            ticked = true;
+            break;
+        }
+        if !ticked {
+            return;
+        }
+        for (entity, corpse) in (&entities, &mut corpses).join() {
+            let initial_state = corpse.rot_state();
+            corpse.age += 1;
+            if corpse.age >= CORPSE_DECAY_AGE {
+                // If the player is carrying it, make sure its key gets freed up first.
+                if let Some(carried) = backpack.get(entity) {
+                    if player.get(carried.owner).is_some() {
+                        remove_keys
+                            .insert(entity, WantsToRemoveKey {})
+                            .expect("Unable to insert WantsToRemoveKey");
+                    }
+                }
+                delete.insert(entity, WantsToDelete {}).expect("Unable to insert WantsToDelete");
+                continue;
+            }
+            if corpse.rot_state() != initial_state {
+                let (name, plural) = corpse.name();
+                names.insert(entity, Name { name, plural }).expect("Unable to insert Name");
+            }
+        }
+    }
+}
@@ -2,15 +2,20 @@ use super::{
     gamelog,
     gui::renderable_colour,
     Equipped,
+    ForSale,
     InBackpack,
     Item,
+    LeavesCorpse,
     LootTable,
+    Map,
+    MasterDungeonMap,
     Name,
     Player,
     Pools,
     Position,
     Renderable,
     RunState,
+    Unique,
     WantsToRemoveKey,
     WantsToDelete,
 };
@@ -67,7 +72,9 @@ pub fn delete_the_dead(ecs: &mut World) {
             }
         }
     }
-    let (mut items_to_delete, loot_to_spawn) = handle_dead_entity_items(ecs, &dead);
+    let (mut items_to_delete, mut loot_to_spawn) = handle_dead_entity_items(ecs, &dead);
+    loot_to_spawn.extend(handle_dead_uniques(ecs, &dead));
+    let corpses_to_spawn = handle_dead_entity_corpses(ecs, &dead);
     {
         let entities = ecs.entities();
         let removekeys = ecs.read_storage::<WantsToRemoveKey>();
@@ -88,9 +95,25 @@ pub fn delete_the_dead(ecs: &mut World) {
             0
         );
     }
+    for (name, fg, leaves, pos) in corpses_to_spawn {
+        crate::spawner::corpse(ecs, pos.x, pos.y, &name, fg, &leaves);
+    }
     for item in items_to_delete {
         ecs.delete_entity(item).expect("Unable to delete item.");
     }
+    // Anything a dead shopkeeper had up for sale is free for the taking.
+    {
+        let entities = ecs.entities();
+        let mut for_sale = ecs.write_storage::<ForSale>();
+        let unowned: Vec<Entity> = (&entities, &for_sale)
+            .join()
+            .filter(|(_e, sale)| dead.contains(&sale.shopkeeper))
+            .map(|(e, _sale)| e)
+            .collect();
+        for item in unowned {
+            for_sale.remove(item);
+        }
+    }
     // For everything that died, increment the event log, and delete.
     for victim in dead {
         gamelog::record_event(events::EVENT::Turn(1));
@@ -99,6 +122,63 @@ pub fn delete_the_dead(ecs: &mut World) {
     }
 }
 
+fn handle_dead_entity_corpses(
+    ecs: &mut World,
+    dead: &Vec<Entity>
+) -> Vec<(String, RGB, LeavesCorpse, Position)> {
+    let mut to_spawn: Vec<(String, RGB, LeavesCorpse, Position)> = Vec::new();
+    let leaves_corpse = ecs.read_storage::<LeavesCorpse>();
+    let names = ecs.read_storage::<Name>();
+    let renderables = ecs.read_storage::<Renderable>();
+    let positions = ecs.read_storage::<Position>();
+    for victim in dead.iter() {
+        if let (Some(leaves), Some(name), Some(pos)) = (
+            leaves_corpse.get(*victim),
+            names.get(*victim),
+            positions.get(*victim),
+        ) {
+            let fg = if let Some(renderable) = renderables.get(*victim) {
+                renderable.fg
+            } else {
+                RGB::named(WHITE)
+            };
+            to_spawn.push((name.name.clone(), fg, leaves.clone(), pos.clone()));
+        }
+    }
+    return to_spawn;
+}
+
+/// Marks any dead uniques as slain, has them say their last, and returns whatever
+/// they always drop.
+fn handle_dead_uniques(ecs: &mut World, dead: &[Entity]) -> Vec<(String, Position)> {
+    let mut to_spawn: Vec<(String, Position)> = Vec::new();
+    let uniques = ecs.read_storage::<Unique>();
+    let positions = ecs.read_storage::<Position>();
+    let renderables = ecs.read_storage::<Renderable>();
+    let map_id = ecs.fetch::<Map>().id;
+    let mut dm = ecs.fetch_mut::<MasterDungeonMap>();
+    let raws = &crate::raws::RAWS.lock().unwrap();
+    for victim in dead.iter() {
+        if let Some(unique) = uniques.get(*victim) {
+            dm.slay_unique(&unique.id, map_id);
+            let (death_message, drops) = crate::raws::get_unique_death(raws, &unique.id);
+            if let Some(death_message) = death_message {
+                gamelog::Logger
+                    ::new()
+                    .colour(renderable_colour(&renderables, *victim))
+                    .append(death_message)
+                    .log();
+            }
+            if let Some(pos) = positions.get(*victim) {
+                for drop in drops {
+                    to_spawn.push((drop, pos.clone()));
+                }
+            }
+        }
+    }
+    return to_spawn;
+}
+
 fn handle_dead_entity_items(
     ecs: &mut World,
     dead: &Vec<Entity>
@@ -1,3 +1,5 @@
+use crate::{ Perk, Skill };
+
 // --- GUI ---
 pub const CHAR_CREATE_HEADER: &str = "Who are you? [Aa-Zz]";
 pub const ANCESTRY_INFO_HEADER: &str = "Your ancestry grants...";
@@ -47,3 +49,31 @@ pub const WIZARD_MAX_SCROLL_LVL: i32 = 3;
 pub const WIZARD_SCROLL_AMOUNT: &str = "1d3+1";
 pub const WIZARD_POTION_AMOUNT: &str = "1d3";
 pub const VILLAGER_STARTING_FOOD: &str = "1d3+2";
+// --- CLASS STARTING SKILLS --- ## If any of these are changed, update class infotext in src/gui/character_creation.rs.
+pub const FIGHTER_STARTING_SKILLS: &[(Skill, i32)] = &[
+    (Skill::LongBlade, 1),
+    (Skill::ShortBlade, 1),
+    (Skill::Axe, 1),
+    (Skill::Shield, 1),
+];
+pub const ROGUE_STARTING_SKILLS: &[(Skill, i32)] = &[(Skill::ShortBlade, 2), (Skill::LongBlade, 1)];
+pub const WIZARD_STARTING_SKILLS: &[(Skill, i32)] = &[(Skill::ShortBlade, 1), (Skill::Polearm, 1)];
+pub const VILLAGER_STARTING_SKILLS: &[(Skill, i32)] = &[(Skill::Polearm, 1), (Skill::Unarmed, 1)];
+// --- CLASS PERKS ---
+pub const FIGHTER_PERKS: &[Perk] = &[Perk::Toughness, Perk::WeaponTraining, Perk::Guarded];
+pub const ROGUE_PERKS: &[Perk] = &[Perk::WeaponTraining, Perk::Guarded, Perk::FleetFooted];
+pub const WIZARD_PERKS: &[Perk] = &[Perk::ArcaneReserves, Perk::SpellFocus, Perk::Toughness];
+pub const VILLAGER_PERKS: &[Perk] = &[Perk::Toughness, Perk::WeaponTraining, Perk::FleetFooted];
+pub const TOUGHNESS_HP: i32 = 5;
+pub const ARCANE_RESERVES_MANA: i32 = 5;
+pub const FLEET_FOOTED_SPEED: i32 = 2;
+// --- CLASS STARTING GOLD ---
+pub const FIGHTER_STARTING_GOLD: &str = "3d10";
+pub const ROGUE_STARTING_GOLD: &str = "5d10";
+pub const WIZARD_STARTING_GOLD: &str = "4d10";
+pub const VILLAGER_STARTING_GOLD: &str = "1d10";
+// --- CLASS STARTING PETS ---
+pub const FIGHTER_STARTING_PET: &str = "dog_little";
+pub const ROGUE_STARTING_PET: &str = "kitten";
+pub const WIZARD_STARTING_PET: &str = "kitten";
+pub const VILLAGER_STARTING_PET: &str = "horse_little";
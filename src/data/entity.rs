@@ -5,6 +5,7 @@ pub const SPEED_MOD_BURDENED: f32 = 0.75;
 pub const SPEED_MOD_STRAINED: f32 = 0.5;
 pub const SPEED_MOD_OVERLOADED: f32 = 0.25;
 pub const SPEED_MOD_OVERMAP_TRAVEL: f32 = 0.33;
+pub const SPEED_MOD_SWIMMING: f32 = 0.5; //             Speed in deep water, for anything that isn't aquatic.
 pub const TURN_COST_MULTIPLIER: i32 = 4; //             How many ticks for NORMAL_SPEED to get a turn.
 pub const ATTR_BONUS_0: i32 = 10; //                    At this value, the attribute bonus is 0.
 pub const ATTR_NEEDED_PER_POINT: i32 = 2; //            How many points +- ATTR_BONUS_0 are needed per +- 1 bonus.
@@ -14,3 +15,50 @@ pub const STANDARD_MANA_DIE: i32 = 4; //                Standard mana die used f
 pub const MINIMUM_MANA: i32 = 0; //                     The minimum mana a monster can have.
 pub const MINIMUM_MANA_PLAYER: i32 = 1; //              The minimum mana a player can have.
 pub const STANDARD_BAC: i32 = 10; //                    Standard BASE AC.
+pub const SHOP_RADIUS: i32 = 8; //                      How far from a shopkeeper their stock can be laid out.
+pub const SELL_PRICE_DIVISOR: i32 = 2; //               Shopkeepers pay base value / this for items sold to them.
+pub const MAX_SAFE_ENCHANTMENT: i32 = 5; //             Enchanting an item above this risks destroying it.
+pub const CORPSE_WEIGHT_PER_LEVEL: f32 = 5.0; //        Corpse weight per mob level, if the mob doesn't specify one.
+pub const CORPSE_NUTRITION_PER_LB: f32 = 20.0; //       Nutrition gained per lb of a fresh corpse.
+pub const CORPSE_LBS_EATEN_PER_TURN: f32 = 5.0; //      How many lbs of a corpse can be eaten in one turn.
+pub const CORPSE_OLD_AGE: i32 = 50; //                  Turns until a corpse is old.
+pub const CORPSE_ROTTEN_AGE: i32 = 150; //              Turns until a corpse is rotten.
+pub const CORPSE_DECAY_AGE: i32 = 300; //               Turns until a corpse rots away entirely.
+pub const CORPSE_INTRINSIC_ONE_IN: i32 = 3; //          Chance of a non-rotten corpse granting its intrinsic.
+pub const CORPSE_SPEED_THRESHOLD: i32 = 18; //          Mobs faster than this leave corpses which grant speed.
+pub const DUAL_WIELD_MAIN_PENALTY: i32 = -2; //         To-hit modifier for the main-hand weapon when dual-wielding.
+pub const DUAL_WIELD_OFF_PENALTY: i32 = -4; //          To-hit modifier for the off-hand weapon when dual-wielding.
+pub const SKILL_TRAINING_PER_LEVEL: i32 = 20; //        Successful uses to train a weapon skill, times (current level + 1).
+pub const SKILL_MAX_LEVEL: i32 = 5; //                  The highest level a weapon skill can be trained to.
+pub const LEVELS_PER_ATTRIBUTE_POINT: i32 = 4; //       Every this many levels, the player gets an attribute point.
+pub const ALLY_FOLLOW_DISTANCE: f32 = 2.5; //           Allies further than this from their owner walk back to them.
+pub const ALLY_LEASH_DISTANCE: f32 = 8.0; //            Following allies won't chase a fight further than this from their owner.
+pub const ALLY_TRAVEL_DISTANCE: f32 = 3.0; //           Following allies this close to the player come along to the next level.
+pub const HIRE_COST_PER_LEVEL: i32 = 25; //             Gold it costs to hire a hireling, per level (plus one).
+pub const BOUNTY_MAX_KILLS: i32 = 5; //                 The most kills a bounty in the endless dungeon will ask for.
+pub const BOUNTY_DELVE_LEVELS: i32 = 3; //              How many levels deeper a delving bounty asks the player to go.
+pub const BOUNTY_RETRIEVE_LEVELS: i32 = 2; //           How many levels deeper a retrieval bounty hides its item.
+pub const BOUNTY_GOLD_PER_DEPTH: i32 = 20; //           Gold paid out for a bounty, per depth of the level it was found on.
+pub const TAME_DIFFICULTY: i32 = 10; //                 Taming succeeds if 1d20 + cha bonus + player level beats this + the animal's level.
+pub const REPUTATION_ATTACK_PENALTY: i32 = -10; //      Standing lost with a faction for attacking one of its (non-hostile) members.
+pub const REPUTATION_KILL_PENALTY: i32 = -30; //        Standing lost with a faction for killing one of its (non-hostile) members.
+pub const REPUTATION_WITNESS_DIVISOR: i32 = 2; //       Other factions that see the act lose the penalty divided by this.
+pub const REPUTATION_WARY: i32 = -20; //                At or below this standing, a faction that would ignore the player flees.
+pub const REPUTATION_HOSTILE: i32 = -50; //             At or below this standing, a faction attacks the player on sight.
+pub const MORALE_FLEE_HP_PERCENT: i32 = 25; //          Mobs with a mind flee once at or below this percentage of their max HP.
+pub const MORALE_RALLY_HP_PERCENT: i32 = 60; //         Fleeing mobs rally once healed back up to this percentage of their max HP.
+pub const MORALE_OUTNUMBERED_RATIO: i32 = 3; //         Mobs flee when the hostiles they can see outnumber them and their friends this many times over.
+pub const MORALE_LEADER_ROUT_TURNS: i32 = 10; //        How many turns a mob flees for after seeing its leader die.
+pub const GROUP_FORM_RADIUS: f32 = 6.0; //              Mobs spawned this close to a leader, or to others of their kind, form a group.
+pub const GROUP_GATHER_RADIUS: f32 = 4.0; //            A group has gathered once half its members are this close to their leader.
+pub const MEMORY_SEARCH_TURNS: i32 = 10; //             How many turns a mob searches around where it last saw its quarry before giving up.
+pub const MEMORY_SEARCH_RADIUS: f32 = 4.0; //           How far from that spot a searching mob will wander.
+pub const NOISE_COMBAT_RADIUS: f32 = 10.0; //           Mobs this close to a fight hear it, and come to investigate.
+pub const ABILITY_HEAL_HP_PERCENT: i32 = 50; //         Mobs with a healing ability use it on friends at or below this percentage of their max HP.
+pub const SWIM_BURDENED_ONE_IN: i32 = 8; //             Chance per turn of a burdened swimmer going under.
+pub const SWIM_STRAINED_ONE_IN: i32 = 4; //             Chance per turn of a strained swimmer going under.
+pub const SWIM_OVERLOADED_ONE_IN: i32 = 2; //           Chance per turn of an overloaded swimmer going under.
+pub const DROWNING_DAMAGE_DIE: i32 = 6; //              Going under deals 1d this.
+pub const LAVA_DAMAGE_DIE: i32 = 10; //                 Every step into lava deals 2d this.
+pub const FALL_DAMAGE_DIE: i32 = 6; //                  Falling to the level below deals 2d this.
+pub const THIN_FLOOR_ONE_IN: i32 = 3; //                Chance of thin floor giving way underfoot.
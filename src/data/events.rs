@@ -4,6 +4,7 @@ use serde::{ Deserialize, Serialize };
 pub enum EVENT {
     Turn(i32),
     Level(i32),
+    LevelUpChoice(String),
     ChangedFloor(String),
     PlayerConfused(i32),
     KickedSomething(i32),
@@ -13,6 +14,9 @@ pub enum EVENT {
     PlayerDied(String),
     Discovered(String),
     Identified(String),
+    DialogueFlag(String),
+    StartedQuest(String),
+    CompletedQuest(String),
 }
 
 impl EVENT {
@@ -24,4 +28,10 @@ impl EVENT {
     pub const COUNT_PLAYER_CONFUSED: &str = "PlayerConfused";
     pub const COUNT_KICK: &str = "kick";
     pub const COUNT_LOOKED_FOR_HELP: &str = "LookedForHelp";
+    pub const COUNT_QUESTS_COMPLETED: &str = "quests_completed";
+
+    /// The counter for how many of a given mob have been killed. i.e. "killed_jackal"
+    pub fn count_killed(name: &str) -> String {
+        format!("{}_{}", EVENT::COUNT_KILLED, name)
+    }
 }
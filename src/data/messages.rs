@@ -16,12 +16,23 @@ pub const HEAL_PLAYER_HIT: &str = "recover some vigour.";
 pub const HEAL_PLAYER_HIT_BLESSED: &str = "You feel great";
 pub const HEAL_OTHER_HIT: &str = "is rejuvenated!";
 
+pub const ENCHANT_NO_WEAPON: &str = "Your hands twitch.";
+pub const ENCHANT_NO_ARMOUR: &str = "Your skin glows, then fades.";
+pub const ENCHANT_GLOW: &str = "glows blue for a moment.";
+pub const ENCHANT_GLOW_CURSED: &str = "glows black for a moment.";
+pub const ENCHANT_EVAPORATE: &str = "violently glows, and evaporates!";
+
 pub const MAGICMAP: &str = "You recall your surroundings!";
 pub const MAGICMAP_CURSED: &str = "... but forget where you last were";
 
 pub const NUTRITION: &str = "You eat the";
 pub const NUTRITION_CURSED: &str = "Blech! Rotten";
 pub const NUTRITION_BLESSED: &str = "Delicious";
+pub const CORPSE_ROTTEN: &str = "Ulch - that meat was tainted!";
+pub const CORPSE_POISONOUS: &str = "Ecch - that must have been poisonous!";
+pub const CHOKE: &str = "You choke over your food!";
+pub const CHOKE_VOMIT: &str = "You stuff yourself and then vomit voluminously.";
+pub const FAINT: &str = "You faint from lack of food.";
 
 pub const LEVELUP_PLAYER: &str = "Welcome to experience level";
 pub const YOU_PICKUP_ITEM: &str = "You pick up the";
@@ -30,6 +41,67 @@ pub const YOU_DROP_ITEM: &str = "You drop the";
 pub const YOU_EQUIP_ITEM: &str = "You equip the";
 pub const YOU_REMOVE_ITEM: &str = "You unequip your";
 pub const YOU_REMOVE_ITEM_CURSED: &str = "You can't remove the";
+pub const SKILL_TRAINED: &str = "You feel more confident in your";
+pub const YOU_PICKUP_GOLD: &str = "You pick up";
+pub const YOU_STEAL_ITEM: &str = "You steal the";
+pub const SHOPKEEPER_THIEF: &str = "shouts \"Thief!\"";
+pub const SHOP_BUY_ITEM: &str = "You buy the";
+pub const SHOP_SELL_ITEM: &str = "You sell the";
+pub const SHOP_CANT_AFFORD: &str = "You can't afford the";
+pub const SHOP_SHOPKEEPER_CANT_AFFORD: &str = "can't afford to buy the";
+pub const CONTAINER_PUT_ITEM: &str = "You put the";
+pub const CONTAINER_TAKE_ITEM: &str = "You take the";
+pub const CONTAINER_INTO_ITSELF: &str = "You can't put something inside itself";
+pub const CONTAINER_LOCKED: &str = "is locked";
+pub const CONTAINER_FORCE_SUCCESS: &str = "You force open the lid of the";
+pub const CONTAINER_FORCE_FAIL: &str = "You fail to force open the";
+pub const CONTAINER_TRAPPED: &str = "It was booby-trapped!";
+pub const TAME_NOTHING: &str = "There's nothing there to tame";
+pub const TAME_NO_FOOD: &str = "You have nothing to offer it";
+pub const TAME_SUCCESS: &str = "eats from your hand, and decides to follow you";
+pub const TAME_FAIL: &str = "snatches the food and ignores you";
+pub const HIRE_NO_GOLD: &str = "You can't afford to hire the";
+pub const HIRE_SUCCESS: &str = "You hire the";
+pub const ALLY_REFUSES_TRAP: &str = "won't step onto the trap";
+pub const ALLY_NO_ALLIES: &str = "You don't have anyone to command";
+pub const ALLY_ORDER_FOLLOW: &str = "You tell your allies to follow you";
+pub const ALLY_ORDER_STAY: &str = "You tell your allies to stay here";
+pub const ALLY_ORDER_ATTACK: &str = "You order your allies to attack the";
+pub const ALLY_NOTHING_TO_ATTACK: &str = "There's nothing there to attack";
+pub const TALK_NOTHING: &str = "There's nobody there to talk to";
+pub const TALK_NOTHING_TO_SAY: &str = "has nothing to say to you";
+pub const DIALOGUE_GIVES: &str = "hands you";
+pub const DIALOGUE_TAKES: &str = "You hand over";
+pub const DIALOGUE_HEALED: &str = "You feel better";
+pub const DIALOGUE_HINT: &str = "You make a note:";
+pub const DIALOGUE_REVEAL_STAIRS: &str = "You learn the way down from here";
+pub const QUEST_STARTED: &str = "New quest:";
+pub const QUEST_COMPLETED: &str = "Quest complete:";
+pub const QUEST_HAND_OVER: &str = "You hand over";
+pub const QUEST_REWARD_GOLD: &str = "You're rewarded with";
+pub const QUEST_REWARD_REPUTATION: &str = "Word of your good deed gets around";
+pub const QUEST_BOUNTY_NOTICE: &str = "A bounty notice is scratched into the wall here.";
+pub const MORALE_FLEE: &str = "turns to flee";
+pub const MORALE_RALLY: &str = "rallies";
+pub const GROUP_ENGAGED: &str = "signals the attack";
+pub const ABILITY_USE: &str = "uses";
+pub const REPUTATION_WARY: &str = "Word gets around. You're watched warily now";
+pub const REPUTATION_HOSTILE: &str = "Word gets around. You're a marked target now";
+pub const SWIM_ENTER: &str = "You wade out of your depth, and start to swim.";
+pub const SWIM_GO_UNDER: &str = "You go under, dragged down by your load!";
+pub const HAZARD_LAVA: &str = "You step into the lava, and it burns!";
+pub const HAZARD_LAVA_OTHER: &str = "is burned by the lava";
+pub const HAZARD_THIN_FLOOR: &str = "The floor gives way beneath you!";
+pub const HAZARD_THIN_FLOOR_OTHER: &str = "The floor gives way!";
+pub const HAZARD_ICE: &str = "You slide across the ice.";
+pub const HAZARD_ICE_OTHER: &str = "slides across the ice";
+pub const FALL_PLAYER: &str = "You fall through to the level below!";
+pub const FALL_OTHER: &str = "falls through to the level below";
+pub const FALL_LEVITATING: &str = "You float safely over the drop.";
+pub const DIG_DOWN: &str = "You dig a hole through the floor!";
+pub const DIG_HOLE: &str = "A hole opens up in the floor!";
+pub const DIG_THROUGH: &str = "The wall crumbles away.";
+pub const DIG_TOO_HARD: &str = "The ground here is too hard to dig through.";
 
 /// Prefixes death message.
 pub const PLAYER_DIED: &str = "You died!";
@@ -37,6 +109,13 @@ pub const PLAYER_DIED: &str = "You died!";
 pub const PLAYER_DIED_SUICIDE: &str = "You killed yourself";
 pub const PLAYER_DIED_NAMED_ATTACKER: &str = "You were killed by";
 pub const PLAYER_DIED_UNKNOWN: &str = "You were killed"; // Ultimately, this should never be used. Slowly include specific messages for any death.
+/// Death messages for deaths without a source.
+pub const PLAYER_DIED_STARVED: &str = "You starved to death!";
+pub const PLAYER_DIED_CHOKED: &str = "You choked to death on your food!";
+pub const PLAYER_DIED_DROWNED: &str = "You drowned!";
+pub const PLAYER_DIED_BURNED: &str = "You burned to death in lava!";
+pub const PLAYER_DIED_FELL: &str = "You fell to your death!";
+pub const PLAYER_DIED_UNKNOWN_CAUSES: &str = "You died from unknown causes!";
 /// Death message addendums. Appended at end of death message.
 pub const PLAYER_DIED_ADDENDUM_FIRST: &str = " ";
 pub const PLAYER_DIED_ADDENDUM_MID: &str = ", ";
@@ -56,6 +56,14 @@ pub const SHALLOW_WATER_COLOUR: (u8, u8, u8) = (24, 47, 99);
 pub const SHALLOW_WATER_OFFSETS: (i32, i32, i32) = (3, 10, 45);
 pub const DEEP_WATER_COLOUR: (u8, u8, u8) = (18, 33, 63);
 pub const DEEP_WATER_OFFSETS: (i32, i32, i32) = (5, 10, 32);
+pub const LAVA_COLOUR: (u8, u8, u8) = (140, 36, 8);
+pub const LAVA_OFFSETS: (i32, i32, i32) = (60, 30, 5);
+pub const CHASM_COLOUR: (u8, u8, u8) = (2, 2, 4);
+pub const CHASM_OFFSETS: (i32, i32, i32) = (2, 2, 2);
+pub const ICE_COLOUR: (u8, u8, u8) = (120, 160, 180);
+pub const ICE_OFFSETS: (i32, i32, i32) = (5, 10, 10);
+pub const THIN_FLOOR_COLOUR: (u8, u8, u8) = (120, 110, 80);
+pub const THIN_FLOOR_OFFSETS: (i32, i32, i32) = (10, 10, 10);
 pub const BARS_COLOUR: (u8, u8, u8) = (100, 100, 100);
 pub const IMPASSABLE_MOUNTAIN_COLOUR: (u8, u8, u8) = (20, 23, 20);
 pub const IMPASSABLE_MOUNTAIN_OFFSETS: (i32, i32, i32) = (4, 4, 4);
@@ -79,6 +87,10 @@ pub const HEAVY_FOLIAGE_GLYPH: char = ';';
 pub const SAND_GLYPH: char = '.';
 pub const SHALLOW_WATER_GLYPH: char = '~';
 pub const DEEP_WATER_GLYPH: char = '≈';
+pub const LAVA_GLYPH: char = '≈';
+pub const CHASM_GLYPH: char = ' ';
+pub const ICE_GLYPH: char = '.';
+pub const THIN_FLOOR_GLYPH: char = ',';
 pub const BARS_GLYPH: char = '#';
 pub const IMPASSABLE_MOUNTAIN_GLYPH: char = '▲';
 
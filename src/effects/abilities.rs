@@ -0,0 +1,221 @@
+use super::{ add_effect, particles, spatial, targeting, EffectType, Entity, Targets, World };
+use crate::{
+    gamelog,
+    gui::renderable_colour,
+    raws::{ self, SpawnType },
+    Abilities,
+    AbilityEffect,
+    EntityMoved,
+    Faction,
+    Map,
+    Name,
+    Position,
+    RandomNumberGenerator,
+    Renderable,
+    SpawnParticleLine,
+    Telepath,
+    Viewshed,
+};
+use crate::data::ids::rgb_to_u8;
+use crate::data::messages::*;
+use crate::data::visuals::DEFAULT_PARTICLE_LIFETIME;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Uses one of source's abilities on target. Whatever the ability does is passed back
+/// into the effects queue, the same way an item's effects are.
+pub fn ability_trigger(
+    source: Option<Entity>,
+    ability: usize,
+    target: &Targets,
+    ecs: &mut World
+) {
+    let source = if let Some(source) = source { source } else { return };
+    let ability = if let Some(abilities) = ecs.read_storage::<Abilities>().get(source) {
+        if let Some(ability) = abilities.abilities.get(ability) {
+            ability.clone()
+        } else {
+            return;
+        }
+    } else {
+        return;
+    };
+    let source_idx = if let Some(idx) = targeting::entity_position(ecs, source) {
+        idx
+    } else {
+        return;
+    };
+    log_ability(ecs, source, source_idx, &ability.name, ability.colour);
+    match ability.effect {
+        AbilityEffect::Bolt { damage_type, n_dice, sides, modifier } => {
+            if let Targets::Entity { target: victim } = target {
+                if let Some(end) = targeting::entity_position(ecs, *victim) {
+                    particles::spawn_line_particles(
+                        ecs,
+                        source_idx as i32,
+                        end as i32,
+                        &bolt_particles(ability.colour)
+                    );
+                }
+            }
+            let amount = roll(ecs, n_dice, sides, modifier);
+            add_effect(Some(source), EffectType::Damage { amount, damage_type }, target.clone());
+        }
+        AbilityEffect::Breath { damage_type, n_dice, sides, modifier } => {
+            burst(ecs, source_idx, target, ability.colour, to_cp437('░'));
+            let amount = roll(ecs, n_dice, sides, modifier);
+            add_effect(Some(source), EffectType::Damage { amount, damage_type }, target.clone());
+        }
+        AbilityEffect::Heal { n_dice, sides, modifier } => {
+            burst(ecs, source_idx, target, ability.colour, to_cp437('♥'));
+            let amount = roll(ecs, n_dice, sides, modifier);
+            add_effect(
+                Some(source),
+                EffectType::Healing { amount, increment_max: false },
+                target.clone()
+            );
+        }
+        AbilityEffect::Summon { id, count } => summon(ecs, source, source_idx, &id, count),
+        AbilityEffect::Blink => {
+            if let Targets::Tile { target: dest } = target {
+                blink(ecs, source, source_idx, *dest, ability.colour);
+            }
+        }
+    }
+}
+
+fn roll(ecs: &World, n_dice: i32, sides: i32, modifier: i32) -> i32 {
+    ecs.write_resource::<RandomNumberGenerator>().roll_dice(n_dice, sides) + modifier
+}
+
+fn bolt_particles(colour: RGB) -> SpawnParticleLine {
+    SpawnParticleLine {
+        glyph: to_cp437('*'),
+        tail_glyph: to_cp437('∙'),
+        colour,
+        lifetime_ms: 25.0,
+        trail_colour: colour,
+        trail_lifetime_ms: 50.0,
+    }
+}
+
+/// Flashes every tile an ability reached, spreading out from where it was used.
+fn burst(ecs: &World, source_idx: usize, target: &Targets, colour: RGB, glyph: FontCharType) {
+    let tiles: Vec<usize> = match target {
+        Targets::Tile { target } => vec![*target],
+        Targets::TileList { targets } => targets.clone(),
+        Targets::Entity { target } =>
+            targeting::entity_position(ecs, *target).into_iter().collect(),
+        Targets::EntityList { targets } =>
+            targets
+                .iter()
+                .filter_map(|target| targeting::entity_position(ecs, *target))
+                .collect(),
+    };
+    let map = ecs.fetch::<Map>();
+    let origin = Point::new((source_idx as i32) % map.width, (source_idx as i32) / map.width);
+    for tile in tiles {
+        let there = Point::new((tile as i32) % map.width, (tile as i32) / map.width);
+        let distance = DistanceAlg::Pythagoras.distance2d(origin, there);
+        add_effect(
+            None,
+            EffectType::Particle {
+                glyph,
+                fg: colour,
+                bg: RGB::named(BLACK),
+                lifespan: DEFAULT_PARTICLE_LIFETIME,
+                delay: distance * 50.0,
+            },
+            Targets::Tile { target: tile }
+        );
+    }
+}
+
+/// Calls up count of a mob next to source, on source's side.
+fn summon(ecs: &mut World, source: Entity, source_idx: usize, id: &str, count: i32) {
+    let (width, height, difficulty) = {
+        let map = ecs.fetch::<Map>();
+        (map.width, map.height, map.difficulty)
+    };
+    let faction = ecs.read_storage::<Faction>().get(source).cloned();
+    let (x, y) = ((source_idx as i32) % width, (source_idx as i32) / width);
+    let mut free: Vec<usize> = Vec::new();
+    for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+        let (tx, ty) = (x + dx, y + dy);
+        if tx < 1 || tx > width - 2 || ty < 1 || ty > height - 2 {
+            continue;
+        }
+        let idx = (ty * width + tx) as usize;
+        if !spatial::is_blocked(idx) {
+            free.push(idx);
+        }
+    }
+    let raws = &raws::RAWS.lock().unwrap();
+    for idx in free.into_iter().take(count as usize) {
+        let pos = SpawnType::AtPosition { x: (idx as i32) % width, y: (idx as i32) / width };
+        if let Some(summoned) = raws::spawn_named_mob(raws, ecs, id, pos, difficulty) {
+            spatial::index_entity(summoned, idx, true);
+            if let Some(faction) = &faction {
+                ecs.write_storage::<Faction>()
+                    .insert(summoned, faction.clone())
+                    .expect("Unable to insert Faction");
+            }
+        }
+    }
+}
+
+/// Moves source straight to dest.
+fn blink(ecs: &mut World, source: Entity, source_idx: usize, dest: usize, colour: RGB) {
+    if spatial::is_blocked(dest) {
+        return;
+    }
+    for idx in [source_idx, dest] {
+        add_effect(
+            None,
+            EffectType::Particle {
+                glyph: to_cp437('*'),
+                fg: colour,
+                bg: RGB::named(BLACK),
+                lifespan: DEFAULT_PARTICLE_LIFETIME,
+                delay: 0.0,
+            },
+            Targets::Tile { target: idx }
+        );
+    }
+    let width = ecs.fetch::<Map>().width;
+    if let Some(pos) = ecs.write_storage::<Position>().get_mut(source) {
+        pos.x = (dest as i32) % width;
+        pos.y = (dest as i32) / width;
+    }
+    spatial::move_entity(source, source_idx, dest);
+    if let Some(viewshed) = ecs.write_storage::<Viewshed>().get_mut(source) {
+        viewshed.dirty = true;
+    }
+    if let Some(telepath) = ecs.write_storage::<Telepath>().get_mut(source) {
+        telepath.dirty = true;
+    }
+    ecs.write_storage::<EntityMoved>()
+        .insert(source, EntityMoved { from: source_idx })
+        .expect("Unable to insert EntityMoved");
+}
+
+/// Lets the player know what's being used, if they can see who's using it.
+fn log_ability(ecs: &World, source: Entity, source_idx: usize, name: &str, colour: RGB) {
+    if !ecs.fetch::<Map>().visible_tiles[source_idx] {
+        return;
+    }
+    if let Some(source_name) = ecs.read_storage::<Name>().get(source) {
+        gamelog::Logger
+            ::new()
+            .append("The")
+            .colour(renderable_colour(&ecs.read_storage::<Renderable>(), source))
+            .append(&source_name.name)
+            .colour(WHITE)
+            .append(ABILITY_USE)
+            .colour(rgb_to_u8(colour))
+            .append_n(name)
+            .colour(WHITE)
+            .period()
+            .log();
+    }
+}
@@ -5,7 +5,9 @@ use crate::{
     Attributes,
     Confusion,
     Destructible,
+    Eating,
     GrantsXP,
+    LevelHistory,
     Map,
     Player,
     Pools,
@@ -15,8 +17,13 @@ use crate::{
     HungerState,
     Bleeds,
     HasDamageModifiers,
+    Position,
+    RunState,
+    TileType,
+    tile_deep,
 };
 use crate::gui::with_article;
+use crate::hunger_system::MAX_SATIATION;
 use crate::data::visuals::{ DEFAULT_PARTICLE_LIFETIME, LONG_PARTICLE_LIFETIME };
 use crate::data::messages::LEVELUP_PLAYER;
 use crate::data::events::*;
@@ -25,6 +32,10 @@ use bracket_lib::prelude::*;
 use specs::prelude::*;
 
 pub fn inflict_damage(ecs: &mut World, damage: &EffectSpawner, target: Entity) {
+    if let Some(source) = damage.source {
+        crate::reputation::on_attacked(ecs, source, target);
+        crate::ai::hear_fighting(ecs, source, target);
+    }
     let mut pools = ecs.write_storage::<Pools>();
     if let Some(target_pool) = pools.get_mut(target) {
         if !target_pool.god {
@@ -37,6 +48,13 @@ pub fn inflict_damage(ecs: &mut World, damage: &EffectSpawner, target: Entity) {
                     1.0
                 };
                 target_pool.hit_points.current -= ((amount as f32) * mult) as i32;
+                // Being attacked puts you off your food.
+                if damage.source.is_some() {
+                    let stopped_eating = ecs.write_storage::<Eating>().remove(target).is_some();
+                    if stopped_eating && target == *ecs.fetch::<Entity>() {
+                        gamelog::Logger::new().append("You stop eating.").log();
+                    }
+                }
                 let bleeders = ecs.read_storage::<Bleeds>();
                 if let Some(bleeds) = bleeders.get(target) {
                     add_effect(
@@ -216,6 +234,10 @@ fn get_death_message(ecs: &World, source: Entity) -> String {
 
 /// Handles EntityDeath effects.
 pub fn entity_death(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let Some(source) = effect.source {
+        crate::reputation::on_killed(ecs, source, target);
+    }
+    crate::ai::rout_followers(ecs, target);
     let mut xp_gain = 0;
     let mut pools = ecs.write_storage::<Pools>();
     let attributes = ecs.read_storage::<Attributes>();
@@ -255,6 +277,17 @@ pub fn entity_death(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
                 // If it was the PLAYER that levelled up:
                 if ecs.read_storage::<Player>().get(source).is_some() {
                     gamelog::record_event(EVENT::Level(1));
+                    // Interrupt the turn to pick perks, and attributes if this level grants any.
+                    // Levels are queued up, so gaining more than one at once doesn't lose a pick.
+                    let mut history = ecs.write_storage::<LevelHistory>();
+                    if let Some(history) = history.get_mut(source) {
+                        history.pending += 1;
+                    } else {
+                        history
+                            .insert(source, LevelHistory { level_ups: Vec::new(), pending: 1 })
+                            .expect("Unable to insert LevelHistory");
+                    }
+                    *ecs.write_resource::<RunState>() = RunState::ShowLevelUp { attribute: None };
                     gamelog::Logger
                         ::new()
                         .append(LEVELUP_PLAYER)
@@ -334,15 +367,31 @@ pub fn entity_death(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
         }
     } else {
         if target == *player {
-            if let Some(hc) = ecs.read_storage::<HungerClock>().get(target) {
+            // Dying with nobody to blame might be down to whatever the player was standing in.
+            let terrain = ecs
+                .read_storage::<Position>()
+                .get(target)
+                .and_then(|pos| {
+                    let map = ecs.fetch::<Map>();
+                    match map.tiles[map.xy_idx(pos.x, pos.y)] {
+                        tile if tile_deep(tile) => Some(PLAYER_DIED_DROWNED),
+                        TileType::Lava => Some(PLAYER_DIED_BURNED),
+                        TileType::Chasm => Some(PLAYER_DIED_FELL),
+                        _ => None,
+                    }
+                });
+            let message = if let Some(hc) = ecs.read_storage::<HungerClock>().get(target) {
                 if hc.state == HungerState::Starving {
-                    gamelog::record_event(EVENT::PlayerDied("You starved to death!".to_string()));
+                    PLAYER_DIED_STARVED
+                } else if hc.duration > MAX_SATIATION {
+                    PLAYER_DIED_CHOKED
+                } else {
+                    terrain.unwrap_or(PLAYER_DIED_UNKNOWN_CAUSES)
                 }
             } else {
-                gamelog::record_event(
-                    EVENT::PlayerDied("You died from unknown causes!".to_string())
-                );
-            }
+                terrain.unwrap_or(PLAYER_DIED_UNKNOWN_CAUSES)
+            };
+            gamelog::record_event(EVENT::PlayerDied(message.to_string()));
         }
     }
 }
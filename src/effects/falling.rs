@@ -0,0 +1,109 @@
+use super::{ add_effect, EffectSpawner, EffectType, Targets };
+use crate::{
+    gamelog,
+    gui::{ item_colour_ecs, obfuscate_name_ecs, renderable_colour_ecs },
+    map::dungeon::{ has_level_below, landing_spot },
+    spatial,
+    DamageType,
+    Item,
+    Levitating,
+    Map,
+    MasterDungeonMap,
+    OtherLevelPosition,
+    Pools,
+    Position,
+    RunState,
+};
+use crate::data::entity::FALL_DAMAGE_DIE;
+use crate::data::messages::{ FALL_OTHER, FALL_LEVITATING, FALL_PLAYER };
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+use std::collections::HashSet;
+
+/// Drops the target through the floor to the level below. The player takes a beating and
+/// heads down after it, and anything else is frozen on the level below until the player
+/// gets there. Only creatures and items fall, and anything levitating stays where it is.
+pub fn fall(ecs: &mut World, _effect: &EffectSpawner, target: Entity) {
+    if ecs.read_storage::<Pools>().get(target).is_none() && ecs.read_storage::<Item>().get(target).is_none() {
+        return;
+    }
+    let pos = if let Some(pos) = ecs.read_storage::<Position>().get(target) {
+        pos.clone()
+    } else {
+        return;
+    };
+    let player_entity = *ecs.fetch::<Entity>();
+    if ecs.read_storage::<Levitating>().get(target).is_some() {
+        if target == player_entity {
+            gamelog::Logger::new().append(FALL_LEVITATING).log();
+        }
+        return;
+    }
+    let (map_id, idx, visible, below) = {
+        let map = ecs.fetch::<Map>();
+        let idx = map.xy_idx(pos.x, pos.y);
+        (map.id, idx, map.visible_tiles[idx], has_level_below(&map))
+    };
+    if !below {
+        return;
+    }
+    if target == player_entity {
+        gamelog::Logger::new().append(FALL_PLAYER).log();
+        add_effect(
+            None,
+            EffectType::Damage {
+                amount: ecs.write_resource::<RandomNumberGenerator>().roll_dice(2, FALL_DAMAGE_DIE),
+                damage_type: DamageType::Physical,
+            },
+            Targets::Entity { target }
+        );
+        *ecs.write_resource::<RunState>() = RunState::FallToLevel(map_id + 1);
+        return;
+    }
+    if visible {
+        let colour = if ecs.read_storage::<Item>().get(target).is_some() {
+            item_colour_ecs(ecs, target)
+        } else {
+            renderable_colour_ecs(ecs, target)
+        };
+        gamelog::Logger
+            ::new()
+            .append("The")
+            .colour(colour)
+            .append(obfuscate_name_ecs(ecs, target).0)
+            .colour(WHITE)
+            .append(FALL_OTHER)
+            .period()
+            .log();
+    }
+    // If the level below has been built, it lands somewhere on it now, clear of anything
+    // that's already there. Otherwise, it's given somewhere once the level's built.
+    let (x, y) = {
+        let dm = ecs.fetch::<MasterDungeonMap>();
+        let others = ecs.read_storage::<OtherLevelPosition>();
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        dm.get_map(map_id + 1)
+            .and_then(|map| {
+                let occupied: HashSet<usize> = (&others)
+                    .join()
+                    .filter(|other| {
+                        other.id == map.id &&
+                            other.x >= 0 &&
+                            other.x < map.width &&
+                            other.y >= 0 &&
+                            other.y < map.height
+                    })
+                    .map(|other| map.xy_idx(other.x, other.y))
+                    .collect();
+                landing_spot(&map, &occupied, &mut rng).map(|spot| {
+                    ((spot as i32) % map.width, (spot as i32) / map.width)
+                })
+            })
+            .unwrap_or((-1, -1))
+    };
+    ecs.write_storage::<Position>().remove(target);
+    ecs.write_storage::<OtherLevelPosition>()
+        .insert(target, OtherLevelPosition { x, y, id: map_id + 1 })
+        .expect("Unable to insert OtherLevelPosition");
+    spatial::remove_entity(target, idx);
+}
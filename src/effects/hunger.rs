@@ -1,11 +1,54 @@
-use super::{ EffectSpawner, EffectType };
-use crate::HungerClock;
+use super::{ add_effect, EffectSpawner, EffectType, Targets };
+use crate::{ gamelog, DamageType, HungerClock, HungerState, Pools };
+use crate::hunger_system::MAX_SATIATION;
+use crate::data::messages::{ CHOKE, CHOKE_VOMIT };
+use bracket_lib::prelude::*;
 use specs::prelude::*;
 
+const CHOKE_SURVIVAL_ONE_IN: i32 = 4; // Chance of vomiting instead of choking to death.
+const NUTRITION_AFTER_VOMITING: i32 = 500;
+
 pub fn modify_nutrition(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
     if let EffectType::ModifyNutrition { amount } = &effect.effect_type {
+        let mut choked = false;
         if let Some(hc) = ecs.write_storage::<HungerClock>().get_mut(target) {
+            // Eating when already satiated, and going over the limit, risks choking.
+            let overeating = hc.duration + amount > MAX_SATIATION;
+            if *amount > 0 && hc.state == HungerState::Satiated && overeating {
+                choked = true;
+            }
             hc.duration += amount;
         }
+        if choked {
+            choke(ecs, target);
+        }
+    }
+}
+
+fn choke(ecs: &mut World, target: Entity) {
+    let survived = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        rng.roll_dice(1, CHOKE_SURVIVAL_ONE_IN) == 1
+    };
+    let is_player = target == *ecs.fetch::<Entity>();
+    if survived {
+        if let Some(hc) = ecs.write_storage::<HungerClock>().get_mut(target) {
+            hc.duration = NUTRITION_AFTER_VOMITING;
+        }
+        if is_player {
+            gamelog::Logger::new().append(CHOKE).append(CHOKE_VOMIT).log();
+        }
+    } else {
+        if is_player {
+            gamelog::Logger::new().append(CHOKE).log();
+        }
+        let hp = if let Some(pools) = ecs.read_storage::<Pools>().get(target) {
+            pools.hit_points.current
+        } else {
+            0
+        };
+        add_effect(None, EffectType::Damage { amount: hp, damage_type: DamageType::Forced }, Targets::Entity {
+            target,
+        });
     }
 }
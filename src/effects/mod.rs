@@ -6,14 +6,16 @@ use std::collections::VecDeque;
 use std::sync::Mutex;
 use crate::components::*;
 
+mod abilities;
 mod damage;
+mod falling;
 mod hunger;
 mod particles;
 mod targeting;
 mod triggers;
 mod intrinsics;
 
-pub use targeting::aoe_tiles;
+pub use targeting::{ aoe_tiles, cone_tiles };
 
 lazy_static! {
     pub static ref EFFECT_QUEUE: Mutex<VecDeque<EffectSpawner>> = Mutex::new(VecDeque::new());
@@ -58,6 +60,10 @@ pub enum EffectType {
     TriggerFire {
         trigger: Entity,
     },
+    AbilityUse {
+        ability: usize,
+    },
+    Fall,
 }
 
 #[derive(Clone)]
@@ -123,6 +129,9 @@ fn target_applicator(ecs: &mut World, effect: &EffectSpawner) {
     } else if let EffectType::TriggerFire { trigger } = effect.effect_type {
         triggers::trigger(effect.source, trigger, &effect.target, ecs);
         return;
+    } else if let EffectType::AbilityUse { ability } = effect.effect_type {
+        abilities::ability_trigger(effect.source, ability, &effect.target, ecs);
+        return;
     }
     // Otherwise, just match the effect and enact it directly.
     match &effect.target {
@@ -138,9 +147,12 @@ fn target_applicator(ecs: &mut World, effect: &EffectSpawner) {
 /// Runs an effect on a given tile index
 fn affect_tile(ecs: &mut World, effect: &EffectSpawner, target: usize) {
     if tile_effect_hits_entities(&effect.effect_type) {
-        spatial::for_each_tile_content(target, |entity| {
+        // Gathered up first, so effects that move things (i.e. falling) can update the index.
+        let mut entities: Vec<Entity> = Vec::new();
+        spatial::for_each_tile_content(target, |entity| entities.push(entity));
+        for entity in entities {
             affect_entity(ecs, effect, entity);
-        });
+        }
     }
 
     match &effect.effect_type {
@@ -158,6 +170,7 @@ fn tile_effect_hits_entities(effect: &EffectType) -> bool {
         EffectType::ModifyNutrition { .. } => true,
         EffectType::Confusion { .. } => true,
         EffectType::AddIntrinsic { .. } => true,
+        EffectType::Fall => true,
         _ => false,
     }
 }
@@ -181,6 +194,7 @@ fn affect_entity(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
         EffectType::EntityDeath => damage::entity_death(ecs, effect, target),
         EffectType::ModifyNutrition { .. } => hunger::modify_nutrition(ecs, effect, target),
         EffectType::AddIntrinsic { .. } => intrinsics::add_intrinsic(ecs, effect, target),
+        EffectType::Fall => falling::fall(ecs, effect, target),
         _ => {}
     }
 }
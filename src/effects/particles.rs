@@ -153,7 +153,7 @@ pub fn handle_line_particles(ecs: &World, entity: Entity, target: &Targets) {
     }
 }
 
-fn spawn_line_particles(ecs: &World, start: i32, end: i32, part: &SpawnParticleLine) {
+pub fn spawn_line_particles(ecs: &World, start: i32, end: i32, part: &SpawnParticleLine) {
     let map = ecs.fetch::<Map>();
     let start_pt = Point::new(start % map.width, start / map.width);
     let end_pt = Point::new(end % map.width, end / map.width);
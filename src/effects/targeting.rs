@@ -20,6 +20,28 @@ pub fn aoe_tiles(map: &Map, target: Point, radius: i32) -> Vec<usize> {
     result
 }
 
+/// Tiles in a cone spreading out from origin towards target, up to range away.
+pub fn cone_tiles(map: &Map, origin: Point, target: Point, range: i32) -> Vec<usize> {
+    let (dx, dy) = ((target.x - origin.x) as f32, (target.y - origin.y) as f32);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return Vec::new();
+    }
+    let mut result = Vec::new();
+    for t in field_of_view(origin, range, &*map).iter() {
+        if t.x < 1 || t.x > map.width - 2 || t.y < 1 || t.y > map.height - 2 || *t == origin {
+            continue;
+        }
+        let (tx, ty) = ((t.x - origin.x) as f32, (t.y - origin.y) as f32);
+        let distance = (tx * tx + ty * ty).sqrt();
+        // Within 45 degrees either side of the line to the target.
+        if distance <= (range as f32) && (tx * dx + ty * dy) / (distance * length) >= 0.7 {
+            result.push(map.xy_idx(t.x, t.y));
+        }
+    }
+    result
+}
+
 pub fn find_item_position(ecs: &World, target: Entity) -> Option<i32> {
     let positions = ecs.read_storage::<Position>();
     let map = ecs.fetch::<Map>();
@@ -1,33 +1,46 @@
-use super::{ add_effect, particles, spatial, EffectType, Entity, Targets, World };
+use super::{ add_effect, particles, spatial, targeting, EffectType, Entity, Targets, World };
 use crate::{
     gamelog,
     gui::item_colour_ecs,
     gui::obfuscate_name_ecs,
     gui::renderable_colour,
+    ArmourClassBonus,
     Beatitude,
     Charges,
     Confusion,
     Consumable,
+    Corpse,
+    DamageType,
     Destructible,
+    Digger,
+    Enchantment,
+    EquipmentChanged,
     Equipped,
     Hidden,
     InBackpack,
     InflictsDamage,
     Item,
     MagicMapper,
+    Map,
     MasterDungeonMap,
     Name,
     ObfuscatedName,
     Player,
     Prop,
+    ProvidesEnchantArmour,
+    ProvidesEnchantWeapon,
     ProvidesHealing,
     ProvidesIdentify,
     ProvidesNutrition,
     ProvidesRemoveCurse,
     RandomNumberGenerator,
     Renderable,
+    RotState,
     RunState,
     SingleActivation,
+    Stackable,
+    TileType,
+    Trapdoor,
     BUC,
     GrantsSpell,
     KnownSpells,
@@ -35,8 +48,10 @@ use crate::{
     Viewshed,
     WantsToRemoveKey,
     WantsToDelete,
+    has_level_below,
 };
 use crate::data::messages::*;
+use crate::data::entity::{ CORPSE_INTRINSIC_ONE_IN, MAX_SAFE_ENCHANTMENT };
 use bracket_lib::prelude::*;
 use specs::prelude::*;
 pub fn item_trigger(source: Option<Entity>, item: Entity, target: &Targets, ecs: &mut World) {
@@ -57,8 +72,14 @@ pub fn item_trigger(source: Option<Entity>, item: Entity, target: &Targets, ecs:
     }
     // Use the item via the generic system
     let did_something = event_trigger(source, item, target, ecs);
-    // If it's a consumable, delete it
+    // If it's a consumable, use one up - deleting it if it was the last of the stack.
     if did_something && ecs.read_storage::<Consumable>().get(item).is_some() {
+        if let Some(stack) = ecs.write_storage::<Stackable>().get_mut(item) {
+            if stack.quantity > 1 {
+                stack.quantity -= 1;
+                return;
+            }
+        }
         let mut removekey = ecs.write_storage::<WantsToRemoveKey>();
         removekey.insert(item, WantsToRemoveKey {}).expect("Unable to insert WantsToRemoveKey");
         let mut delete = ecs.write_storage::<WantsToDelete>();
@@ -112,10 +133,13 @@ fn event_trigger(
     let (logger, granted_spell) = handle_grant_spell(ecs, &mut event, logger);
     let (logger, removed_curse) = handle_remove_curse(ecs, &mut event, logger);
     let (logger, identified) = handle_identify(ecs, &mut event, logger);
+    let (logger, enchanted_weapon) = handle_enchant_weapon(ecs, &mut event, logger);
+    let (logger, enchanted_armour) = handle_enchant_armour(ecs, &mut event, logger);
     let (logger, healed) = handle_healing(ecs, &mut event, logger);
     let (logger, damaged) = handle_damage(ecs, &mut event, logger);
     let (logger, confused) = handle_confusion(ecs, &mut event, logger);
-    //let (logger, dug) = handle_dig(ecs, &mut event, logger); -- NYI i.e. Wand of Digging
+    let (logger, dug) = handle_dig(ecs, &mut event, logger);
+    let (logger, opened_trapdoor) = handle_trapdoor(ecs, &mut event, logger);
     did_something |=
         restored_nutrition ||
         magic_mapped ||
@@ -124,7 +148,11 @@ fn event_trigger(
         damaged ||
         confused ||
         removed_curse ||
-        identified;
+        identified ||
+        enchanted_weapon ||
+        enchanted_armour ||
+        dug ||
+        opened_trapdoor;
 
     if event.log {
         logger.log();
@@ -138,24 +166,85 @@ fn handle_restore_nutrition(
     event: &mut EventInfo,
     mut logger: gamelog::Logger
 ) -> (gamelog::Logger, bool) {
-    if ecs.read_storage::<ProvidesNutrition>().get(event.entity).is_some() {
-        let amount = match event.buc {
-            BUC::Blessed => 600,
-            BUC::Uncursed => 400,
-            BUC::Cursed => 200,
-        };
-        add_effect(event.source, EffectType::ModifyNutrition { amount }, event.target.clone());
-        logger = logger
-            .append(NUTRITION)
-            .colour(item_colour_ecs(ecs, event.entity))
-            .append_n(obfuscate_name_ecs(ecs, event.entity).0)
-            .colour(WHITE)
-            .period()
-            .buc(event.buc.clone(), Some(NUTRITION_CURSED), Some(NUTRITION_BLESSED));
-        event.log = true;
-        return (logger, true);
+    let nutrition = if
+        let Some(nutrition) = ecs.read_storage::<ProvidesNutrition>().get(event.entity)
+    {
+        nutrition.amount
+    } else {
+        return (logger, false);
+    };
+    if ecs.read_storage::<Corpse>().get(event.entity).is_some() {
+        return handle_eat_corpse(ecs, event, logger, nutrition);
     }
-    return (logger, false);
+    let amount = match event.buc {
+        BUC::Blessed => (nutrition * 3) / 2,
+        BUC::Uncursed => nutrition,
+        BUC::Cursed => nutrition / 2,
+    };
+    add_effect(event.source, EffectType::ModifyNutrition { amount }, event.target.clone());
+    logger = logger
+        .append(NUTRITION)
+        .colour(item_colour_ecs(ecs, event.entity))
+        .append_n(obfuscate_name_ecs(ecs, event.entity).0)
+        .colour(WHITE)
+        .period()
+        .buc(event.buc.clone(), Some(NUTRITION_CURSED), Some(NUTRITION_BLESSED));
+    event.log = true;
+    return (logger, true);
+}
+
+fn handle_eat_corpse(
+    ecs: &mut World,
+    event: &mut EventInfo,
+    mut logger: gamelog::Logger,
+    nutrition: i32
+) -> (gamelog::Logger, bool) {
+    let corpse = ecs.read_storage::<Corpse>().get(event.entity).unwrap().clone();
+    let state = corpse.rot_state();
+    let amount = match state {
+        RotState::Fresh => nutrition,
+        RotState::Old => nutrition / 2,
+        RotState::Rotten => nutrition / 4,
+    };
+    add_effect(event.source, EffectType::ModifyNutrition { amount }, event.target.clone());
+    logger = logger
+        .append(NUTRITION)
+        .colour(item_colour_ecs(ecs, event.entity))
+        .append_n(obfuscate_name_ecs(ecs, event.entity).0)
+        .colour(WHITE)
+        .period();
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    if state == RotState::Rotten {
+        logger = logger.append(CORPSE_ROTTEN);
+        if rng.roll_dice(1, 2) == 1 {
+            add_effect(
+                event.source,
+                EffectType::Confusion { turns: rng.roll_dice(2, 4) },
+                event.target.clone()
+            );
+        } else {
+            add_effect(
+                event.source,
+                EffectType::Damage { amount: rng.roll_dice(1, 6), damage_type: DamageType::Forced },
+                event.target.clone()
+            );
+        }
+    }
+    if corpse.poisonous {
+        logger = logger.append(CORPSE_POISONOUS);
+        add_effect(
+            event.source,
+            EffectType::Damage { amount: rng.roll_dice(1, 8), damage_type: DamageType::Poison },
+            event.target.clone()
+        );
+    }
+    if let Some(intrinsic) = corpse.intrinsic {
+        if state != RotState::Rotten && rng.roll_dice(1, CORPSE_INTRINSIC_ONE_IN) == 1 {
+            add_effect(event.source, EffectType::AddIntrinsic { intrinsic }, event.target.clone());
+        }
+    }
+    event.log = true;
+    return (logger, true);
 }
 
 fn handle_magic_mapper(
@@ -313,6 +402,98 @@ fn handle_confusion(
     return (logger, false);
 }
 
+/// Digging through a wall clears it away, and digging into the floor leaves a hole through
+/// to the level below, which anything standing there falls down. Zapping a digger at your
+/// own feet is the quickest way down.
+fn handle_dig(
+    ecs: &mut World,
+    event: &mut EventInfo,
+    mut logger: gamelog::Logger
+) -> (gamelog::Logger, bool) {
+    if ecs.read_storage::<Digger>().get(event.entity).is_none() {
+        return (logger, false);
+    }
+    let targets: Vec<usize> = match &event.target {
+        Targets::Tile { target } => vec![*target],
+        Targets::TileList { targets } => targets.clone(),
+        _ => {
+            return (logger, false);
+        }
+    };
+    let source_idx = event.source.and_then(|source| targeting::entity_position(ecs, source));
+    let mut holes: Vec<usize> = Vec::new();
+    let (mut dug_through, mut too_hard) = (false, false);
+    {
+        let mut map = ecs.write_resource::<Map>();
+        let below = has_level_below(&map);
+        for idx in targets {
+            let (x, y) = ((idx as i32) % map.width, (idx as i32) / map.width);
+            let edge = x < 1 || y < 1 || x > map.width - 2 || y > map.height - 2;
+            match map.tiles[idx] {
+                TileType::Wall if !edge => {
+                    map.tiles[idx] = TileType::Floor;
+                    dug_through = true;
+                }
+                tile if diggable(tile) && below => {
+                    map.tiles[idx] = TileType::Chasm;
+                    holes.push(idx);
+                }
+                tile if diggable(tile) => {
+                    too_hard = true;
+                }
+                _ => {}
+            }
+        }
+    }
+    if dug_through {
+        logger = logger.append(DIG_THROUGH);
+    }
+    if too_hard {
+        logger = logger.append(DIG_TOO_HARD);
+    }
+    if source_idx.map_or(false, |idx| holes.contains(&idx)) {
+        logger = logger.append(DIG_DOWN);
+    } else if !holes.is_empty() {
+        logger = logger.append(DIG_HOLE);
+    }
+    for idx in holes.iter() {
+        add_effect(event.source, EffectType::Fall, Targets::Tile { target: *idx });
+    }
+    if dug_through || too_hard || !holes.is_empty() {
+        event.log = true;
+    }
+    return (logger, true);
+}
+
+/// Ground that a digger can make a hole in. Stairs and the like are left alone.
+fn diggable(tile: TileType) -> bool {
+    matches!(
+        tile,
+        TileType::Floor |
+            TileType::WoodFloor |
+            TileType::Gravel |
+            TileType::Road |
+            TileType::Grass |
+            TileType::Foliage |
+            TileType::HeavyFoliage |
+            TileType::Sand |
+            TileType::Ice |
+            TileType::ThinFloor
+    )
+}
+
+fn handle_trapdoor(
+    ecs: &mut World,
+    event: &mut EventInfo,
+    logger: gamelog::Logger
+) -> (gamelog::Logger, bool) {
+    if ecs.read_storage::<Trapdoor>().get(event.entity).is_some() {
+        add_effect(event.source, EffectType::Fall, event.target.clone());
+        return (logger, true);
+    }
+    return (logger, false);
+}
+
 fn select_single(ecs: &World, runstate: RunState) {
     let mut new_runstate = ecs.fetch_mut::<RunState>();
     *new_runstate = runstate;
@@ -337,6 +518,7 @@ fn handle_identify(
         }
         let mut to_identify: Vec<(Entity, String)> = Vec::new();
         let mut beatitudes = ecs.write_storage::<Beatitude>();
+        let mut enchantments = ecs.write_storage::<Enchantment>();
         let obfuscated = ecs.read_storage::<ObfuscatedName>();
         for (e, _i, _bp, name) in (
             &ecs.entities(),
@@ -353,9 +535,16 @@ fn handle_identify(
                     .get(event.source.unwrap())
                     .map(|b| b.known)
                     .unwrap_or(true);
+                let known_enchantment = enchantments
+                    .get(*e)
+                    .map(|ench| ench.known)
+                    .unwrap_or(true);
                 let result =
                     in_this_backpack &&
-                    (has_obfuscated_name || !already_identified || !known_beatitude);
+                    (has_obfuscated_name ||
+                        !already_identified ||
+                        !known_beatitude ||
+                        !known_enchantment);
                 return result;
             }) {
             to_identify.push((e, name.name.clone()));
@@ -365,6 +554,9 @@ fn handle_identify(
             if let Some(beatitude) = beatitudes.get_mut(item.0) {
                 beatitude.known = true;
             }
+            if let Some(enchantment) = enchantments.get_mut(item.0) {
+                enchantment.known = true;
+            }
         }
         logger = logger
             .append(IDENTIFY_ALL)
@@ -439,6 +631,97 @@ fn handle_remove_curse(
     return (logger, false);
 }
 
+fn handle_enchant_weapon(
+    ecs: &mut World,
+    event: &mut EventInfo,
+    logger: gamelog::Logger
+) -> (gamelog::Logger, bool) {
+    if ecs.read_storage::<ProvidesEnchantWeapon>().get(event.entity).is_some() {
+        let weapon = (&ecs.entities(), &ecs.read_storage::<Equipped>())
+            .join()
+            .filter(|(_e, eq)| eq.owner == event.source.unwrap() && eq.slot.holds_weapon())
+            .map(|(e, _eq)| e)
+            .next();
+        return (enchant_item(ecs, event, logger, weapon, ENCHANT_NO_WEAPON), true);
+    }
+    return (logger, false);
+}
+
+fn handle_enchant_armour(
+    ecs: &mut World,
+    event: &mut EventInfo,
+    logger: gamelog::Logger
+) -> (gamelog::Logger, bool) {
+    if ecs.read_storage::<ProvidesEnchantArmour>().get(event.entity).is_some() {
+        let worn: Vec<Entity> = (
+            &ecs.entities(),
+            &ecs.read_storage::<Equipped>(),
+            &ecs.read_storage::<ArmourClassBonus>(),
+        )
+            .join()
+            .filter(|(_e, eq, _ac)| eq.owner == event.source.unwrap())
+            .map(|(e, _eq, _ac)| e)
+            .collect();
+        // Pick one piece of worn armour at random.
+        let armour = if worn.is_empty() {
+            None
+        } else {
+            let roll = ecs.write_resource::<RandomNumberGenerator>().roll_dice(1, worn.len() as i32);
+            Some(worn[(roll - 1) as usize])
+        };
+        return (enchant_item(ecs, event, logger, armour, ENCHANT_NO_ARMOUR), true);
+    }
+    return (logger, false);
+}
+
+/// Changes the enchantment of an item by an amount based on the beatitude of the scroll.
+/// Trying to raise the enchantment of an already heavily-enchanted item destroys it.
+fn enchant_item(
+    ecs: &mut World,
+    event: &mut EventInfo,
+    mut logger: gamelog::Logger,
+    item: Option<Entity>,
+    nothing_to_enchant: &str
+) -> gamelog::Logger {
+    event.log = true;
+    let item = if let Some(item) = item {
+        item
+    } else {
+        return logger.append(nothing_to_enchant);
+    };
+    let amount = match event.buc {
+        BUC::Blessed => ecs.write_resource::<RandomNumberGenerator>().roll_dice(1, 2),
+        BUC::Uncursed => 1,
+        BUC::Cursed => -1,
+    };
+    let (current, known) = ecs
+        .read_storage::<Enchantment>()
+        .get(item)
+        .map(|e| (e.amount, e.known))
+        .unwrap_or((0, false));
+    logger = logger
+        .append("Your")
+        .colour(item_colour_ecs(ecs, item))
+        .append_n(obfuscate_name_ecs(ecs, item).0)
+        .colour(WHITE);
+    if amount > 0 && current > MAX_SAFE_ENCHANTMENT {
+        ecs.write_storage::<WantsToRemoveKey>()
+            .insert(item, WantsToRemoveKey {})
+            .expect("Unable to insert WantsToRemoveKey");
+        ecs.write_storage::<WantsToDelete>()
+            .insert(item, WantsToDelete {})
+            .expect("Unable to insert WantsToDelete");
+        ecs.write_storage::<EquipmentChanged>()
+            .insert(event.source.unwrap(), EquipmentChanged {})
+            .expect("Unable to insert EquipmentChanged");
+        return logger.append(ENCHANT_EVAPORATE);
+    }
+    ecs.write_storage::<Enchantment>()
+        .insert(item, Enchantment { amount: current + amount, known })
+        .expect("Unable to insert Enchantment");
+    return logger.append(if amount > 0 { ENCHANT_GLOW } else { ENCHANT_GLOW_CURSED });
+}
+
 fn get_entity_targets(target: &Targets) -> Vec<Entity> {
     let mut entities: Vec<Entity> = Vec::new();
     match target {
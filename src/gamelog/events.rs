@@ -88,6 +88,9 @@ pub fn record_event(event: EVENT) {
                 new_event = format!("Advanced to level {}", new_lvl);
             }
         }
+        EVENT::LevelUpChoice(description) => {
+            new_event = description;
+        }
         EVENT::ChangedFloor(n) => {
             modify_event_count(EVENT::COUNT_CHANGED_FLOOR, 1);
             if VISITED.lock().unwrap().contains(&n) {
@@ -115,6 +118,7 @@ pub fn record_event(event: EVENT) {
         }
         EVENT::Killed(name) => {
             modify_event_count(EVENT::COUNT_KILLED, 1);
+            modify_event_count(EVENT::count_killed(&name), 1);
             if KILLED.lock().unwrap().contains(&name) {
                 significant_event = false;
             } else {
@@ -128,6 +132,18 @@ pub fn record_event(event: EVENT) {
         EVENT::Identified(name) => {
             new_event = format!("Identified {}", crate::gui::with_article(name));
         }
+        EVENT::DialogueFlag(flag) => {
+            // Something said in conversation that the dialogue trees want to remember.
+            modify_event_count(flag, 1);
+            significant_event = false;
+        }
+        EVENT::StartedQuest(quest) => {
+            new_event = format!("Took on a task: {}", quest);
+        }
+        EVENT::CompletedQuest(quest) => {
+            modify_event_count(EVENT::COUNT_QUESTS_COMPLETED, 1);
+            new_event = format!("Completed a task: {}", quest);
+        }
         EVENT::PlayerDied(str) => {
             // Generating the String is handled in the death effect, to avoid passing the ecs here.
             new_event = format!("{}", str);
@@ -1,4 +1,4 @@
-use super::{ Skill, Skills };
+use super::{ Skill, Skills, BUC };
 use crate::gui::{ Ancestry, Class };
 use crate::data::entity;
 use crate::data::char_create::*;
@@ -52,15 +52,87 @@ pub fn mana_at_level(rng: &mut RandomNumberGenerator, intelligence: i32, level:
     return total;
 }
 
-/// Returns the skill bonus for a given skill, or -4 if the skill is not present.
+/// Returns the skill bonus for a given skill, or -4 if the skill is not present. Weapon
+/// skills are on top of the base melee skill, so an untrained one is just no bonus.
 pub fn skill_bonus(skill: Skill, skills: &Skills) -> i32 {
     if skills.skills.contains_key(&skill) {
         return skills.skills[&skill];
+    } else if skill.is_weapon_skill() {
+        return 0;
     } else {
         return -4;
     }
 }
 
+/// Returns how many successful uses it takes to advance a weapon skill past a level.
+pub fn skill_training_needed(level: i32) -> i32 {
+    return entity::SKILL_TRAINING_PER_LEVEL * (level + 1);
+}
+
+/// Trains a weapon skill by one successful use. Returns true if the skill advanced.
+pub fn train_skill(skill: Skill, skills: &mut Skills) -> bool {
+    let level = *skills.skills.get(&skill).unwrap_or(&0);
+    if !skill.is_weapon_skill() || level >= entity::SKILL_MAX_LEVEL {
+        return false;
+    }
+    let progress = skills.training.entry(skill).or_insert(0);
+    *progress += 1;
+    if *progress < skill_training_needed(level) {
+        return false;
+    }
+    skills.training.insert(skill, 0);
+    skills.skills.insert(skill, level + 1);
+    return true;
+}
+
+/// Rolls an enchantment for a newly-spawned equippable item. Cursed items are always
+/// negatively enchanted, and blessed items are more likely to be positively enchanted.
+pub fn roll_enchantment(rng: &mut RandomNumberGenerator, buc: &BUC) -> i32 {
+    match buc {
+        BUC::Cursed => -rng.roll_dice(1, 3),
+        BUC::Uncursed => {
+            if rng.roll_dice(1, 10) == 1 { 1 } else { 0 }
+        }
+        BUC::Blessed => {
+            if rng.roll_dice(1, 2) == 1 { rng.roll_dice(1, 3) } else { 0 }
+        }
+    }
+}
+
+/// Returns the multiplier applied to an item's value for its beatitude.
+fn buc_price_multiplier(buc: &BUC) -> f32 {
+    match buc {
+        BUC::Blessed => 1.25,
+        BUC::Uncursed => 1.0,
+        BUC::Cursed => 0.5,
+    }
+}
+
+/// Returns the multiplier a shopkeeper charges on top of value for a given charisma score.
+fn charisma_price_multiplier(charisma: i32) -> f32 {
+    match charisma {
+        i32::MIN..=5 => 2.0,
+        6..=7 => 1.5,
+        8..=10 => 1.33,
+        11..=15 => 1.0,
+        16..=17 => 0.75,
+        18 => 0.67,
+        _ => 0.5,
+    }
+}
+
+/// Returns the price a shopkeeper asks for an item, based on its value, beatitude, and the buyer's charisma.
+pub fn buy_price(value: f32, buc: &BUC, charisma: i32) -> i32 {
+    let price = value * buc_price_multiplier(buc) * charisma_price_multiplier(charisma);
+    return max(price.round() as i32, 1);
+}
+
+/// Returns the price a shopkeeper offers for an item, based on its value and beatitude.
+pub fn sell_price(value: f32, buc: &BUC) -> i32 {
+    let price = (value * buc_price_multiplier(buc)) / (entity::SELL_PRICE_DIVISOR as f32);
+    return max(price.round() as i32, 1);
+}
+
 /// Roll 4d6 and drop the lowest, for rolling d20-style stats
 #[allow(unused)]
 pub fn roll_4d6(rng: &mut RandomNumberGenerator) -> i32 {
@@ -0,0 +1,127 @@
+use super::{ obfuscate_name_ecs, renderable_colour_ecs };
+use crate::{ gamelog, Ally, AllyCommand, Chasing, Name, Position, Pools, WantsToApproach };
+use crate::data::messages;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum AllyMenuResult {
+    Cancel,
+    NoResponse,
+    Selected {
+        command: AllyCommand,
+    },
+}
+
+/// Returns every ally belonging to the player on the current level.
+pub fn player_allies(ecs: &World) -> Vec<Entity> {
+    let player_entity = ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let allies = ecs.read_storage::<Ally>();
+    let positions = ecs.read_storage::<Position>();
+    (&entities, &allies, &positions)
+        .join()
+        .filter(|(_, ally, _)| ally.owner == *player_entity)
+        .map(|(entity, _, _)| entity)
+        .collect()
+}
+
+/// Lists the player's allies and what they're doing, and asks what they should do next.
+pub fn show_ally_commands(ecs: &World, ctx: &mut BTerm) -> AllyMenuResult {
+    let allies = player_allies(ecs);
+    let ally_components = ecs.read_storage::<Ally>();
+    let names = ecs.read_storage::<Name>();
+    let (x, y) = (3, 12);
+    let height = (allies.len() as i32) + 9;
+    ctx.draw_box(x, y, 36, height, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(x + 3, y, RGB::named(YELLOW), RGB::named(BLACK), " Allies ");
+    ctx.print_color(x + 3, y + height, RGB::named(YELLOW), RGB::named(BLACK), " ESC to cancel ");
+    for (i, ally) in allies.iter().enumerate() {
+        let name = names.get(*ally).map(|n| n.name.clone()).unwrap_or_default();
+        let command = ally_components.get(*ally).map(|a| a.command.string()).unwrap_or_default();
+        let row = y + 2 + (i as i32);
+        let colour = renderable_colour_ecs(ecs, *ally);
+        ctx.print_color(x + 2, row, RGB::named(colour), RGB::named(BLACK), name);
+        ctx.print_color(x + 20, row, RGB::named(GREY), RGB::named(BLACK), command);
+    }
+    let y = y + 3 + (allies.len() as i32);
+    ctx.print_color(x + 2, y, RGB::named(GREEN), RGB::named(BLACK), "Tell them to:");
+    let options = ["follow you", "stay here", "attack something"];
+    for (i, option) in options.iter().enumerate() {
+        let row = y + 2 + (i as i32);
+        ctx.set(x + 2, row, RGB::named(YELLOW), RGB::named(BLACK), 97 + (i as FontCharType));
+        ctx.print(x + 4, row, option);
+    }
+
+    match ctx.key {
+        None => AllyMenuResult::NoResponse,
+        Some(key) =>
+            match key {
+                VirtualKeyCode::Escape => AllyMenuResult::Cancel,
+                VirtualKeyCode::A => AllyMenuResult::Selected { command: AllyCommand::Follow },
+                VirtualKeyCode::B => AllyMenuResult::Selected { command: AllyCommand::Stay },
+                VirtualKeyCode::C => AllyMenuResult::Selected { command: AllyCommand::Attack },
+                _ => AllyMenuResult::NoResponse,
+            }
+    }
+}
+
+/// Orders all of the player's allies to follow, stay, or attack the given target.
+pub fn command_allies(ecs: &mut World, command: AllyCommand, target: Option<Entity>) {
+    let allies = player_allies(ecs);
+    {
+        let mut ally_components = ecs.write_storage::<Ally>();
+        let mut chasing = ecs.write_storage::<Chasing>();
+        let mut wants_to_approach = ecs.write_storage::<WantsToApproach>();
+        for ally in allies.iter() {
+            if let Some(ally_component) = ally_components.get_mut(*ally) {
+                ally_component.command = command;
+            }
+            wants_to_approach.remove(*ally);
+            match target {
+                Some(target) => {
+                    chasing.insert(*ally, Chasing { target }).expect("Unable to insert Chasing");
+                }
+                None => {
+                    chasing.remove(*ally);
+                }
+            }
+        }
+    }
+    match (command, target) {
+        (AllyCommand::Attack, Some(target)) => {
+            gamelog::Logger
+                ::new()
+                .append(messages::ALLY_ORDER_ATTACK)
+                .colour(renderable_colour_ecs(ecs, target))
+                .append_n(obfuscate_name_ecs(ecs, target).0)
+                .colour(WHITE)
+                .period()
+                .log();
+        }
+        (AllyCommand::Stay, _) => {
+            gamelog::Logger::new().append(messages::ALLY_ORDER_STAY).period().log();
+        }
+        _ => {
+            gamelog::Logger::new().append(messages::ALLY_ORDER_FOLLOW).period().log();
+        }
+    }
+}
+
+/// Returns whatever's at a targeted tile that the player's allies could be told to attack.
+pub fn ally_target_at(ecs: &World, target: Point) -> Option<Entity> {
+    let player_entity = ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let positions = ecs.read_storage::<Position>();
+    let pools = ecs.read_storage::<Pools>();
+    let allies = ecs.read_storage::<Ally>();
+    (&entities, &positions, &pools)
+        .join()
+        .find(|(entity, pos, _)| {
+            pos.x == target.x &&
+                pos.y == target.y &&
+                *entity != *player_entity &&
+                allies.get(*entity).is_none()
+        })
+        .map(|(entity, _, _)| entity)
+}
@@ -11,7 +11,9 @@ use super::{
 use crate::data::entity;
 use crate::data::char_create::*;
 use crate::{
+    map::dungeon,
     raws,
+    spawner,
     Attribute,
     Energy,
     HasAncestry,
@@ -19,6 +21,7 @@ use crate::{
     KnownSpell,
     KnownSpells,
     Pool,
+    Purse,
     Skill,
     Skills,
     Telepath,
@@ -75,25 +78,29 @@ lazy_static! {
             vec![
                 format!("a longsword, ring mail, and {} food", FIGHTER_STARTING_FOOD),
                 "10 str, 8 dex, 10 con, 6 int, 6 wis, 8 cha".to_string(),
-                "and 27 random stat points".to_string()]);
+                "and 27 random stat points".to_string(),
+                "training with blades, axes, and shields".to_string()]);
         m.insert(
             "rogue".to_string(),
             vec![
                 format!("a rapier, leather armour, and {} food", ROGUE_STARTING_FOOD),
                 "8 str, 10 dex, 8 con, 6 int, 8 wis, 10 cha".to_string(),
-                "and 35 random stat points".to_string()]);
+                "and 35 random stat points".to_string(),
+                "expertise with short blades, and training with long blades".to_string()]);
         m.insert(
             "wizard".to_string(),
             vec![
                 format!("a dagger, random scrolls/potions, and {} food", WIZARD_STARTING_FOOD),
                 "6 str, 8 dex, 6 con, 10 int, 10 wis, 8 cha".to_string(),
-                "and 17 random stat points".to_string()]);
+                "and 17 random stat points".to_string(),
+                "training with short blades and polearms".to_string()]);
         m.insert(
             "villager".to_string(),
             vec![
                 format!("the first weapon you could find, and {} food", VILLAGER_STARTING_FOOD),
                 "6 str, 6 dex, 6 con, 6 int, 6 wis, 6 cha".to_string(),
-                "and 39 random stat points".to_string()]);
+                "and 39 random stat points".to_string(),
+                "training with polearms and your fists".to_string()]);
         return m;
     };
 }
@@ -258,7 +265,7 @@ pub fn setup_player_ancestry(ecs: &mut World, ancestry: Ancestry) {
         skills
     } else {
         skills
-            .insert(*player, Skills { skills: HashMap::new() })
+            .insert(*player, Skills { skills: HashMap::new(), training: HashMap::new() })
             .expect("Unable to insert skills component");
         skills.get_mut(*player).unwrap()
     };
@@ -373,6 +380,16 @@ pub fn setup_player_class(ecs: &mut World, class: Class, ancestry: Ancestry) {
             .expect("Unable to insert pools component");
     }
 
+    // SKILLS
+    {
+        let mut skills = ecs.write_storage::<Skills>();
+        if let Some(player_skills) = skills.get_mut(player) {
+            for (skill, level) in get_starting_skills(class).iter() {
+                player_skills.skills.insert(*skill, *level);
+            }
+        }
+    }
+
     // TODO: use seeded RNG here
     let mut rng = RandomNumberGenerator::new();
     let starts_with = get_starting_inventory(class, &mut rng);
@@ -397,6 +414,51 @@ pub fn setup_player_class(ecs: &mut World, class: Class, ancestry: Ancestry) {
             0
         );
     }
+    let gold = parse_dice_string(get_starting_gold(class)).expect("Error parsing dice");
+    ecs.write_storage::<Purse>()
+        .insert(player, Purse { gold: rng.roll_dice(gold.n_dice, gold.die_type) + gold.bonus })
+        .expect("Unable to insert Purse");
+
+    // PET
+    let player_pos = *ecs.fetch::<Point>();
+    let pet = raws::spawn_named_mob(
+        &raws::RAWS.lock().unwrap(),
+        ecs,
+        get_starting_pet(class),
+        raws::SpawnType::AtPosition { x: player_pos.x, y: player_pos.y },
+        0
+    );
+    if let Some(pet) = pet {
+        spawner::make_ally(ecs, pet, player);
+        dungeon::place_allies(ecs, &[pet]);
+    }
+}
+
+fn get_starting_pet(class: Class) -> &'static str {
+    match class {
+        Class::Fighter => FIGHTER_STARTING_PET,
+        Class::Rogue => ROGUE_STARTING_PET,
+        Class::Wizard => WIZARD_STARTING_PET,
+        Class::Villager => VILLAGER_STARTING_PET,
+    }
+}
+
+fn get_starting_gold(class: Class) -> &'static str {
+    match class {
+        Class::Fighter => FIGHTER_STARTING_GOLD,
+        Class::Rogue => ROGUE_STARTING_GOLD,
+        Class::Wizard => WIZARD_STARTING_GOLD,
+        Class::Villager => VILLAGER_STARTING_GOLD,
+    }
+}
+
+fn get_starting_skills(class: Class) -> &'static [(Skill, i32)] {
+    match class {
+        Class::Fighter => FIGHTER_STARTING_SKILLS,
+        Class::Rogue => ROGUE_STARTING_SKILLS,
+        Class::Wizard => WIZARD_STARTING_SKILLS,
+        Class::Villager => VILLAGER_STARTING_SKILLS,
+    }
 }
 
 fn get_starting_inventory(
@@ -0,0 +1,139 @@
+use super::{ item_colour_ecs, obfuscate_name_ecs, quantity_name, letter_to_option };
+use crate::{ Entity, InBackpack, InContainer, Key, Name, Renderable, states::state::* };
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ContainerMode {
+    Take,
+    Put,
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ContainerMenuResult {
+    Cancel,
+    NoResponse,
+    SwitchMode,
+    Selected,
+}
+
+struct ContainerLine {
+    entity: Entity,
+    idx: usize,
+    glyph: FontCharType,
+    glyph_colour: RGB,
+    name: String,
+    name_colour: RGB,
+}
+
+fn container_lines(ecs: &World, container: Entity, mode: ContainerMode) -> Vec<ContainerLine> {
+    let player_entity = ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let renderables = ecs.read_storage::<Renderable>();
+    let mut candidates: Vec<(Entity, usize)> = Vec::new();
+    match mode {
+        ContainerMode::Take => {
+            let in_container = ecs.read_storage::<InContainer>();
+            let names = ecs.read_storage::<Name>();
+            let mut contents: Vec<(Entity, String)> = (&entities, &in_container, &names)
+                .join()
+                .filter(|(_e, c, _n)| c.owner == container)
+                .map(|(e, _c, n)| (e, n.name.clone()))
+                .collect();
+            contents.sort_by(|a, b| a.1.cmp(&b.1));
+            for (i, (e, _n)) in contents.into_iter().enumerate() {
+                candidates.push((e, i));
+            }
+        }
+        ContainerMode::Put => {
+            let backpack = ecs.read_storage::<InBackpack>();
+            let keys = ecs.read_storage::<Key>();
+            for (e, bp, key) in (&entities, &backpack, &keys).join() {
+                if bp.owner == *player_entity && e != container {
+                    candidates.push((e, key.idx));
+                }
+            }
+            candidates.sort_by(|a, b| a.1.cmp(&b.1));
+        }
+    }
+    let mut lines: Vec<ContainerLine> = Vec::new();
+    for (entity, idx) in candidates {
+        let (glyph, glyph_colour) = if let Some(renderable) = renderables.get(entity) {
+            (renderable.glyph, renderable.fg)
+        } else {
+            (to_cp437('?'), RGB::named(WHITE))
+        };
+        lines.push(ContainerLine {
+            entity,
+            idx,
+            glyph,
+            glyph_colour,
+            name: quantity_name(ecs, entity),
+            name_colour: RGB::named(item_colour_ecs(ecs, entity)),
+        });
+    }
+    return lines;
+}
+
+/// Handles the take-out/put-in menu for a container, like a chest or a sack.
+pub fn show_container(
+    gs: &mut State,
+    ctx: &mut BTerm,
+    container: Entity,
+    mode: ContainerMode
+) -> (ContainerMenuResult, Option<Entity>) {
+    let lines = container_lines(&gs.ecs, container, mode);
+    let container_name = obfuscate_name_ecs(&gs.ecs, container).0;
+    let (x_offset, y_offset) = (1, 10);
+    let title = match mode {
+        ContainerMode::Take => format!("Take what out of the {}? [aA-zZ][Tab. to put in][Esc.]", container_name),
+        ContainerMode::Put => format!("Put what into the {}? [aA-zZ][Tab. to take out][Esc.]", container_name),
+    };
+    ctx.print_color(1 + x_offset, 1 + y_offset, RGB::named(WHITE), RGB::named(BLACK), title);
+
+    let x = 1 + x_offset;
+    let mut y = 3 + y_offset;
+    let name_width = lines
+        .iter()
+        .map(|l| l.name.len() as i32)
+        .max()
+        .unwrap_or(0);
+    let width = i32::max(name_width + 6, 24);
+    let count = i32::max(lines.len() as i32, 1);
+    ctx.draw_box(x, y, width, count + 1, RGB::named(WHITE), RGB::named(BLACK));
+    y += 1;
+    if lines.is_empty() {
+        let text = match mode {
+            ContainerMode::Take => "It's empty.",
+            ContainerMode::Put => "Nothing to put in.",
+        };
+        ctx.print_color(x + 1, y, RGB::named(GREY), RGB::named(BLACK), text);
+    }
+    for line in lines.iter() {
+        let letter = if line.idx < 26 { 97 + line.idx } else { 65 - 26 + line.idx };
+        ctx.set(x + 1, y, RGB::named(YELLOW), RGB::named(BLACK), letter as FontCharType);
+        ctx.set(x + 3, y, line.glyph_colour, RGB::named(BLACK), line.glyph);
+        ctx.print_color(x + 5, y, line.name_colour, RGB::named(BLACK), &line.name);
+        y += 1;
+    }
+
+    match ctx.key {
+        None => (ContainerMenuResult::NoResponse, None),
+        Some(key) =>
+            match key {
+                VirtualKeyCode::Escape => (ContainerMenuResult::Cancel, None),
+                VirtualKeyCode::Tab => (ContainerMenuResult::SwitchMode, None),
+                _ => {
+                    let selection = letter_to_option::letter_to_option(key, ctx.shift);
+                    if selection != -1 {
+                        for line in lines.iter() {
+                            if line.idx == (selection as usize) {
+                                return (ContainerMenuResult::Selected, Some(line.entity));
+                            }
+                        }
+                    }
+                    (ContainerMenuResult::NoResponse, None)
+                }
+            }
+    }
+}
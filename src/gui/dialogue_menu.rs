@@ -0,0 +1,312 @@
+use super::{ letter_to_option, obfuscate_name_ecs, renderable_colour_ecs, with_article };
+use crate::{
+    effects::{ add_effect, EffectType, Targets },
+    gamelog,
+    quests::{ self, QuestLog, QuestStatus },
+    raws::{ self, DialogueActions, DialogueConditions, DialogueTree, RawMaster, SpawnType },
+    raws::Reaction,
+    Dialogue,
+    Faction,
+    HasAncestry,
+    Map,
+    Pools,
+    Stackable,
+    WantsToDelete,
+    WantsToRemoveKey,
+};
+use crate::data::events::EVENT;
+use crate::data::messages;
+use crate::map::TileType;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+const DIALOGUE_WIDTH: i32 = 50;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum DialogueResult {
+    Cancel,
+    NoResponse,
+    Selected {
+        option: usize,
+    },
+}
+
+/// Returns the id of the dialogue tree an entity talks from, if they have one.
+fn speaker_tree(ecs: &World, speaker: Entity) -> Option<String> {
+    ecs.read_storage::<Dialogue>()
+        .get(speaker)
+        .map(|d| d.tree.clone())
+}
+
+/// Finds something the player is carrying by its raw id.
+fn find_carried(ecs: &World, raws: &RawMaster, id: &str) -> Option<Entity> {
+    quests::find_carried(ecs, &raws::get_item_name(raws, id)?)
+}
+
+fn conditions_met(
+    ecs: &World,
+    raws: &RawMaster,
+    speaker: Entity,
+    conditions: &DialogueConditions
+) -> bool {
+    let player_entity = *ecs.fetch::<Entity>();
+    if let Some(min_level) = conditions.min_level {
+        let level = ecs
+            .read_storage::<Pools>()
+            .get(player_entity)
+            .map(|p| p.level)
+            .unwrap_or(1);
+        if level < min_level {
+            return false;
+        }
+    }
+    if let Some(item) = &conditions.has_item {
+        if find_carried(ecs, raws, item).is_none() {
+            return false;
+        }
+    }
+    if let Some(events) = &conditions.events_min {
+        if events.iter().any(|(event, n)| gamelog::get_event_count(event) < *n) {
+            return false;
+        }
+    }
+    if let Some(events) = &conditions.events_max {
+        if events.iter().any(|(event, n)| gamelog::get_event_count(event) > *n) {
+            return false;
+        }
+    }
+    if let Some(reaction) = &conditions.reaction {
+        let wanted = match reaction.as_str() {
+            "attack" => Reaction::Attack,
+            "flee" => Reaction::Flee,
+            _ => Reaction::Ignore,
+        };
+        let actual = raws::get_reactions(
+            speaker,
+            player_entity,
+            &ecs.read_storage::<Faction>(),
+            &ecs.read_storage::<HasAncestry>(),
+            raws
+        );
+        if actual != wanted {
+            return false;
+        }
+    }
+    if let Some(quest) = &conditions.quest {
+        let status = ecs.fetch::<QuestLog>().status(&quest.id);
+        let matches = match quest.status.as_str() {
+            "active" => status == Some(QuestStatus::Active),
+            "completed" => status == Some(QuestStatus::Completed),
+            _ => status.is_none(),
+        };
+        if !matches {
+            return false;
+        }
+    }
+    return true;
+}
+
+/// The options on a node that the player can currently pick, as indices into its options.
+fn available_options(
+    ecs: &World,
+    raws: &RawMaster,
+    speaker: Entity,
+    tree: &DialogueTree,
+    node: usize
+) -> Vec<usize> {
+    tree.nodes[node].options
+        .iter()
+        .enumerate()
+        .filter(|(_, option)| {
+            option.conditions
+                .as_ref()
+                .map(|c| conditions_met(ecs, raws, speaker, c))
+                .unwrap_or(true)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Splits text into lines no longer than width, breaking on spaces.
+pub(super) fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + word.len() + 1 > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Shows what the speaker has to say at this point in the conversation, and the replies
+/// the player has available to them.
+pub fn show_dialogue(ecs: &World, ctx: &mut BTerm, speaker: Entity, node: usize) -> DialogueResult {
+    let raws = &raws::RAWS.lock().unwrap();
+    let tree = match speaker_tree(ecs, speaker).and_then(|id| raws::get_dialogue(raws, &id)) {
+        Some(tree) if node < tree.nodes.len() => tree,
+        _ => {
+            return DialogueResult::Cancel;
+        }
+    };
+    let options = available_options(ecs, raws, speaker, tree, node);
+    let text = wrap_text(&tree.nodes[node].text, (DIALOGUE_WIDTH - 4) as usize);
+
+    let (x, y) = (3, 12);
+    let height = (text.len() as i32) + (options.len() as i32) + 4;
+    ctx.draw_box(x, y, DIALOGUE_WIDTH, height, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(
+        x + 3,
+        y,
+        RGB::named(renderable_colour_ecs(ecs, speaker)),
+        RGB::named(BLACK),
+        format!(" {} ", obfuscate_name_ecs(ecs, speaker).0)
+    );
+    ctx.print_color(x + 3, y + height, RGB::named(YELLOW), RGB::named(BLACK), " ESC to leave ");
+    for (i, line) in text.iter().enumerate() {
+        ctx.print(x + 2, y + 2 + (i as i32), line);
+    }
+    let y = y + 3 + (text.len() as i32);
+    for (i, option) in options.iter().enumerate() {
+        let row = y + (i as i32);
+        ctx.set(x + 2, row, RGB::named(YELLOW), RGB::named(BLACK), 97 + (i as FontCharType));
+        ctx.print_color(
+            x + 4,
+            row,
+            RGB::named(GREEN),
+            RGB::named(BLACK),
+            &tree.nodes[node].options[*option].text
+        );
+    }
+
+    match ctx.key {
+        None => DialogueResult::NoResponse,
+        Some(VirtualKeyCode::Escape) => DialogueResult::Cancel,
+        Some(key) => {
+            let selection = letter_to_option::letter_to_option(key, ctx.shift);
+            if selection >= 0 && (selection as usize) < options.len() {
+                return DialogueResult::Selected { option: options[selection as usize] };
+            }
+            DialogueResult::NoResponse
+        }
+    }
+}
+
+/// Carries out whatever picking an option does, and returns the node the conversation
+/// moves on to - or None, if it's over.
+pub fn choose_dialogue_option(
+    ecs: &mut World,
+    speaker: Entity,
+    node: usize,
+    option: usize
+) -> Option<usize> {
+    let raws = &raws::RAWS.lock().unwrap();
+    let tree = speaker_tree(ecs, speaker).and_then(|id| raws::get_dialogue(raws, &id))?;
+    let option = tree.nodes.get(node)?.options.get(option)?;
+    if let Some(actions) = &option.actions {
+        apply_dialogue_actions(ecs, raws, speaker, actions);
+    }
+    let next = option.next.as_ref()?;
+    tree.nodes.iter().position(|n| n.id == *next)
+}
+
+fn apply_dialogue_actions(
+    ecs: &mut World,
+    raws: &RawMaster,
+    speaker: Entity,
+    actions: &DialogueActions
+) {
+    let player_entity = *ecs.fetch::<Entity>();
+    if let Some(id) = &actions.take_item {
+        if let Some(item) = find_carried(ecs, raws, id) {
+            let name = obfuscate_name_ecs(ecs, item).0;
+            // Hand over one of them, deleting it if it was the last of the stack.
+            let last_of_stack = match ecs.write_storage::<Stackable>().get_mut(item) {
+                Some(stack) if stack.quantity > 1 => {
+                    stack.quantity -= 1;
+                    false
+                }
+                _ => true,
+            };
+            if last_of_stack {
+                ecs.write_storage::<WantsToRemoveKey>()
+                    .insert(item, WantsToRemoveKey {})
+                    .expect("Unable to insert WantsToRemoveKey");
+                ecs.write_storage::<WantsToDelete>()
+                    .insert(item, WantsToDelete {})
+                    .expect("Unable to insert WantsToDelete");
+            }
+            gamelog::Logger
+                ::new()
+                .append(messages::DIALOGUE_TAKES)
+                .append_n(with_article(name))
+                .period()
+                .log();
+        }
+    }
+    if let Some(id) = &actions.give_item {
+        let item = raws::spawn_named_entity(
+            raws,
+            ecs,
+            id,
+            None,
+            SpawnType::Carried { by: player_entity },
+            0
+        );
+        if let Some(item) = item {
+            gamelog::Logger
+                ::new()
+                .append("The")
+                .colour(renderable_colour_ecs(ecs, speaker))
+                .append(obfuscate_name_ecs(ecs, speaker).0)
+                .colour(WHITE)
+                .append(messages::DIALOGUE_GIVES)
+                .append_n(with_article(obfuscate_name_ecs(ecs, item).0))
+                .period()
+                .log();
+        }
+    }
+    if let Some(heal) = &actions.heal {
+        if let Ok(dice) = parse_dice_string(heal) {
+            let amount = ecs
+                .write_resource::<RandomNumberGenerator>()
+                .roll_dice(dice.n_dice, dice.die_type) + dice.bonus;
+            add_effect(
+                Some(speaker),
+                EffectType::Healing { amount, increment_max: false },
+                Targets::Entity { target: player_entity }
+            );
+            gamelog::Logger::new().append(messages::DIALOGUE_HEALED).period().log();
+        }
+    }
+    if let Some(hint) = &actions.hint {
+        gamelog::Logger
+            ::new()
+            .append(messages::DIALOGUE_HINT)
+            .colour(YELLOW)
+            .append(hint)
+            .log();
+    }
+    if actions.reveal_stairs.unwrap_or(false) {
+        let mut map = ecs.fetch_mut::<Map>();
+        for idx in 0..map.tiles.len() {
+            if map.tiles[idx] == TileType::DownStair {
+                map.revealed_tiles[idx] = true;
+            }
+        }
+        gamelog::Logger::new().append(messages::DIALOGUE_REVEAL_STAIRS).period().log();
+    }
+    if let Some(quest) = &actions.start_quest {
+        quests::start_quest(ecs, raws, quest);
+    }
+    if let Some(flag) = &actions.record {
+        gamelog::record_event(EVENT::DialogueFlag(flag.clone()));
+    }
+}
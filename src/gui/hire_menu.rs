@@ -0,0 +1,78 @@
+use super::{ renderable_colour_ecs, YesNoResult };
+use crate::{ gamelog, gamesystem::buy_price, spawner, Attributes, Hireling, Name, Purse, BUC };
+use crate::data::messages;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Returns what it'd cost the player to hire a hireling - charisma helps, as with shopkeepers.
+pub fn hire_price(ecs: &World, hireling: Entity) -> i32 {
+    let player_entity = ecs.fetch::<Entity>();
+    let cost = ecs
+        .read_storage::<Hireling>()
+        .get(hireling)
+        .map(|h| h.cost)
+        .unwrap_or(0);
+    let charisma = ecs
+        .read_storage::<Attributes>()
+        .get(*player_entity)
+        .map(|a| a.charisma.base + a.charisma.modifiers)
+        .unwrap_or(10);
+    return buy_price(cost as f32, &BUC::Uncursed, charisma);
+}
+
+/// Asks the player if they want to hire a hireling they've bumped into.
+pub fn show_hire(ecs: &World, ctx: &mut BTerm, hireling: Entity) -> YesNoResult {
+    let name = ecs
+        .read_storage::<Name>()
+        .get(hireling)
+        .map(|n| n.name.clone())
+        .unwrap_or("hireling".to_string());
+    let (x, y) = (3, 12);
+    let question = format!("Hire the {} for {} gold? [y/n]", name, hire_price(ecs, hireling));
+    ctx.draw_box(x, y, (question.len() as i32) + 3, 2, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print(x + 2, y + 1, question);
+
+    match ctx.key {
+        None => YesNoResult::NoSelection,
+        Some(key) =>
+            match key {
+                VirtualKeyCode::Y => YesNoResult::Yes,
+                VirtualKeyCode::N | VirtualKeyCode::Escape => YesNoResult::No,
+                _ => YesNoResult::NoSelection,
+            }
+    }
+}
+
+/// Pays a hireling from the player's purse, and has them join up - if the player can afford it.
+pub fn try_hire(ecs: &mut World, hireling: Entity) {
+    let player_entity = *ecs.fetch::<Entity>();
+    let price = hire_price(ecs, hireling);
+    let colour = renderable_colour_ecs(ecs, hireling);
+    let name = ecs
+        .read_storage::<Name>()
+        .get(hireling)
+        .map(|n| n.name.clone())
+        .unwrap_or("hireling".to_string());
+    let paid = {
+        let mut purses = ecs.write_storage::<Purse>();
+        match purses.get_mut(player_entity) {
+            Some(purse) if purse.gold >= price => {
+                purse.gold -= price;
+                true
+            }
+            _ => false,
+        }
+    };
+    let message = if paid { messages::HIRE_SUCCESS } else { messages::HIRE_NO_GOLD };
+    gamelog::Logger
+        ::new()
+        .append(message)
+        .colour(colour)
+        .append_n(&name)
+        .colour(WHITE)
+        .period()
+        .log();
+    if paid {
+        spawner::make_ally(ecs, hireling, player_entity);
+    }
+}
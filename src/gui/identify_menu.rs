@@ -4,6 +4,7 @@ use super::{
     obfuscate_name_ecs,
     print_options,
     unique_ecs,
+    quantity_ecs,
     check_key,
     letter_to_option,
     ItemMenuResult,
@@ -11,6 +12,7 @@ use super::{
 use crate::{
     gamelog,
     Beatitude,
+    Enchantment,
     Entity,
     Equipped,
     InBackpack,
@@ -38,6 +40,7 @@ pub fn identify(gs: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Option<Enti
     let names = gs.ecs.read_storage::<Name>();
     let renderables = gs.ecs.read_storage::<Renderable>();
     let beatitudes = gs.ecs.read_storage::<Beatitude>();
+    let enchantments = gs.ecs.read_storage::<Enchantment>();
     let keys = gs.ecs.read_storage::<Key>();
 
     let build_identify_iterator = || {
@@ -67,6 +70,10 @@ pub fn identify(gs: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Option<Enti
                     beatitudes
                         .get(*item_entity)
                         .map(|beatitude| beatitude.known)
+                        .unwrap_or(true) &&
+                    enchantments
+                        .get(*item_entity)
+                        .map(|enchantment| enchantment.known)
                         .unwrap_or(true)
                 {
                     return false;
@@ -98,12 +105,13 @@ pub fn identify(gs: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Option<Enti
     let mut player_inventory: super::PlayerInventory = HashMap::new();
     for (entity, _i, _r, _n, key) in build_identify_iterator() {
         let unique_item = unique_ecs(&gs.ecs, entity);
+        let count = quantity_ecs(&gs.ecs, entity);
         player_inventory
             .entry(unique_item)
             .and_modify(|slot| {
-                slot.count += 1;
+                slot.count += count;
             })
-            .or_insert(super::InventorySlot { item: entity, count: 1, idx: key.idx });
+            .or_insert(super::InventorySlot { item: entity, count, idx: key.idx });
     }
     // Get display args
     let width = get_max_inventory_width(&player_inventory);
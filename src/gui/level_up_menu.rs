@@ -0,0 +1,289 @@
+use super::{ letter_to_option, Ancestry, Class };
+use crate::{
+    gamelog,
+    gamesystem::attr_bonus,
+    Attribute,
+    Attributes,
+    Energy,
+    HasAncestry,
+    HasClass,
+    LevelHistory,
+    LevelUpRecord,
+    Perk,
+    Pools,
+    Skill,
+    Skills,
+    states::state::*,
+};
+use crate::data::char_create::*;
+use crate::data::entity::LEVELS_PER_ATTRIBUTE_POINT;
+use crate::data::events::EVENT;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+const ATTRIBUTE_NAMES: [&str; 6] = [
+    "strength",
+    "dexterity",
+    "constitution",
+    "intelligence",
+    "wisdom",
+    "charisma",
+];
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum LevelUpResult {
+    NoResponse {
+        attribute: Option<usize>,
+    },
+    Selected {
+        attribute: Option<usize>,
+        perk: Perk,
+    },
+}
+
+/// Returns true if reaching this level grants an attribute point.
+pub fn grants_attribute_point(level: i32) -> bool {
+    level % LEVELS_PER_ATTRIBUTE_POINT == 0
+}
+
+fn attribute_base(attributes: &Attributes, idx: usize) -> i32 {
+    match idx {
+        0 => attributes.strength.base,
+        1 => attributes.dexterity.base,
+        2 => attributes.constitution.base,
+        3 => attributes.intelligence.base,
+        4 => attributes.wisdom.base,
+        _ => attributes.charisma.base,
+    }
+}
+
+fn attribute_by_index(attributes: &mut Attributes, idx: usize) -> &mut Attribute {
+    match idx {
+        0 => &mut attributes.strength,
+        1 => &mut attributes.dexterity,
+        2 => &mut attributes.constitution,
+        3 => &mut attributes.intelligence,
+        4 => &mut attributes.wisdom,
+        _ => &mut attributes.charisma,
+    }
+}
+
+/// The attributes the player can still raise, as indices into ATTRIBUTE_NAMES.
+fn raisable_attributes(ecs: &World) -> Vec<usize> {
+    let player_entity = ecs.fetch::<Entity>();
+    let maximums: [i32; 6] = match ecs.read_storage::<HasAncestry>().get(*player_entity) {
+        Some(ancestry) =>
+            match ancestry.name {
+                Ancestry::Human => HUMAN_MAX_ATTR,
+                Ancestry::Elf => ELF_MAX_ATTR,
+                Ancestry::Dwarf => DWARF_MAX_ATTR,
+                Ancestry::Gnome => GNOME_MAX_ATTR,
+                Ancestry::Catfolk => CATFOLK_MAX_ATTR,
+                Ancestry::NULL => UNKNOWN_MAX_ATTR,
+            }
+        None => UNKNOWN_MAX_ATTR,
+    };
+    let attributes = ecs.read_storage::<Attributes>();
+    let mut raisable = Vec::new();
+    if let Some(attributes) = attributes.get(*player_entity) {
+        for (idx, max) in maximums.iter().enumerate() {
+            if attribute_base(attributes, idx) < *max {
+                raisable.push(idx);
+            }
+        }
+    }
+    raisable
+}
+
+/// The level whose choices are being made - the earliest one still waiting on them.
+fn level_being_chosen(ecs: &World) -> i32 {
+    let player_entity = ecs.fetch::<Entity>();
+    let level = ecs
+        .read_storage::<Pools>()
+        .get(*player_entity)
+        .map(|p| p.level)
+        .unwrap_or(1);
+    let pending = ecs
+        .read_storage::<LevelHistory>()
+        .get(*player_entity)
+        .map(|h| h.pending)
+        .unwrap_or(1);
+    level - i32::max(pending, 1) + 1
+}
+
+/// The perks the player's class can pick from, minus any one-off perks already taken.
+fn available_perks(ecs: &World) -> Vec<Perk> {
+    let player_entity = ecs.fetch::<Entity>();
+    let class_perks = match ecs.read_storage::<HasClass>().get(*player_entity) {
+        Some(class) =>
+            match class.name {
+                Class::Fighter => FIGHTER_PERKS,
+                Class::Rogue => ROGUE_PERKS,
+                Class::Wizard => WIZARD_PERKS,
+                Class::Villager => VILLAGER_PERKS,
+            }
+        None => FIGHTER_PERKS,
+    };
+    let history = ecs.read_storage::<LevelHistory>();
+    let taken: Vec<Perk> = history
+        .get(*player_entity)
+        .map(|h| {
+            h.level_ups
+                .iter()
+                .map(|l| l.perk)
+                .collect()
+        })
+        .unwrap_or_default();
+    class_perks
+        .iter()
+        .filter(|perk| perk.repeatable() || !taken.contains(perk))
+        .copied()
+        .collect()
+}
+
+/// Shows the level-up screen. If this level grants an attribute point, that's picked
+/// first, and then a perk. Levelling up can't be cancelled.
+pub fn show_level_up(gs: &mut State, ctx: &mut BTerm, attribute: Option<usize>) -> LevelUpResult {
+    let level = level_being_chosen(&gs.ecs);
+    let raisable = raisable_attributes(&gs.ecs);
+    let picking_attribute =
+        grants_attribute_point(level) && attribute.is_none() && !raisable.is_empty();
+    let perks = available_perks(&gs.ecs);
+
+    let (x, y) = (3, 12);
+    let rows = (if picking_attribute { raisable.len() } else { perks.len() }) as i32;
+    ctx.draw_box(x, y, 50, rows + 5, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(x + 3, y, RGB::named(YELLOW), RGB::named(BLACK), format!(" Level {} ", level));
+    let prompt = if picking_attribute {
+        "Choose an attribute to improve:"
+    } else {
+        "Choose a perk:"
+    };
+    ctx.print_color(x + 2, y + 2, RGB::named(GREEN), RGB::named(BLACK), prompt);
+
+    if picking_attribute {
+        let attributes = gs.ecs.read_storage::<Attributes>();
+        let player_entity = gs.ecs.fetch::<Entity>();
+        let attrs = attributes.get(*player_entity);
+        for (i, idx) in raisable.iter().enumerate() {
+            let current = attrs.map(|a| attribute_base(a, *idx)).unwrap_or(0);
+            let row = y + 4 + (i as i32);
+            ctx.set(x + 2, row, RGB::named(YELLOW), RGB::named(BLACK), 97 + (i as FontCharType));
+            ctx.print(x + 4, row, format!("{} ({})", ATTRIBUTE_NAMES[*idx], current));
+        }
+    } else {
+        for (i, perk) in perks.iter().enumerate() {
+            let row = y + 4 + (i as i32);
+            ctx.set(x + 2, row, RGB::named(YELLOW), RGB::named(BLACK), 97 + (i as FontCharType));
+            ctx.print(x + 4, row, format!("{} - {}", perk.string(), perk.description()));
+        }
+    }
+
+    match ctx.key {
+        None => LevelUpResult::NoResponse { attribute },
+        Some(key) => {
+            let selection = letter_to_option::letter_to_option(key, ctx.shift);
+            if selection < 0 {
+                return LevelUpResult::NoResponse { attribute };
+            }
+            let selection = selection as usize;
+            if picking_attribute {
+                if selection < raisable.len() {
+                    return LevelUpResult::NoResponse { attribute: Some(raisable[selection]) };
+                }
+            } else if selection < perks.len() {
+                return LevelUpResult::Selected { attribute, perk: perks[selection] };
+            }
+            LevelUpResult::NoResponse { attribute }
+        }
+    }
+}
+
+/// Applies the choices made on the level-up screen to the player, and records them.
+/// Returns true if there are more levels still waiting to be chosen for.
+pub fn apply_level_up(ecs: &mut World, attribute: Option<usize>, perk: Perk) -> bool {
+    let player_entity = *ecs.fetch::<Entity>();
+    let level = level_being_chosen(ecs);
+    let mut pools = ecs.write_storage::<Pools>();
+    let mut logger = gamelog::Logger::new();
+    if let Some(idx) = attribute {
+        if let Some(attributes) = ecs.write_storage::<Attributes>().get_mut(player_entity) {
+            let attr = attribute_by_index(attributes, idx);
+            attr.base += 1;
+            attr.bonus = attr_bonus(attr.base + attr.modifiers);
+        }
+        logger = logger
+            .append("Your")
+            .colour(GREEN)
+            .append(ATTRIBUTE_NAMES[idx])
+            .colour(WHITE)
+            .append("improves!");
+    }
+    match perk {
+        Perk::Toughness => {
+            if let Some(pools) = pools.get_mut(player_entity) {
+                pools.hit_points.max += TOUGHNESS_HP;
+                pools.hit_points.current += TOUGHNESS_HP;
+            }
+        }
+        Perk::ArcaneReserves => {
+            if let Some(pools) = pools.get_mut(player_entity) {
+                pools.mana.max += ARCANE_RESERVES_MANA;
+                pools.mana.current += ARCANE_RESERVES_MANA;
+            }
+        }
+        Perk::WeaponTraining | Perk::Guarded | Perk::SpellFocus => {
+            let skill = match perk {
+                Perk::WeaponTraining => Skill::Melee,
+                Perk::Guarded => Skill::Defence,
+                _ => Skill::Magic,
+            };
+            if let Some(skills) = ecs.write_storage::<Skills>().get_mut(player_entity) {
+                *skills.skills.entry(skill).or_insert(0) += 1;
+            }
+        }
+        Perk::FleetFooted => {
+            if let Some(energy) = ecs.write_storage::<Energy>().get_mut(player_entity) {
+                energy.speed += FLEET_FOOTED_SPEED;
+            }
+        }
+    }
+    logger
+        .append("You gain the")
+        .colour(YELLOW)
+        .append(perk.string())
+        .colour(WHITE)
+        .append("perk.")
+        .log();
+    let record = LevelUpRecord {
+        level,
+        perk,
+        attribute: attribute.map(|idx| ATTRIBUTE_NAMES[idx].to_string()),
+    };
+    gamelog::record_event(EVENT::LevelUpChoice(describe_level_up(&record)));
+    let mut history = ecs.write_storage::<LevelHistory>();
+    if let Some(history) = history.get_mut(player_entity) {
+        history.level_ups.push(record);
+        history.pending = i32::max(history.pending - 1, 0);
+        return history.pending > 0;
+    }
+    history
+        .insert(player_entity, LevelHistory { level_ups: vec![record], pending: 0 })
+        .expect("Unable to insert LevelHistory");
+    return false;
+}
+
+/// e.g. "Took toughness at level 4, and raised strength"
+pub fn describe_level_up(record: &LevelUpRecord) -> String {
+    match &record.attribute {
+        Some(attribute) => {
+            format!(
+                "Took {} at level {}, and raised {}",
+                record.perk.string(),
+                record.level,
+                attribute
+            )
+        }
+        None => format!("Took {} at level {}", record.perk.string(), record.level),
+    }
+}
@@ -9,6 +9,7 @@ use super::{
     Beatitude,
     Burden,
     Charges,
+    Enchantment,
     Equipped,
     Hidden,
     HungerClock,
@@ -25,17 +26,19 @@ use super::{
     Pools,
     Position,
     Prop,
+    Purse,
     Renderable,
     RunState,
     states::state::*,
     Skill,
     Skills,
+    Stackable,
     Viewshed,
     BUC,
     Key,
     Item,
     ItemType,
-    data::ids::get_local_col,
+    raws,
 };
 use crate::data::entity::CARRY_CAPACITY_PER_STRENGTH;
 use crate::data::visuals::{
@@ -62,6 +65,24 @@ pub use cheat_menu::*;
 use crate::data::events::*;
 mod farlook;
 pub use farlook::*;
+mod shop_menu;
+pub use shop_menu::*;
+mod container_menu;
+pub use container_menu::*;
+mod quantity_menu;
+pub use quantity_menu::*;
+mod skills_menu;
+pub use skills_menu::*;
+mod level_up_menu;
+pub use level_up_menu::*;
+mod ally_menu;
+pub use ally_menu::*;
+mod hire_menu;
+pub use hire_menu::*;
+mod dialogue_menu;
+pub use dialogue_menu::*;
+mod quest_menu;
+pub use quest_menu::*;
 
 /// Gives a popup box with a message and a title, and waits for a keypress.
 #[allow(unused)]
@@ -127,6 +148,18 @@ fn calc_ac(ecs: &World, skills: &Skills, stats: &Pools, attr: &Attributes) -> i3
             armour_ac_bonus += ac.amount;
         }
     }
+    let wearing_shield = (&equipped)
+        .join()
+        .any(|eq| eq.owner == *player_entity && eq.slot == crate::EquipmentSlot::Shield);
+    if wearing_shield {
+        armour_ac_bonus += gamesystem::skill_bonus(Skill::Shield, skills);
+    }
+    let enchantments = ecs.read_storage::<Enchantment>();
+    for (wielded, _ac, enchantment) in (&equipped, &ac, &enchantments).join() {
+        if wielded.owner == *player_entity {
+            armour_ac_bonus += enchantment.amount;
+        }
+    }
     stats.bac - attr.dexterity.bonus / 2 - skill_ac_bonus - armour_ac_bonus
 }
 
@@ -283,18 +316,21 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
 
         // Draw backpack
         ctx.print_color(72, y, RGB::named(BLACK), RGB::named(WHITE), "Backpack");
-        ctx.print_color(
-            81,
-            y,
-            RGB::named(WHITE),
-            RGB::named(BLACK),
-            &format!(
-                "[{:.1}/{} lbs]",
-                stats.weight,
-                (attributes.strength.base + attributes.strength.modifiers) *
-                    CARRY_CAPACITY_PER_STRENGTH
-            )
+        let weight_text = format!(
+            "[{:.1}/{} lbs]",
+            stats.weight,
+            (attributes.strength.base + attributes.strength.modifiers) * CARRY_CAPACITY_PER_STRENGTH
         );
+        ctx.print_color(81, y, RGB::named(WHITE), RGB::named(BLACK), &weight_text);
+        if let Some(purse) = ecs.read_storage::<Purse>().get(*player_entity) {
+            ctx.print_color(
+                82 + (weight_text.len() as i32),
+                y,
+                RGB::named(GOLD),
+                RGB::named(BLACK),
+                format!("{} gold", purse.gold)
+            );
+        }
         y += 1;
         let backpack = items(&ecs, Filter::Backpack);
         y = print_options(&ecs, &backpack, 72, y, ctx);
@@ -397,7 +433,8 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
     } else {
         format!("{}", map.short_name)
     };
-    ctx.print_color_right(70, 54, get_local_col(map.id), RGB::named(BLACK), &id);
+    let colour = raws::get_level_colour(&raws::RAWS.lock().unwrap(), map.id);
+    ctx.print_color_right(70, 54, colour, RGB::named(BLACK), &id);
 
     // Render turn
     let turns = crate::gamelog::get_event_count(EVENT::COUNT_TURN);
@@ -536,7 +573,7 @@ pub fn print_options(
             let text = if let Some(worn) = ecs.read_storage::<Equipped>().get(slot.item) {
                 use crate::EquipmentSlot;
                 let text = match worn.slot {
-                    EquipmentSlot::Melee | EquipmentSlot::Shield => "being held",
+                    EquipmentSlot::Melee | EquipmentSlot::OffHand | EquipmentSlot::Shield => "being held",
                     _ => "being worn",
                 };
                 format!("{} ({})", info.display_name.singular.to_string(), text)
@@ -589,6 +626,7 @@ pub fn obfuscate_name(
     magic_items: &ReadStorage<MagicItem>,
     obfuscated_names: &ReadStorage<ObfuscatedName>,
     beatitudes: &ReadStorage<Beatitude>,
+    enchantments: &ReadStorage<Enchantment>,
     dm: &MasterDungeonMap,
     wand: Option<&ReadStorage<Charges>>
 ) -> (String, String) {
@@ -622,6 +660,13 @@ pub fn obfuscate_name(
             (singular, plural) = (name.name.clone(), name.plural.clone());
         }
     }
+    if let Some(enchantment) = enchantments.get(item) {
+        if enchantment.known {
+            let prefix = format!("{:+} ", enchantment.amount);
+            singular.insert_str(0, &prefix);
+            plural.insert_str(0, &prefix);
+        }
+    }
     if let Some(has_beatitude) = beatitudes.get(item) {
         if has_beatitude.known {
             let prefix = match has_beatitude.buc {
@@ -668,6 +713,13 @@ pub fn obfuscate_name_ecs(ecs: &World, item: Entity) -> (String, String) {
             (singular, plural) = (name.name.clone(), name.plural.clone());
         }
     }
+    if let Some(enchantment) = ecs.read_storage::<Enchantment>().get(item) {
+        if enchantment.known {
+            let prefix = format!("{:+} ", enchantment.amount);
+            singular.insert_str(0, &prefix);
+            plural.insert_str(0, &prefix);
+        }
+    }
     if let Some(has_beatitude) = ecs.read_storage::<Beatitude>().get(item) {
         if has_beatitude.known {
             let prefix = match has_beatitude.buc {
@@ -689,6 +741,11 @@ pub fn unobf_name_ecs(ecs: &World, item: Entity) -> (String, String) {
     if let Some(name) = ecs.read_storage::<Name>().get(item) {
         (singular, plural) = (name.name.clone(), name.plural.clone());
     }
+    if let Some(enchantment) = ecs.read_storage::<Enchantment>().get(item) {
+        let prefix = format!("{:+} ", enchantment.amount);
+        singular.insert_str(0, &prefix);
+        plural.insert_str(0, &prefix);
+    }
     if let Some(has_beatitude) = ecs.read_storage::<Beatitude>().get(item) {
         let prefix = match has_beatitude.buc {
             BUC::Cursed => "cursed ",
@@ -776,7 +833,7 @@ pub fn item_colour(item: Entity, beatitudes: &ReadStorage<Beatitude>) -> (u8, u8
 pub fn show_help(ctx: &mut BTerm) -> YesNoResult {
     let mut x = 3;
     let mut y = 12;
-    let height = 22;
+    let height = 24;
     let width = 25;
     ctx.draw_box(x, y, width, height, RGB::named(WHITE), RGB::named(BLACK));
     ctx.print_color(x + 3, y, RGB::named(YELLOW), RGB::named(BLACK), " Controls ");
@@ -800,6 +857,12 @@ pub fn show_help(ctx: &mut BTerm) -> YesNoResult {
     ctx.print(x, y, "o open   c close");
     y += 1;
     ctx.print(x, y, "f force  x farlook");
+    y += 1;
+    ctx.print(x, y, "t tame   A allies");
+    y += 1;
+    ctx.print(x, y, "S skills T talk");
+    y += 1;
+    ctx.print(x, y, "Q quests");
     y += 2;
     ctx.print_color(x, y, RGB::named(GREEN), RGB::named(BLACK), "MOUSE CONTROL");
     y += 2;
@@ -854,13 +917,14 @@ pub enum Filter {
 
 macro_rules! includeitem {
     ($inv:expr, $ecs:expr, $e:expr, $k:expr) => {
+        let count = quantity_ecs($ecs, $e);
         $inv.entry(unique_ecs($ecs, $e))
             .and_modify(|slot| {
-                slot.count += 1;
+                slot.count += count;
             })
             .or_insert(InventorySlot {
                 item: $e,
-                count: 1,
+                count,
                 idx: $k.idx,
             });
     };
@@ -1429,6 +1493,36 @@ pub fn game_over(ctx: &mut BTerm) -> YesNoResult {
     }
 }
 
+/// Returns the number of items in a stack. Anything that doesn't stack is a stack of one.
+pub fn quantity(item: Entity, stackables: &ReadStorage<Stackable>) -> i32 {
+    stackables
+        .get(item)
+        .map(|s| s.quantity)
+        .unwrap_or(1)
+}
+
+pub fn quantity_ecs(ecs: &World, item: Entity) -> i32 {
+    return quantity(item, &ecs.read_storage::<Stackable>());
+}
+
+/// Takes a (singular, plural) name, and returns the name for a given quantity.
+/// i.e. "ration" for 1, or "3 rations" for 3.
+pub fn with_quantity(name: (String, String), quantity: i32) -> String {
+    if quantity > 1 {
+        return format!("{} {}", quantity, name.1);
+    }
+    name.0
+}
+
+/// Names an item for a menu line, i.e. "a dagger", or "3 rations".
+pub fn quantity_name(ecs: &World, item: Entity) -> String {
+    let quantity = quantity_ecs(ecs, item);
+    if quantity > 1 {
+        return with_quantity(obfuscate_name_ecs(ecs, item), quantity);
+    }
+    with_article(obfuscate_name_ecs(ecs, item).0)
+}
+
 pub fn with_article(name: String) -> String {
     // If first letter is a capital
     if name.chars().nth(0).unwrap().is_uppercase() {
@@ -1448,6 +1542,7 @@ pub fn unique(
     obfuscated_names: &ReadStorage<ObfuscatedName>,
     renderables: &ReadStorage<Renderable>,
     beatitudes: &ReadStorage<Beatitude>,
+    enchantments: &ReadStorage<Enchantment>,
     magic_items: &ReadStorage<MagicItem>,
     charges: Option<&ReadStorage<Charges>>,
     dm: &MasterDungeonMap
@@ -1459,6 +1554,7 @@ pub fn unique(
         magic_items,
         obfuscated_names,
         beatitudes,
+        enchantments,
         dm,
         charges
     );
@@ -1505,6 +1601,7 @@ pub fn unique_ecs(ecs: &World, entity: Entity) -> UniqueInventoryItem {
         &ecs.read_storage::<ObfuscatedName>(),
         &ecs.read_storage::<Renderable>(),
         &ecs.read_storage::<Beatitude>(),
+        &ecs.read_storage::<Enchantment>(),
         &ecs.read_storage::<MagicItem>(),
         Some(&ecs.read_storage::<Charges>()),
         &ecs.fetch::<MasterDungeonMap>()
@@ -0,0 +1,95 @@
+use super::{ item_colour_ecs, obfuscate_name_ecs, quantity_ecs, with_quantity };
+use crate::{ Entity, states::state::* };
+use bracket_lib::prelude::*;
+
+/// What's going to be done with the items once a quantity has been chosen.
+#[derive(PartialEq, Copy, Clone)]
+pub enum QuantityAction {
+    Drop,
+    Sell {
+        shopkeeper: Entity,
+    },
+    Put {
+        container: Entity,
+    },
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum QuantityMenuResult {
+    Cancel,
+    NoResponse {
+        amount: i32,
+    },
+    Selected {
+        amount: i32,
+    },
+}
+
+fn key_to_digit(key: VirtualKeyCode) -> Option<i32> {
+    match key {
+        VirtualKeyCode::Key0 | VirtualKeyCode::Numpad0 => Some(0),
+        VirtualKeyCode::Key1 | VirtualKeyCode::Numpad1 => Some(1),
+        VirtualKeyCode::Key2 | VirtualKeyCode::Numpad2 => Some(2),
+        VirtualKeyCode::Key3 | VirtualKeyCode::Numpad3 => Some(3),
+        VirtualKeyCode::Key4 | VirtualKeyCode::Numpad4 => Some(4),
+        VirtualKeyCode::Key5 | VirtualKeyCode::Numpad5 => Some(5),
+        VirtualKeyCode::Key6 | VirtualKeyCode::Numpad6 => Some(6),
+        VirtualKeyCode::Key7 | VirtualKeyCode::Numpad7 => Some(7),
+        VirtualKeyCode::Key8 | VirtualKeyCode::Numpad8 => Some(8),
+        VirtualKeyCode::Key9 | VirtualKeyCode::Numpad9 => Some(9),
+        _ => None,
+    }
+}
+
+/// Asks how many items to take from a stack. An amount of 0 means the whole stack.
+pub fn show_quantity(
+    gs: &mut State,
+    ctx: &mut BTerm,
+    item: Entity,
+    amount: i32,
+    action: QuantityAction
+) -> QuantityMenuResult {
+    let total = quantity_ecs(&gs.ecs, item);
+    let name = with_quantity(obfuscate_name_ecs(&gs.ecs, item), total);
+    let verb = match action {
+        QuantityAction::Drop => "Drop",
+        QuantityAction::Sell { .. } => "Sell",
+        QuantityAction::Put { .. } => "Put away",
+    };
+    let (x_offset, y_offset) = (1, 10);
+    ctx.print_color(
+        1 + x_offset,
+        1 + y_offset,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+        format!("{} how many? [0-9][Enter.][Esc.]", verb)
+    );
+    let x = 1 + x_offset;
+    let y = 3 + y_offset;
+    let amount_text = if amount > 0 { format!("{}", amount) } else { "all".to_string() };
+    let width = i32::max(name.len() as i32, amount_text.len() as i32) + 6;
+    ctx.draw_box(x, y, width, 3, RGB::named(WHITE), RGB::named(BLACK));
+    let colour = RGB::named(item_colour_ecs(&gs.ecs, item));
+    ctx.print_color(x + 2, y + 1, colour, RGB::named(BLACK), &name);
+    ctx.print_color(x + 2, y + 2, RGB::named(YELLOW), RGB::named(BLACK), &amount_text);
+
+    match ctx.key {
+        None => QuantityMenuResult::NoResponse { amount },
+        Some(key) =>
+            match key {
+                VirtualKeyCode::Escape => QuantityMenuResult::Cancel,
+                VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                    QuantityMenuResult::Selected { amount }
+                }
+                VirtualKeyCode::Back => QuantityMenuResult::NoResponse { amount: amount / 10 },
+                _ => {
+                    if let Some(digit) = key_to_digit(key) {
+                        return QuantityMenuResult::NoResponse {
+                            amount: i32::min(amount * 10 + digit, total),
+                        };
+                    }
+                    QuantityMenuResult::NoResponse { amount }
+                }
+            }
+    }
+}
@@ -0,0 +1,62 @@
+use super::dialogue_menu::wrap_text;
+use super::YesNoResult;
+use crate::quests::{ describe_objective, objective_progress, QuestLog, QuestStatus };
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+const QUEST_LOG_WIDTH: i32 = 60;
+
+/// Shows the quests the player is working on, how far along each objective is, and
+/// the names of any they've already finished.
+pub fn show_quest_log(ecs: &World, ctx: &mut BTerm) -> YesNoResult {
+    let quest_log = ecs.fetch::<QuestLog>();
+    let mut lines: Vec<(String, (u8, u8, u8))> = Vec::new();
+    let active: Vec<_> = quest_log.quests
+        .iter()
+        .filter(|q| q.status == QuestStatus::Active)
+        .collect();
+    let completed: Vec<_> = quest_log.quests
+        .iter()
+        .filter(|q| q.status == QuestStatus::Completed)
+        .collect();
+
+    if active.is_empty() {
+        lines.push(("You aren't on any quests.".to_string(), GREY));
+    }
+    for quest in active.iter() {
+        lines.push((quest.name.clone(), YELLOW));
+        for line in wrap_text(&quest.description, (QUEST_LOG_WIDTH - 6) as usize) {
+            lines.push((format!("  {}", line), GREY));
+        }
+        for objective in quest.objectives.iter() {
+            let (progress, needed) = objective_progress(ecs, objective);
+            let colour = if progress >= needed { GREEN } else { WHITE };
+            lines.push((format!("  - {}", describe_objective(ecs, objective)), colour));
+        }
+        lines.push((String::new(), WHITE));
+    }
+    if !completed.is_empty() {
+        lines.push(("COMPLETED".to_string(), GREEN));
+        for quest in completed.iter() {
+            lines.push((format!("  {}", quest.name), GREY));
+        }
+    }
+
+    let (x, y) = (3, 4);
+    let height = (lines.len() as i32) + 3;
+    ctx.draw_box(x, y, QUEST_LOG_WIDTH, height, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(x + 3, y, RGB::named(YELLOW), RGB::named(BLACK), " Quests ");
+    ctx.print_color(x + 3, y + height, RGB::named(YELLOW), RGB::named(BLACK), " ESC to close ");
+    for (i, (line, colour)) in lines.iter().enumerate() {
+        ctx.print_color(x + 2, y + 2 + (i as i32), RGB::named(*colour), RGB::named(BLACK), line);
+    }
+
+    match ctx.key {
+        None => YesNoResult::NoSelection,
+        Some(key) =>
+            match key {
+                VirtualKeyCode::Escape => YesNoResult::Yes,
+                _ => YesNoResult::NoSelection,
+            }
+    }
+}
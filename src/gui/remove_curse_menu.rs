@@ -4,6 +4,7 @@ use super::{
     obfuscate_name_ecs,
     print_options,
     unique_ecs,
+    quantity_ecs,
     check_key,
     letter_to_option,
     ItemMenuResult,
@@ -93,14 +94,15 @@ pub fn remove_curse(gs: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Option<
     let mut player_inventory: super::PlayerInventory = HashMap::new();
     for (entity, _i, _b, _r, _n, key) in build_cursed_iterator() {
         let unique_item = unique_ecs(&gs.ecs, entity);
+        let count = quantity_ecs(&gs.ecs, entity);
         player_inventory
             .entry(unique_item)
             .and_modify(|slot| {
-                slot.count += 1;
+                slot.count += count;
             })
             .or_insert(InventorySlot {
                 item: entity,
-                count: 1,
+                count,
                 idx: key.idx,
             });
     }
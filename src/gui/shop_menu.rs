@@ -0,0 +1,217 @@
+use super::{ item_colour_ecs, quantity_ecs, quantity_name, letter_to_option };
+use crate::{
+    gamesystem::{ buy_price, sell_price },
+    Attributes,
+    Beatitude,
+    Entity,
+    ForSale,
+    InBackpack,
+    Item,
+    Key,
+    Name,
+    Position,
+    Purse,
+    Renderable,
+    BUC,
+    states::state::*,
+};
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ShopMode {
+    Buy,
+    Sell,
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ShopMenuResult {
+    Cancel,
+    NoResponse,
+    SwitchMode,
+    Selected,
+}
+
+struct ShopLine {
+    entity: Entity,
+    idx: usize,
+    glyph: FontCharType,
+    glyph_colour: RGB,
+    name: String,
+    name_colour: RGB,
+    price: i32,
+}
+
+/// Returns the price of an item (or a whole stack), from the perspective of the player buying or selling it.
+pub fn price_of(ecs: &World, item: Entity, mode: ShopMode) -> i32 {
+    let value = ecs
+        .read_storage::<Item>()
+        .get(item)
+        .map(|i| i.value)
+        .unwrap_or(0.0);
+    let beatitudes = ecs.read_storage::<Beatitude>();
+    let buc = beatitudes
+        .get(item)
+        .map(|b| b.buc.clone())
+        .unwrap_or(BUC::Uncursed);
+    let price = match mode {
+        ShopMode::Buy => {
+            let player_entity = ecs.fetch::<Entity>();
+            let charisma = ecs
+                .read_storage::<Attributes>()
+                .get(*player_entity)
+                .map(|a| a.charisma.base + a.charisma.modifiers)
+                .unwrap_or(10);
+            buy_price(value, &buc, charisma)
+        }
+        ShopMode::Sell => sell_price(value, &buc),
+    };
+    price * quantity_ecs(ecs, item)
+}
+
+fn shop_lines(ecs: &World, shopkeeper: Entity, mode: ShopMode) -> Vec<ShopLine> {
+    let player_entity = ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let renderables = ecs.read_storage::<Renderable>();
+    let mut candidates: Vec<(Entity, usize)> = Vec::new();
+    match mode {
+        ShopMode::Buy => {
+            // Stock is everything still on the floor that belongs to this shopkeeper.
+            let for_sale = ecs.read_storage::<ForSale>();
+            let positions = ecs.read_storage::<Position>();
+            let names = ecs.read_storage::<Name>();
+            let mut stock: Vec<(Entity, String)> = (&entities, &for_sale, &positions, &names)
+                .join()
+                .filter(|(_e, sale, _p, _n)| sale.shopkeeper == shopkeeper)
+                .map(|(e, _s, _p, n)| (e, n.name.clone()))
+                .collect();
+            stock.sort_by(|a, b| a.1.cmp(&b.1));
+            for (i, (e, _n)) in stock.into_iter().enumerate() {
+                candidates.push((e, i));
+            }
+        }
+        ShopMode::Sell => {
+            let backpack = ecs.read_storage::<InBackpack>();
+            let keys = ecs.read_storage::<Key>();
+            for (e, bp, key) in (&entities, &backpack, &keys).join() {
+                if bp.owner == *player_entity {
+                    candidates.push((e, key.idx));
+                }
+            }
+            candidates.sort_by(|a, b| a.1.cmp(&b.1));
+        }
+    }
+    let mut lines: Vec<ShopLine> = Vec::new();
+    for (entity, idx) in candidates {
+        let (glyph, glyph_colour) = if let Some(renderable) = renderables.get(entity) {
+            (renderable.glyph, renderable.fg)
+        } else {
+            (to_cp437('?'), RGB::named(WHITE))
+        };
+        lines.push(ShopLine {
+            entity,
+            idx,
+            glyph,
+            glyph_colour,
+            name: quantity_name(ecs, entity),
+            name_colour: RGB::named(item_colour_ecs(ecs, entity)),
+            price: price_of(ecs, entity, mode),
+        });
+    }
+    return lines;
+}
+
+/// Handles the buy/sell menu for a shopkeeper.
+pub fn show_shop(
+    gs: &mut State,
+    ctx: &mut BTerm,
+    shopkeeper: Entity,
+    mode: ShopMode
+) -> (ShopMenuResult, Option<Entity>) {
+    let lines = shop_lines(&gs.ecs, shopkeeper, mode);
+    let player_gold = {
+        let player_entity = gs.ecs.fetch::<Entity>();
+        gs.ecs
+            .read_storage::<Purse>()
+            .get(*player_entity)
+            .map(|p| p.gold)
+            .unwrap_or(0)
+    };
+    let shopkeeper_name = gs.ecs
+        .read_storage::<Name>()
+        .get(shopkeeper)
+        .map(|n| n.name.clone())
+        .unwrap_or("shopkeeper".to_string());
+    let (x_offset, y_offset) = (1, 10);
+    let title = match mode {
+        ShopMode::Buy => format!("Buy what from the {}? [aA-zZ][Tab. to sell][Esc.]", shopkeeper_name),
+        ShopMode::Sell => format!("Sell what to the {}? [aA-zZ][Tab. to buy][Esc.]", shopkeeper_name),
+    };
+    ctx.print_color(1 + x_offset, 1 + y_offset, RGB::named(WHITE), RGB::named(BLACK), title);
+
+    let x = 1 + x_offset;
+    let mut y = 3 + y_offset;
+    let name_width = lines
+        .iter()
+        .map(|l| l.name.len() as i32)
+        .max()
+        .unwrap_or(0);
+    let price_width = lines
+        .iter()
+        .map(|l| l.price.to_string().len() as i32)
+        .max()
+        .unwrap_or(0);
+    let width = i32::max(name_width + price_width + 12, 24);
+    let count = i32::max(lines.len() as i32, 1);
+    ctx.draw_box(x, y, width, count + 1, RGB::named(WHITE), RGB::named(BLACK));
+    y += 1;
+    if lines.is_empty() {
+        let text = match mode {
+            ShopMode::Buy => "Nothing for sale.",
+            ShopMode::Sell => "Nothing to sell.",
+        };
+        ctx.print_color(x + 1, y, RGB::named(GREY), RGB::named(BLACK), text);
+    }
+    for line in lines.iter() {
+        let letter = if line.idx < 26 { 97 + line.idx } else { 65 - 26 + line.idx };
+        ctx.set(x + 1, y, RGB::named(YELLOW), RGB::named(BLACK), letter as FontCharType);
+        ctx.set(x + 3, y, line.glyph_colour, RGB::named(BLACK), line.glyph);
+        ctx.print_color(x + 5, y, line.name_colour, RGB::named(BLACK), &line.name);
+        let price_text = format!("{} gold", line.price);
+        ctx.print_color(
+            x + width - (price_text.len() as i32),
+            y,
+            RGB::named(GOLD),
+            RGB::named(BLACK),
+            price_text
+        );
+        y += 1;
+    }
+    ctx.print_color(
+        x,
+        y + 1,
+        RGB::named(GOLD),
+        RGB::named(BLACK),
+        format!("You have {} gold.", player_gold)
+    );
+
+    match ctx.key {
+        None => (ShopMenuResult::NoResponse, None),
+        Some(key) =>
+            match key {
+                VirtualKeyCode::Escape => (ShopMenuResult::Cancel, None),
+                VirtualKeyCode::Tab => (ShopMenuResult::SwitchMode, None),
+                _ => {
+                    let selection = letter_to_option::letter_to_option(key, ctx.shift);
+                    if selection != -1 {
+                        for line in lines.iter() {
+                            if line.idx == (selection as usize) {
+                                return (ShopMenuResult::Selected, Some(line.entity));
+                            }
+                        }
+                    }
+                    (ShopMenuResult::NoResponse, None)
+                }
+            }
+    }
+}
@@ -0,0 +1,45 @@
+use super::YesNoResult;
+use crate::{ gamesystem::skill_training_needed, Skill, Skills };
+use crate::data::entity::SKILL_MAX_LEVEL;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Shows the player's weapon skills, their levels, and how close each is to advancing.
+pub fn show_skills(ecs: &World, ctx: &mut BTerm) -> YesNoResult {
+    let player_entity = ecs.fetch::<Entity>();
+    let skills = ecs.read_storage::<Skills>();
+    let (x, y) = (3, 12);
+    let (width, height) = (36, (Skill::WEAPON_SKILLS.len() as i32) + 5);
+    ctx.draw_box(x, y, width, height, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(x + 3, y, RGB::named(YELLOW), RGB::named(BLACK), " Skills ");
+    ctx.print_color(x + 3, y + height, RGB::named(YELLOW), RGB::named(BLACK), " ESC to close ");
+    ctx.print_color(x + 2, y + 2, RGB::named(GREEN), RGB::named(BLACK), "WEAPON SKILLS");
+
+    if let Some(player_skills) = skills.get(*player_entity) {
+        for (i, skill) in Skill::WEAPON_SKILLS.iter().enumerate() {
+            let row = y + 4 + (i as i32);
+            let level = *player_skills.skills.get(skill).unwrap_or(&0);
+            let (colour, progress) = if level >= SKILL_MAX_LEVEL {
+                (YELLOW, "mastered".to_string())
+            } else {
+                let trained = *player_skills.training.get(skill).unwrap_or(&0);
+                let needed = skill_training_needed(level);
+                let filled = ((trained * 10) / needed) as usize;
+                let colour = if level > 0 { WHITE } else { GREY };
+                (colour, format!("[{}{}]", "#".repeat(filled), "-".repeat(10 - filled)))
+            };
+            ctx.print_color(x + 2, row, RGB::named(colour), RGB::named(BLACK), skill.string());
+            ctx.print_color(x + 15, row, RGB::named(colour), RGB::named(BLACK), format!("+{}", level));
+            ctx.print_color(x + 20, row, RGB::named(colour), RGB::named(BLACK), progress);
+        }
+    }
+
+    match ctx.key {
+        None => YesNoResult::NoSelection,
+        Some(key) =>
+            match key {
+                VirtualKeyCode::Escape => YesNoResult::Yes,
+                _ => YesNoResult::NoSelection,
+            }
+    }
+}
@@ -3,6 +3,7 @@ use super::{
     Attributes,
     Hidden,
     Map,
+    MasterDungeonMap,
     Name,
     Pools,
     Position,
@@ -11,7 +12,7 @@ use super::{
     RGB,
 };
 use crate::TileType;
-use crate::data::ids::*;
+use crate::raws;
 use bracket_lib::prelude::*;
 use specs::prelude::*;
 
@@ -93,15 +94,17 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut BTerm, xy: Option<(i32, i32)>) {
     
     match map.tiles[map.xy_idx(mouse_pos_adjusted.0, mouse_pos_adjusted.1)] {
         TileType::ToLocal(n) => {
-            let name = get_local_desc(n);
+            let raws = &raws::RAWS.lock().unwrap();
+            let name = raws::get_level_name(raws, &ecs.fetch::<MasterDungeonMap>(), n);
             let mut tip = Tooltip::new();
-            tip.add(format!("You see {}.", name), get_local_col(n));
+            tip.add(format!("You see {}.", name), raws::get_level_colour(raws, n));
             tooltips.push(tip);
         }
         TileType::ToOvermap(n) => {
-            let name = get_local_desc(n);
+            let raws = &raws::RAWS.lock().unwrap();
+            let name = raws::get_level_name(raws, &ecs.fetch::<MasterDungeonMap>(), n);
             let mut tip = Tooltip::new();
-            tip.add(format!("You see an exit from {}.", name), get_local_col(n));
+            tip.add(format!("You see an exit from {}.", name), raws::get_level_colour(raws, n));
             tooltips.push(tip);
         }
         _ => {}
@@ -161,6 +164,11 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut BTerm, xy: Option<(i32, i32)>) {
                     tip.add("injured", YELLOW_WARNING);
                 }
             }
+            if ecs.read_storage::<crate::components::Fleeing>().get(entity).is_some() {
+                tip.add("fleeing", YELLOW_WARNING);
+            } else if let Some(awareness) = awareness(ecs, entity, *player_entity) {
+                tip.add(awareness.0, awareness.1);
+            }
             tooltips.push(tip);
         }
     }
@@ -203,3 +211,28 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut BTerm, xy: Option<(i32, i32)>) {
         y += t.height();
     }
 }
+
+/// Whether a hostile mob is after the player, looking for them, or unaware of them.
+/// None for anything that isn't hostile, or doesn't have a mind to be aware with.
+fn awareness(ecs: &World, entity: Entity, player_entity: Entity) -> Option<(&'static str, RGB)> {
+    if ecs.read_storage::<crate::components::Mind>().get(entity).is_none() {
+        return None;
+    }
+    let reaction = crate::raws::get_reactions(
+        entity,
+        player_entity,
+        &ecs.read_storage::<crate::components::Faction>(),
+        &ecs.read_storage::<crate::components::HasAncestry>(),
+        &crate::raws::RAWS.lock().unwrap()
+    );
+    if reaction != crate::raws::Reaction::Attack {
+        return None;
+    }
+    if ecs.read_storage::<crate::components::Chasing>().get(entity).is_some() {
+        return Some(("hunting", ORANGE_WARNING));
+    }
+    if ecs.read_storage::<crate::components::LastSeen>().get(entity).is_some() {
+        return Some(("searching", YELLOW_WARNING));
+    }
+    return Some(("unaware", GREEN_WARNING));
+}
@@ -1,7 +1,11 @@
 use super::{
     effects::{ add_effect, EffectType, Targets },
     gamelog,
+    gamesystem::attr_bonus,
+    Attributes,
     Clock,
+    EquipmentChanged,
+    Fainted,
     HungerClock,
     HungerState,
     TakingTurn,
@@ -11,12 +15,13 @@ use super::{
 use bracket_lib::prelude::*;
 use specs::prelude::*;
 use crate::config::CONFIG;
+use crate::data::messages;
 
 /// HungerSystem is in charge of ticking down the hunger clock for entities with a hunger clock,
 /// every time the turn clock ticks.
 pub struct HungerSystem {}
 
-const MAX_SATIATION: i32 = 2000;
+pub const MAX_SATIATION: i32 = 2000;
 const HUNGER_BREAKPOINTS: [(i32, HungerState); 5] = [
     (1000, HungerState::Satiated),
     (600, HungerState::Normal),
@@ -25,6 +30,8 @@ const HUNGER_BREAKPOINTS: [(i32, HungerState); 5] = [
     (0, HungerState::Fainting),
 ];
 const BASE_CLOCK_DECREMENT_PER_TURN: i32 = 1;
+const FAINT_ONE_IN: i32 = 10; // Chance per turn of fainting, when fainting or starving.
+const FAINT_MAX_TURNS: i32 = 10;
 
 pub fn get_hunger_state(duration: i32) -> HungerState {
     for (threshold, state) in HUNGER_BREAKPOINTS.iter() {
@@ -35,6 +42,15 @@ pub fn get_hunger_state(duration: i32) -> HungerState {
     return HungerState::Starving;
 }
 
+/// Returns the penalty to strength for being at a given hunger state.
+pub fn get_hunger_str_penalty(state: HungerState) -> i32 {
+    match state {
+        HungerState::Weak => 1,
+        HungerState::Fainting | HungerState::Starving => 2,
+        _ => 0,
+    }
+}
+
 pub fn get_hunger_colour(state: HungerState) -> (u8, u8, u8) {
     match state {
         HungerState::Satiated => GREEN,
@@ -55,10 +71,25 @@ impl<'a> System<'a> for HungerSystem {
         ReadStorage<'a, Clock>,
         ReadStorage<'a, TakingTurn>,
         ReadStorage<'a, Intrinsics>,
+        WriteStorage<'a, Attributes>,
+        WriteStorage<'a, EquipmentChanged>,
+        WriteStorage<'a, Fainted>,
+        WriteExpect<'a, RandomNumberGenerator>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, mut hunger_clock, player_entity, turn_clock, turns, intrinsics) = data;
+        let (
+            entities,
+            mut hunger_clock,
+            player_entity,
+            turn_clock,
+            turns,
+            intrinsics,
+            mut attributes,
+            mut equipment_changed,
+            mut fainted,
+            mut rng,
+        ) = data;
 
         // If the turn clock isn't taking a turn this tick, don't bother ticking hunger.
         let mut ticked = false;
@@ -94,6 +125,20 @@ impl<'a> System<'a> for HungerSystem {
                     Targets::Entity { target: entity }
                 );
             }
+            // Fainting spells, if we're not already passed out.
+            if
+                (hunger_clock.state == HungerState::Fainting ||
+                    hunger_clock.state == HungerState::Starving) &&
+                fainted.get(entity).is_none() &&
+                rng.roll_dice(1, FAINT_ONE_IN) == 1
+            {
+                fainted
+                    .insert(entity, Fainted { turns: rng.roll_dice(1, FAINT_MAX_TURNS) })
+                    .expect("Unable to insert Fainted");
+                if entity == *player_entity {
+                    gamelog::Logger::new().append(messages::FAINT).log();
+                }
+            }
             if CONFIG.logging.log_ticks && entity == *player_entity {
                 console::log(
                     format!(
@@ -105,6 +150,18 @@ impl<'a> System<'a> for HungerSystem {
             if hunger_clock.state == initial_state {
                 continue;
             }
+            // Weakness from hunger saps strength, and eating restores it.
+            let penalty_change =
+                get_hunger_str_penalty(hunger_clock.state) - get_hunger_str_penalty(initial_state);
+            if penalty_change != 0 {
+                if let Some(attr) = attributes.get_mut(entity) {
+                    attr.strength.modifiers -= penalty_change;
+                    attr.strength.bonus = attr_bonus(attr.strength.base + attr.strength.modifiers);
+                    equipment_changed
+                        .insert(entity, EquipmentChanged {})
+                        .expect("Unable to insert EquipmentChanged");
+                }
+            }
             if entity != *player_entity {
                 continue;
             }
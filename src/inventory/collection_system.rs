@@ -1,18 +1,28 @@
+use super::stacks_with;
 use crate::{
     gamelog,
     gui::obfuscate_name,
     gui::item_colour,
+    gui::with_quantity,
     Beatitude,
     Charges,
+    Currency,
     EquipmentChanged,
+    Faction,
+    ForSale,
+    HasAncestry,
     InBackpack,
     MagicItem,
     MasterDungeonMap,
     Name,
     ObfuscatedName,
     Position,
+    Purse,
+    RawItem,
     WantsToPickupItem,
     WantsToAssignKey,
+    Enchantment,
+    Stackable,
 };
 use specs::prelude::*;
 use crate::data::messages;
@@ -35,6 +45,15 @@ impl<'a> System<'a> for ItemCollectionSystem {
         ReadExpect<'a, MasterDungeonMap>,
         ReadStorage<'a, Charges>,
         ReadStorage<'a, WantsToAssignKey>,
+        Entities<'a>,
+        ReadStorage<'a, Currency>,
+        WriteStorage<'a, Purse>,
+        WriteStorage<'a, ForSale>,
+        WriteStorage<'a, Faction>,
+        WriteStorage<'a, HasAncestry>,
+        ReadStorage<'a, Enchantment>,
+        WriteStorage<'a, Stackable>,
+        ReadStorage<'a, RawItem>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -51,27 +70,105 @@ impl<'a> System<'a> for ItemCollectionSystem {
             dm,
             wands,
             wants_key,
+            entities,
+            currency,
+            mut purses,
+            mut for_sale,
+            mut factions,
+            mut ancestries,
+            enchantments,
+            mut stackables,
+            raw_items,
         ) = data;
         let mut to_remove: Vec<Entity> = Vec::new();
         // For every item that wants to be picked up that *isn't* waiting on a key assignment.
         for (pickup, _key) in (&wants_pickup, !&wants_key).join() {
+            // Picking up something that hasn't been paid for is theft. It only stops being
+            // stock once the player has it - anyone else is just carrying the shop's goods.
+            if pickup.collected_by == *player_entity {
+                if let Some(sale) = for_sale.get(pickup.item) {
+                    gamelog::Logger
+                        ::new()
+                        .append(messages::YOU_STEAL_ITEM)
+                        .colour(item_colour(pickup.item, &beatitudes))
+                        .append_n(
+                            obfuscate_name(
+                                pickup.item,
+                                &names,
+                                &magic_items,
+                                &obfuscated_names,
+                                &beatitudes,
+                                &enchantments,
+                                &dm,
+                                Some(&wands)
+                            ).0
+                        )
+                        .colour(WHITE)
+                        .append("!")
+                        .log();
+                    if let Some(name) = names.get(sale.shopkeeper) {
+                        gamelog::Logger
+                            ::new()
+                            .append("The")
+                            .colour(RED)
+                            .append(&name.name)
+                            .colour(WHITE)
+                            .append(messages::SHOPKEEPER_THIEF)
+                            .log();
+                    }
+                    // Ancestry reactions take precedence over factions, so a wronged
+                    // shopkeeper has to forget any kinship to actually turn hostile.
+                    factions
+                        .insert(sale.shopkeeper, Faction { name: "hostile".to_string() })
+                        .expect("Unable to insert Faction");
+                    ancestries.remove(sale.shopkeeper);
+                }
+                for_sale.remove(pickup.item);
+            }
+            // Currency goes straight into the collector's purse.
+            if let Some(coins) = currency.get(pickup.item) {
+                if let Some(purse) = purses.get_mut(pickup.collected_by) {
+                    purse.gold += coins.amount;
+                } else {
+                    purses
+                        .insert(pickup.collected_by, Purse { gold: coins.amount })
+                        .expect("Unable to insert Purse");
+                }
+                if pickup.collected_by == *player_entity {
+                    gamelog::Logger
+                        ::new()
+                        .append(messages::YOU_PICKUP_GOLD)
+                        .colour(GOLD)
+                        .append_n(format!("{} gold", coins.amount))
+                        .colour(WHITE)
+                        .period()
+                        .log();
+                }
+                entities.delete(pickup.item).expect("Unable to delete currency");
+                to_remove.push(pickup.collected_by);
+                continue;
+            }
             if pickup.collected_by == *player_entity {
                 gamelog::Logger
                     ::new()
                     .append(messages::YOU_PICKUP_ITEM)
                     .colour(item_colour(pickup.item, &beatitudes))
                     .append_n(
-                        format!(
-                            "{}",
+                        with_quantity(
                             obfuscate_name(
                                 pickup.item,
                                 &names,
                                 &magic_items,
                                 &obfuscated_names,
                                 &beatitudes,
+                                &enchantments,
                                 &dm,
                                 Some(&wands)
-                            ).0
+                            ),
+                            stackables
+                                .get(pickup.item)
+                                .map(|s| s.quantity)
+                                .unwrap_or(1)
                         )
                     )
                     .colour(WHITE)
@@ -79,6 +176,31 @@ impl<'a> System<'a> for ItemCollectionSystem {
                     .log();
             }
             positions.remove(pickup.item);
+            // The player's stacks are merged when they're given a key. Anyone else just adds
+            // to whatever matching stack they already have, so long as neither is shop stock.
+            if pickup.collected_by != *player_entity && for_sale.get(pickup.item).is_none() {
+                if let Some(incoming) = stackables.get(pickup.item).map(|s| s.quantity) {
+                    let existing = (&entities, &backpack, &stackables, !&for_sale)
+                        .join()
+                        .find(|(e, pack, _s, _f)| {
+                            *e != pickup.item &&
+                                pack.owner == pickup.collected_by &&
+                                stacks_with(*e, pickup.item, &raw_items, &beatitudes, &enchantments)
+                        })
+                        .map(|(e, _pack, _s, _f)| e);
+                    if let Some(existing) = existing {
+                        if let Some(stack) = stackables.get_mut(existing) {
+                            stack.quantity += incoming;
+                        }
+                        entities.delete(pickup.item).expect("Unable to delete merged stack");
+                        equipment_changed
+                            .insert(pickup.collected_by, EquipmentChanged {})
+                            .expect("Unable to insert EquipmentChanged");
+                        to_remove.push(pickup.collected_by);
+                        continue;
+                    }
+                }
+            }
             backpack
                 .insert(pickup.item, InBackpack { owner: pickup.collected_by })
                 .expect("Unable to pickup item");
@@ -0,0 +1,174 @@
+use crate::{
+    gamelog,
+    gui::obfuscate_name,
+    gui::item_colour,
+    gui::quantity,
+    gui::with_quantity,
+    Beatitude,
+    Charges,
+    Currency,
+    EquipmentChanged,
+    InBackpack,
+    InContainer,
+    MagicItem,
+    MasterDungeonMap,
+    Name,
+    ObfuscatedName,
+    Purse,
+    WantsToAssignKey,
+    WantsToPutInContainer,
+    WantsToRemoveKey,
+    WantsToTakeFromContainer,
+    Enchantment,
+    Stackable,
+};
+use specs::prelude::*;
+use crate::data::messages;
+use bracket_lib::prelude::*;
+
+pub struct ContainerSystem {}
+
+impl<'a> System<'a> for ContainerSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        Entities<'a>,
+        WriteStorage<'a, WantsToPutInContainer>,
+        WriteStorage<'a, WantsToTakeFromContainer>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, InContainer>,
+        WriteStorage<'a, Purse>,
+        WriteStorage<'a, EquipmentChanged>,
+        WriteStorage<'a, WantsToAssignKey>,
+        WriteStorage<'a, WantsToRemoveKey>,
+        ReadStorage<'a, Currency>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, MagicItem>,
+        ReadStorage<'a, ObfuscatedName>,
+        ReadStorage<'a, Beatitude>,
+        ReadExpect<'a, MasterDungeonMap>,
+        ReadStorage<'a, Charges>,
+        ReadStorage<'a, Enchantment>,
+        ReadStorage<'a, Stackable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            entities,
+            mut wants_put,
+            mut wants_take,
+            mut backpack,
+            mut in_container,
+            mut purses,
+            mut equipment_changed,
+            mut assign_keys,
+            mut remove_keys,
+            currency,
+            names,
+            magic_items,
+            obfuscated_names,
+            beatitudes,
+            dm,
+            wands,
+            enchantments,
+            stackables,
+        ) = data;
+
+        let item_name = |item: Entity| -> String {
+            with_quantity(
+                obfuscate_name(
+                    item,
+                    &names,
+                    &magic_items,
+                    &obfuscated_names,
+                    &beatitudes,
+                    &enchantments,
+                    &dm,
+                    Some(&wands)
+                ),
+                quantity(item, &stackables)
+            )
+        };
+
+        for (entity, put) in (&entities, &wants_put).join() {
+            // Only things in the backpack can be put away - not anything equipped.
+            if backpack.get(put.item).map(|b| b.owner) != Some(entity) {
+                continue;
+            }
+            if put.item == put.container {
+                if entity == *player_entity {
+                    gamelog::Logger::new().append(messages::CONTAINER_INTO_ITSELF).period().log();
+                }
+                continue;
+            }
+            backpack.remove(put.item);
+            in_container
+                .insert(put.item, InContainer { owner: put.container })
+                .expect("Unable to insert InContainer");
+            equipment_changed.insert(entity, EquipmentChanged {}).expect("Unable to insert EquipmentChanged");
+            if entity == *player_entity {
+                remove_keys.insert(put.item, WantsToRemoveKey {}).expect("Unable to insert WantsToRemoveKey");
+                gamelog::Logger
+                    ::new()
+                    .append(messages::CONTAINER_PUT_ITEM)
+                    .colour(item_colour(put.item, &beatitudes))
+                    .append(item_name(put.item))
+                    .colour(WHITE)
+                    .append("into the")
+                    .colour(item_colour(put.container, &beatitudes))
+                    .append_n(item_name(put.container))
+                    .colour(WHITE)
+                    .period()
+                    .log();
+            }
+        }
+
+        for (entity, take) in (&entities, &wants_take).join() {
+            if in_container.get(take.item).map(|c| c.owner) != Some(take.container) {
+                continue;
+            }
+            in_container.remove(take.item);
+            // Currency goes straight into the taker's purse.
+            if let Some(coins) = currency.get(take.item) {
+                if let Some(purse) = purses.get_mut(entity) {
+                    purse.gold += coins.amount;
+                } else {
+                    purses.insert(entity, Purse { gold: coins.amount }).expect("Unable to insert Purse");
+                }
+                if entity == *player_entity {
+                    gamelog::Logger
+                        ::new()
+                        .append(messages::YOU_PICKUP_GOLD)
+                        .colour(GOLD)
+                        .append_n(format!("{} gold", coins.amount))
+                        .colour(WHITE)
+                        .period()
+                        .log();
+                }
+                entities.delete(take.item).expect("Unable to delete currency");
+                continue;
+            }
+            backpack.insert(take.item, InBackpack { owner: entity }).expect("Unable to insert InBackpack");
+            equipment_changed.insert(entity, EquipmentChanged {}).expect("Unable to insert EquipmentChanged");
+            if entity == *player_entity {
+                assign_keys.insert(take.item, WantsToAssignKey {}).expect("Unable to insert WantsToAssignKey");
+                gamelog::Logger
+                    ::new()
+                    .append(messages::CONTAINER_TAKE_ITEM)
+                    .colour(item_colour(take.item, &beatitudes))
+                    .append(item_name(take.item))
+                    .colour(WHITE)
+                    .append("out of the")
+                    .colour(item_colour(take.container, &beatitudes))
+                    .append_n(item_name(take.container))
+                    .colour(WHITE)
+                    .period()
+                    .log();
+            }
+        }
+
+        wants_put.clear();
+        wants_take.clear();
+    }
+}
@@ -1,18 +1,24 @@
+use super::stacks_with;
 use crate::{
     gamelog,
     gui::obfuscate_name,
     gui::item_colour,
+    gui::with_quantity,
     Beatitude,
     Charges,
     EquipmentChanged,
+    ForSale,
     InBackpack,
     MagicItem,
     MasterDungeonMap,
     Name,
     ObfuscatedName,
     Position,
+    RawItem,
     WantsToDropItem,
     WantsToRemoveKey,
+    Enchantment,
+    Stackable,
 };
 use specs::prelude::*;
 use crate::data::messages;
@@ -36,6 +42,10 @@ impl<'a> System<'a> for ItemDropSystem {
         ReadExpect<'a, MasterDungeonMap>,
         ReadStorage<'a, Charges>,
         WriteStorage<'a, WantsToRemoveKey>,
+        ReadStorage<'a, Enchantment>,
+        WriteStorage<'a, Stackable>,
+        ReadStorage<'a, RawItem>,
+        ReadStorage<'a, ForSale>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -53,6 +63,10 @@ impl<'a> System<'a> for ItemDropSystem {
             dm,
             wands,
             mut keys,
+            enchantments,
+            mut stackables,
+            raw_items,
+            for_sale,
         ) = data;
 
         for (entity, to_drop) in (&entities, &wants_drop).join() {
@@ -79,23 +93,47 @@ impl<'a> System<'a> for ItemDropSystem {
                     .append(messages::YOU_DROP_ITEM)
                     .colour(item_colour(to_drop.item, &beatitudes))
                     .append_n(
-                        format!(
-                            "{}",
+                        with_quantity(
                             obfuscate_name(
                                 to_drop.item,
                                 &names,
                                 &magic_items,
                                 &obfuscated_names,
                                 &beatitudes,
+                                &enchantments,
                                 &dm,
                                 Some(&wands)
-                            ).0
+                            ),
+                            stackables
+                                .get(to_drop.item)
+                                .map(|s| s.quantity)
+                                .unwrap_or(1)
                         )
                     )
                     .colour(WHITE)
                     .period()
                     .log();
             }
+            // Anything already on the floor here that's the same gets merged into what was
+            // dropped - never the other way around, as only the dropped stack can have a key.
+            if stackables.get(to_drop.item).is_none() || for_sale.get(to_drop.item).is_some() {
+                continue;
+            }
+            let floor_stack = (&entities, &positions, &stackables, !&for_sale)
+                .join()
+                .find(|(e, pos, _s, _f)| {
+                    *e != to_drop.item &&
+                        pos.x == dropper_pos.x &&
+                        pos.y == dropper_pos.y &&
+                        stacks_with(*e, to_drop.item, &raw_items, &beatitudes, &enchantments)
+                })
+                .map(|(e, _pos, s, _f)| (e, s.quantity));
+            if let Some((floor_stack, amount)) = floor_stack {
+                if let Some(stack) = stackables.get_mut(to_drop.item) {
+                    stack.quantity += amount;
+                }
+                entities.delete(floor_stack).expect("Unable to delete merged stack");
+            }
         }
 
         wants_drop.clear();
@@ -3,6 +3,7 @@ use crate::{
     gui::{ item_colour, obfuscate_name },
     Beatitude,
     EquipmentChanged,
+    EquipmentSlot,
     Equippable,
     Equipped,
     IdentifiedBeatitude,
@@ -12,8 +13,11 @@ use crate::{
     MasterDungeonMap,
     Name,
     ObfuscatedName,
+    OffHandable,
+    TwoHanded,
     WantsToUseItem,
     BUC,
+    Enchantment,
 };
 use specs::prelude::*;
 use crate::data::messages;
@@ -38,6 +42,9 @@ impl<'a> System<'a> for ItemEquipSystem {
         ReadStorage<'a, Beatitude>,
         WriteStorage<'a, IdentifiedBeatitude>,
         ReadExpect<'a, MasterDungeonMap>,
+        ReadStorage<'a, Enchantment>,
+        ReadStorage<'a, TwoHanded>,
+        ReadStorage<'a, OffHandable>,
     );
 
     #[allow(clippy::cognitive_complexity)]
@@ -57,14 +64,69 @@ impl<'a> System<'a> for ItemEquipSystem {
             beatitudes,
             mut identified_beatitude,
             dm,
+            enchantments,
+            two_handed,
+            off_handable,
         ) = data;
         let mut remove: Vec<Entity> = Vec::new();
         // For every item with a target, if the item is equippable, find the correct slot.
         for (target, wants_to_use_item) in (&entities, &wants_to_use_item).join() {
             if let Some(can_equip) = equippable.get(wants_to_use_item.item) {
-                let target_slot = can_equip.slot;
+                // What the target already has equipped, by slot.
+                let worn_in = |slot: EquipmentSlot| -> Option<Entity> {
+                    (&entities, &equipped)
+                        .join()
+                        .find(|(_e, eq)| eq.owner == target && eq.slot == slot)
+                        .map(|(e, _eq)| e)
+                };
+                let is_cursed = |item: Entity| -> bool {
+                    matches!(beatitudes.get(item), Some(b) if b.buc == BUC::Cursed)
+                };
+                let target_slot = match can_equip.slot {
+                    // Rings go on whichever hand is free, or replace one that isn't stuck on.
+                    EquipmentSlot::LeftRing | EquipmentSlot::RightRing => {
+                        match (worn_in(EquipmentSlot::LeftRing), worn_in(EquipmentSlot::RightRing)) {
+                            (None, _) => EquipmentSlot::LeftRing,
+                            (Some(_), None) => EquipmentSlot::RightRing,
+                            (Some(left), Some(_)) if is_cursed(left) => EquipmentSlot::RightRing,
+                            _ => EquipmentSlot::LeftRing,
+                        }
+                    }
+                    // A light weapon goes in the off-hand if the main hand is already
+                    // holding a one-handed weapon, and the off-hand is free.
+                    EquipmentSlot::Melee if off_handable.get(wants_to_use_item.item).is_some() => {
+                        let main_hand = worn_in(EquipmentSlot::Melee);
+                        let off_hand_free =
+                            worn_in(EquipmentSlot::Shield).is_none() &&
+                            worn_in(EquipmentSlot::OffHand).is_none();
+                        match main_hand {
+                            Some(main) if off_hand_free && two_handed.get(main).is_none() => {
+                                EquipmentSlot::OffHand
+                            }
+                            _ => EquipmentSlot::Melee,
+                        }
+                    }
+                    slot => slot,
+                };
+                // Work out every slot that needs to be emptied. Two-handed weapons need
+                // both hands, and anything held in the off-hand needs a free main hand.
+                let mut slots_to_clear = vec![target_slot];
+                if two_handed.get(wants_to_use_item.item).is_some() {
+                    slots_to_clear.push(EquipmentSlot::Shield);
+                    slots_to_clear.push(EquipmentSlot::OffHand);
+                }
+                if matches!(target_slot, EquipmentSlot::Shield | EquipmentSlot::OffHand) {
+                    // A shield and an off-hand weapon can't be held at once.
+                    slots_to_clear.push(EquipmentSlot::Shield);
+                    slots_to_clear.push(EquipmentSlot::OffHand);
+                    if let Some(main) = worn_in(EquipmentSlot::Melee) {
+                        if two_handed.get(main).is_some() {
+                            slots_to_clear.push(EquipmentSlot::Melee);
+                        }
+                    }
+                }
                 let mut logger = gamelog::Logger::new();
-                // Remove any items target has in item's slot
+                // Remove any items target has in the slots being used
                 let mut can_equip = true;
                 let mut to_unequip: Vec<Entity> = Vec::new();
                 for (item_entity, already_equipped, _name) in (
@@ -72,7 +134,10 @@ impl<'a> System<'a> for ItemEquipSystem {
                     &equipped,
                     &names,
                 ).join() {
-                    if already_equipped.owner == target && already_equipped.slot == target_slot {
+                    if
+                        already_equipped.owner == target &&
+                        slots_to_clear.contains(&already_equipped.slot)
+                    {
                         if let Some(beatitude) = beatitudes.get(item_entity) {
                             if beatitude.buc == BUC::Cursed {
                                 can_equip = false;
@@ -86,6 +151,7 @@ impl<'a> System<'a> for ItemEquipSystem {
                                             &magic_items,
                                             &obfuscated_names,
                                             &beatitudes,
+                                            &enchantments,
                                             &dm,
                                             None
                                         ).0
@@ -120,6 +186,7 @@ impl<'a> System<'a> for ItemEquipSystem {
                                     &magic_items,
                                     &obfuscated_names,
                                     &beatitudes,
+                                    &enchantments,
                                     &dm,
                                     None
                                 ).0
@@ -145,6 +212,7 @@ impl<'a> System<'a> for ItemEquipSystem {
                                 &magic_items,
                                 &obfuscated_names,
                                 &beatitudes,
+                                &enchantments,
                                 &dm,
                                 None
                             ).0
@@ -12,6 +12,7 @@ use crate::{
     WantsToAssignKey,
     WantsToRemoveKey,
     Key,
+    Enchantment,
 };
 use specs::prelude::*;
 use crate::data::messages;
@@ -29,7 +30,7 @@ impl<'a> System<'a> for KeyHandling {
         WriteStorage<'a, WantsToAssignKey>,
         WriteStorage<'a, WantsToRemoveKey>,
         WriteStorage<'a, Key>,
-        ReadStorage<'a, Stackable>,
+        WriteStorage<'a, Stackable>,
         ReadStorage<'a, Name>,
         ReadStorage<'a, ObfuscatedName>,
         ReadStorage<'a, Renderable>,
@@ -37,6 +38,7 @@ impl<'a> System<'a> for KeyHandling {
         ReadStorage<'a, MagicItem>,
         ReadStorage<'a, Charges>,
         ReadExpect<'a, MasterDungeonMap>,
+        ReadStorage<'a, Enchantment>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -45,7 +47,7 @@ impl<'a> System<'a> for KeyHandling {
             mut wants_keys,
             mut wants_removekey,
             mut keys,
-            stackable,
+            mut stackable,
             names,
             obfuscated_names,
             renderables,
@@ -53,6 +55,7 @@ impl<'a> System<'a> for KeyHandling {
             magic_items,
             wands,
             dm,
+            enchantments,
         ) = data;
 
         // For every entity that wants to be picked up, that still needs a key assigned.
@@ -69,6 +72,7 @@ impl<'a> System<'a> for KeyHandling {
                     &obfuscated_names,
                     &renderables,
                     &beatitudes,
+                    &enchantments,
                     &magic_items,
                     Some(&wands),
                     &dm
@@ -76,13 +80,26 @@ impl<'a> System<'a> for KeyHandling {
             );
             if stacks {
                 console::log(&format!("KEYHANDLING: Item is stackable."));
-                let maybe_key = item_exists(&unique);
-                if maybe_key.is_some() {
-                    console::log(&format!("KEYHANDLING: Existing stack found for this item."));
-                    let key = maybe_key.unwrap();
-                    keys.insert(e, Key { idx: key }).expect("Unable to insert Key.");
-                    console::log(&format!("KEYHANDLING: Assigned key idx {} to item.", key));
-                    handled = true;
+                if let Some(key) = item_exists(&unique) {
+                    // Find the stack already using this key, and merge this item into it.
+                    let existing = (&entities, &keys)
+                        .join()
+                        .find(|(entity, k)| *entity != e && k.idx == key)
+                        .map(|(entity, _k)| entity);
+                    if let Some(existing) = existing {
+                        console::log(
+                            &format!("KEYHANDLING: Merging item into existing stack at idx {}.", key)
+                        );
+                        let incoming = stackable
+                            .get(e)
+                            .map(|s| s.quantity)
+                            .unwrap_or(1);
+                        if let Some(stack) = stackable.get_mut(existing) {
+                            stack.quantity += incoming;
+                        }
+                        entities.delete(e).expect("Unable to delete merged item.");
+                        handled = true;
+                    }
                 }
             }
             if !handled {
@@ -107,43 +124,18 @@ impl<'a> System<'a> for KeyHandling {
             }
         }
         for (e, _wants_key) in (&entities, &wants_removekey).join() {
-            let idx = keys.get(e).unwrap().idx;
+            // Items split off from a stack never had a key of their own.
+            let idx = if let Some(key) = keys.get(e) {
+                key.idx
+            } else {
+                continue;
+            };
             if DEBUG_KEYHANDLING {
                 console::log(&format!("KEYHANDLING: Removing key from {:?}", e));
             }
-            // If the item is *not* stackable, then we can just remove the key and clear the index.
-            if let None = stackable.get(e) {
-                console::log(
-                    &format!("KEYHANDLING: Item is not stackable, clearing index {}.", idx)
-                );
-                clear_idx(idx);
-                keys.remove(e);
-                continue;
-            }
-            // If the item *is* stackable, then we need to check if there are any other items that
-            // share this key assignment, before clearing the index.
-            console::log(
-                &format!(
-                    "KEYHANDLING: Item is stackable, checking if any other items share this key."
-                )
-            );
-            let mut sole_item_with_key = true;
-            for (entity, key) in (&entities, &keys).join() {
-                if entity != e && key.idx == idx {
-                    console::log(&format!("KEYHANDLING: Another item shares index {}", idx));
-                    sole_item_with_key = false;
-                    break;
-                }
-            }
-            // If no other items shared this key, free up the index.
-            if sole_item_with_key {
-                console::log(
-                    &format!("KEYHANDLING: No other items found, clearing index {}.", idx)
-                );
-                clear_idx(idx);
-            }
-            // Either way, remove the key component from this item, because we're dropping it.
-            console::log(&format!("KEYHANDLING: Removing key component from item."));
+            // Every stack is a single entity, so nothing else can be sharing this key.
+            console::log(&format!("KEYHANDLING: Clearing index {}.", idx));
+            clear_idx(idx);
             keys.remove(e);
         }
 
@@ -1,3 +1,6 @@
+use crate::{ Beatitude, Enchantment, RawItem };
+use specs::prelude::*;
+
 mod collection_system;
 mod drop_system;
 mod equip_system;
@@ -5,6 +8,8 @@ mod identification_system;
 mod remove_system;
 mod use_system;
 mod keyhandling;
+mod shop_system;
+mod container_system;
 
 pub use self::{
     collection_system::ItemCollectionSystem,
@@ -14,4 +19,25 @@ pub use self::{
     remove_system::ItemRemoveSystem,
     use_system::ItemUseSystem,
     keyhandling::KeyHandling,
+    shop_system::{ shop_floor, ShopSystem },
+    container_system::ContainerSystem,
 };
+
+/// Whether two items are the same thing, as far as stacking goes. Anything that would be
+/// told apart in an inventory - its beatitude or enchantment - keeps them separate.
+pub fn stacks_with(
+    a: Entity,
+    b: Entity,
+    raw_items: &ReadStorage<RawItem>,
+    beatitudes: &ReadStorage<Beatitude>,
+    enchantments: &ReadStorage<Enchantment>
+) -> bool {
+    let same_item = match (raw_items.get(a), raw_items.get(b)) {
+        (Some(a), Some(b)) => a.id == b.id,
+        _ => false,
+    };
+    same_item &&
+        beatitudes.get(a) == beatitudes.get(b) &&
+        enchantments.get(a).map(|e| (e.amount, e.known)) ==
+            enchantments.get(b).map(|e| (e.amount, e.known))
+}
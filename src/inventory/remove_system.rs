@@ -10,6 +10,7 @@ use crate::{
     ObfuscatedName,
     WantsToRemoveItem,
     BUC,
+    Enchantment,
 };
 use bracket_lib::prelude::*;
 use specs::prelude::*;
@@ -30,6 +31,7 @@ impl<'a> System<'a> for ItemRemoveSystem {
         ReadStorage<'a, ObfuscatedName>,
         ReadStorage<'a, Beatitude>,
         ReadExpect<'a, MasterDungeonMap>,
+        ReadStorage<'a, Enchantment>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -44,6 +46,7 @@ impl<'a> System<'a> for ItemRemoveSystem {
             obfuscated_names,
             beatitudes,
             dm,
+            enchantments,
         ) = data;
 
         for (entity, to_remove) in (&entities, &wants_remove).join() {
@@ -63,6 +66,7 @@ impl<'a> System<'a> for ItemRemoveSystem {
                                 &magic_items,
                                 &obfuscated_names,
                                 &beatitudes,
+                                &enchantments,
                                 &dm,
                                 None
                             ).0
@@ -90,6 +94,7 @@ impl<'a> System<'a> for ItemRemoveSystem {
                                 &magic_items,
                                 &obfuscated_names,
                                 &beatitudes,
+                                &enchantments,
                                 &dm,
                                 None
                             ).0
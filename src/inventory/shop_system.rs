@@ -0,0 +1,278 @@
+use crate::{
+    gamelog,
+    gamesystem::{ buy_price, sell_price },
+    gui::obfuscate_name,
+    gui::item_colour,
+    gui::quantity,
+    gui::with_quantity,
+    Attributes,
+    Beatitude,
+    Charges,
+    EquipmentChanged,
+    ForSale,
+    InBackpack,
+    Item,
+    MagicItem,
+    MasterDungeonMap,
+    Name,
+    ObfuscatedName,
+    Position,
+    Purse,
+    WantsToAssignKey,
+    WantsToBuy,
+    WantsToRemoveKey,
+    WantsToSell,
+    BUC,
+    Enchantment,
+    Key,
+    Stackable,
+    Map,
+};
+use specs::prelude::*;
+use crate::data::messages;
+use crate::data::entity::SHOP_RADIUS;
+use bracket_lib::prelude::*;
+use std::collections::HashSet;
+
+/// The tiles a shopkeeper standing at origin can see that share the floor type
+/// they're standing on, i.e. the floor of their shop, nearest first.
+pub fn shop_floor(map: &Map, origin: Point) -> Vec<Point> {
+    let floor = map.tiles[map.xy_idx(origin.x, origin.y)];
+    let mut tiles: Vec<Point> = field_of_view(origin, SHOP_RADIUS, map)
+        .into_iter()
+        .filter(|pt| map.in_bounds(*pt) && *pt != origin && map.tiles[map.xy_idx(pt.x, pt.y)] == floor)
+        .collect();
+    tiles.sort_by_key(|pt| (pt.x - origin.x).abs().max((pt.y - origin.y).abs()));
+    return tiles;
+}
+
+pub struct ShopSystem {}
+
+impl<'a> System<'a> for ShopSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        Entities<'a>,
+        WriteStorage<'a, WantsToBuy>,
+        WriteStorage<'a, WantsToSell>,
+        WriteStorage<'a, ForSale>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, Purse>,
+        WriteStorage<'a, EquipmentChanged>,
+        WriteStorage<'a, WantsToAssignKey>,
+        WriteStorage<'a, WantsToRemoveKey>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, MagicItem>,
+        ReadStorage<'a, ObfuscatedName>,
+        ReadStorage<'a, Beatitude>,
+        ReadExpect<'a, MasterDungeonMap>,
+        ReadStorage<'a, Charges>,
+        ReadStorage<'a, Enchantment>,
+        ReadStorage<'a, Stackable>,
+        ReadStorage<'a, Key>,
+        ReadExpect<'a, Map>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            entities,
+            mut wants_buy,
+            mut wants_sell,
+            mut for_sale,
+            mut positions,
+            mut backpack,
+            mut purses,
+            mut equipment_changed,
+            mut assign_keys,
+            mut remove_keys,
+            items,
+            attributes,
+            names,
+            magic_items,
+            obfuscated_names,
+            beatitudes,
+            dm,
+            wands,
+            enchantments,
+            stackables,
+            keys,
+            map,
+        ) = data;
+
+        let item_name = |item: Entity| -> String {
+            with_quantity(
+                obfuscate_name(
+                    item,
+                    &names,
+                    &magic_items,
+                    &obfuscated_names,
+                    &beatitudes,
+                    &enchantments,
+                    &dm,
+                    Some(&wands)
+                ),
+                quantity(item, &stackables)
+            )
+        };
+        let item_buc = |item: Entity| -> BUC {
+            beatitudes
+                .get(item)
+                .map(|b| b.buc.clone())
+                .unwrap_or(BUC::Uncursed)
+        };
+        let item_value = |item: Entity| -> f32 {
+            items
+                .get(item)
+                .map(|i| i.value)
+                .unwrap_or(0.0)
+        };
+
+        for (entity, buy) in (&entities, &wants_buy).join() {
+            // Make sure the item is still on the shelf.
+            if for_sale.get(buy.item).map(|s| s.shopkeeper) != Some(buy.shopkeeper) {
+                continue;
+            }
+            let charisma = attributes
+                .get(entity)
+                .map(|a| a.charisma.base + a.charisma.modifiers)
+                .unwrap_or(10);
+            let price =
+                buy_price(item_value(buy.item), &item_buc(buy.item), charisma) *
+                quantity(buy.item, &stackables);
+            let gold = purses
+                .get(entity)
+                .map(|p| p.gold)
+                .unwrap_or(0);
+            if gold < price {
+                if entity == *player_entity {
+                    gamelog::Logger
+                        ::new()
+                        .append(messages::SHOP_CANT_AFFORD)
+                        .colour(item_colour(buy.item, &beatitudes))
+                        .append_n(item_name(buy.item))
+                        .colour(WHITE)
+                        .period()
+                        .log();
+                }
+                continue;
+            }
+            if let Some(purse) = purses.get_mut(entity) {
+                purse.gold -= price;
+            }
+            if let Some(purse) = purses.get_mut(buy.shopkeeper) {
+                purse.gold += price;
+            }
+            for_sale.remove(buy.item);
+            positions.remove(buy.item);
+            backpack.insert(buy.item, InBackpack { owner: entity }).expect("Unable to insert InBackpack");
+            equipment_changed.insert(entity, EquipmentChanged {}).expect("Unable to insert EquipmentChanged");
+            if entity == *player_entity {
+                assign_keys.insert(buy.item, WantsToAssignKey {}).expect("Unable to insert WantsToAssignKey");
+                gamelog::Logger
+                    ::new()
+                    .append(messages::SHOP_BUY_ITEM)
+                    .colour(item_colour(buy.item, &beatitudes))
+                    .append_n(item_name(buy.item))
+                    .colour(WHITE)
+                    .append("for")
+                    .colour(GOLD)
+                    .append(format!("{} gold", price))
+                    .colour(WHITE)
+                    .period()
+                    .log();
+            }
+        }
+
+        for (entity, sell) in (&entities, &wants_sell).join() {
+            if backpack.get(sell.item).map(|b| b.owner) != Some(entity) {
+                continue;
+            }
+            let price =
+                sell_price(item_value(sell.item), &item_buc(sell.item)) * quantity(sell.item, &stackables);
+            let shopkeeper_gold = purses
+                .get(sell.shopkeeper)
+                .map(|p| p.gold)
+                .unwrap_or(0);
+            if shopkeeper_gold < price {
+                if entity == *player_entity {
+                    // Anything split off a stack to be sold goes back onto it.
+                    if keys.get(sell.item).is_none() {
+                        assign_keys
+                            .insert(sell.item, WantsToAssignKey {})
+                            .expect("Unable to insert WantsToAssignKey");
+                    }
+                    if let Some(name) = names.get(sell.shopkeeper) {
+                        gamelog::Logger
+                            ::new()
+                            .append("The")
+                            .append(&name.name)
+                            .append(messages::SHOP_SHOPKEEPER_CANT_AFFORD)
+                            .colour(item_colour(sell.item, &beatitudes))
+                            .append_n(item_name(sell.item))
+                            .colour(WHITE)
+                            .period()
+                            .log();
+                    }
+                }
+                continue;
+            }
+            let keeper_pos = if let Some(pos) = positions.get(sell.shopkeeper) {
+                Point::new(pos.x, pos.y)
+            } else {
+                continue;
+            };
+            if let Some(purse) = purses.get_mut(sell.shopkeeper) {
+                purse.gold -= price;
+            }
+            if let Some(purse) = purses.get_mut(entity) {
+                purse.gold += price;
+            } else {
+                purses.insert(entity, Purse { gold: price }).expect("Unable to insert Purse");
+            }
+            backpack.remove(sell.item);
+            // Sold goods go out on the shop floor as stock, or into the shopkeeper's pack if
+            // there's no room left - never under the seller's feet, where picking them back
+            // up would be theft.
+            let occupied: HashSet<usize> = positions
+                .join()
+                .map(|pos| map.xy_idx(pos.x, pos.y))
+                .collect();
+            let shelf = shop_floor(&map, keeper_pos)
+                .into_iter()
+                .find(|pt| !occupied.contains(&map.xy_idx(pt.x, pt.y)));
+            if let Some(shelf) = shelf {
+                positions.insert(sell.item, Position { x: shelf.x, y: shelf.y }).expect("Unable to insert Position");
+                for_sale
+                    .insert(sell.item, ForSale { shopkeeper: sell.shopkeeper })
+                    .expect("Unable to insert ForSale");
+            } else {
+                backpack
+                    .insert(sell.item, InBackpack { owner: sell.shopkeeper })
+                    .expect("Unable to insert InBackpack");
+            }
+            equipment_changed.insert(entity, EquipmentChanged {}).expect("Unable to insert EquipmentChanged");
+            if entity == *player_entity {
+                remove_keys.insert(sell.item, WantsToRemoveKey {}).expect("Unable to insert WantsToRemoveKey");
+                gamelog::Logger
+                    ::new()
+                    .append(messages::SHOP_SELL_ITEM)
+                    .colour(item_colour(sell.item, &beatitudes))
+                    .append_n(item_name(sell.item))
+                    .colour(WHITE)
+                    .append("for")
+                    .colour(GOLD)
+                    .append(format!("{} gold", price))
+                    .colour(WHITE)
+                    .period()
+                    .log();
+            }
+        }
+
+        wants_buy.clear();
+        wants_sell.clear();
+    }
+}
@@ -1,10 +1,13 @@
 use crate::{
     effects::{ add_effect, aoe_tiles, EffectType, Targets },
+    gamelog,
+    Eating,
     EquipmentChanged,
     IdentifiedItem,
     IdentifiedBeatitude,
     Map,
     Name,
+    ProvidesNutrition,
     WantsToUseItem,
     AOE,
 };
@@ -24,6 +27,8 @@ impl<'a> System<'a> for ItemUseSystem {
         WriteStorage<'a, EquipmentChanged>,
         WriteStorage<'a, IdentifiedItem>,
         WriteStorage<'a, IdentifiedBeatitude>,
+        ReadStorage<'a, ProvidesNutrition>,
+        WriteStorage<'a, Eating>,
     );
 
     #[allow(clippy::cognitive_complexity)]
@@ -38,6 +43,8 @@ impl<'a> System<'a> for ItemUseSystem {
             mut dirty,
             mut identified_item,
             mut identified_beatitude,
+            nutrition,
+            mut eating,
         ) = data;
 
         for (entity, useitem) in (&entities, &wants_use).join() {
@@ -49,6 +56,23 @@ impl<'a> System<'a> for ItemUseSystem {
                     .expect("Unable to insert");
                 identified_beatitude.insert(useitem.item, IdentifiedBeatitude {}).expect("Unable to push");
             }
+            // Food that takes more than a turn to eat is eaten over time, instead of all at once.
+            if let Some(food) = nutrition.get(useitem.item) {
+                if food.turns > 1 {
+                    eating
+                        .insert(entity, Eating { food: useitem.item, turns: food.turns - 1 })
+                        .expect("Unable to insert Eating");
+                    if entity == *player_entity {
+                        gamelog::Logger
+                            ::new()
+                            .append("You begin eating the")
+                            .append_n(&names.get(useitem.item).unwrap().name)
+                            .period()
+                            .log();
+                    }
+                    continue;
+                }
+            }
             // Call the effects system
             add_effect(Some(entity), EffectType::ItemUse { item: useitem.item }, match useitem.target {
                 None => Targets::Entity { target: *player_entity },
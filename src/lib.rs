@@ -24,6 +24,7 @@ pub mod spawner;
 pub mod visibility_system;
 pub mod damage_system;
 pub mod hunger_system;
+pub mod corpse_system;
 pub mod melee_combat_system;
 pub mod trigger_system;
 pub mod inventory;
@@ -39,6 +40,8 @@ pub mod spatial;
 pub mod morgue;
 pub mod states;
 pub mod invkeys;
+pub mod quests;
+pub mod reputation;
 
 pub use components::*;
 use particle_system::ParticleBuilder;
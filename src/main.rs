@@ -43,9 +43,18 @@ fn main() -> BError {
     gs.ecs.register::<HasAncestry>();
     gs.ecs.register::<HasClass>();
     gs.ecs.register::<Chasing>();
+    gs.ecs.register::<Fleeing>();
+    gs.ecs.register::<LastSeen>();
+    gs.ecs.register::<Leader>();
+    gs.ecs.register::<GroupMember>();
+    gs.ecs.register::<Unique>();
     gs.ecs.register::<Faction>();
+    gs.ecs.register::<Ally>();
+    gs.ecs.register::<Tameable>();
+    gs.ecs.register::<Hireling>();
     gs.ecs.register::<Clock>();
     gs.ecs.register::<Quips>();
+    gs.ecs.register::<Dialogue>();
     gs.ecs.register::<Mind>();
     gs.ecs.register::<Viewshed>();
     gs.ecs.register::<Telepath>();
@@ -61,6 +70,7 @@ fn main() -> BError {
     gs.ecs.register::<WantsToMelee>();
     gs.ecs.register::<Item>();
     gs.ecs.register::<Beatitude>();
+    gs.ecs.register::<Enchantment>();
     gs.ecs.register::<IdentifiedItem>();
     gs.ecs.register::<IdentifiedBeatitude>();
     gs.ecs.register::<MagicItem>();
@@ -71,8 +81,13 @@ fn main() -> BError {
     gs.ecs.register::<Equippable>();
     gs.ecs.register::<EquipmentChanged>();
     gs.ecs.register::<Equipped>();
+    gs.ecs.register::<TwoHanded>();
+    gs.ecs.register::<OffHandable>();
+    gs.ecs.register::<WeaponSkill>();
+    gs.ecs.register::<LevelHistory>();
     gs.ecs.register::<MeleeWeapon>();
     gs.ecs.register::<NaturalAttacks>();
+    gs.ecs.register::<Abilities>();
     gs.ecs.register::<ArmourClassBonus>();
     gs.ecs.register::<ToHitBonus>();
     gs.ecs.register::<MoveMode>();
@@ -82,7 +97,10 @@ fn main() -> BError {
     gs.ecs.register::<AOE>();
     gs.ecs.register::<Digger>();
     gs.ecs.register::<Confusion>();
+    gs.ecs.register::<Fainted>();
     gs.ecs.register::<Blind>();
+    gs.ecs.register::<Aquatic>();
+    gs.ecs.register::<Levitating>();
     gs.ecs.register::<MagicMapper>();
     gs.ecs.register::<InBackpack>();
     gs.ecs.register::<WantsToApproach>();
@@ -95,6 +113,9 @@ fn main() -> BError {
     gs.ecs.register::<SingleActivation>();
     gs.ecs.register::<Charges>();
     gs.ecs.register::<ProvidesNutrition>();
+    gs.ecs.register::<LeavesCorpse>();
+    gs.ecs.register::<Corpse>();
+    gs.ecs.register::<Eating>();
     gs.ecs.register::<Destructible>();
     gs.ecs.register::<Hidden>();
     gs.ecs.register::<EntryTrigger>();
@@ -102,6 +123,8 @@ fn main() -> BError {
     gs.ecs.register::<MultiAttack>();
     gs.ecs.register::<ProvidesRemoveCurse>();
     gs.ecs.register::<ProvidesIdentify>();
+    gs.ecs.register::<ProvidesEnchantWeapon>();
+    gs.ecs.register::<ProvidesEnchantArmour>();
     gs.ecs.register::<KnownSpells>();
     gs.ecs.register::<GrantsSpell>();
     gs.ecs.register::<Bleeds>();
@@ -113,10 +136,24 @@ fn main() -> BError {
     gs.ecs.register::<Intrinsics>();
     gs.ecs.register::<IntrinsicChanged>();
     gs.ecs.register::<Stackable>();
+    gs.ecs.register::<RawItem>();
     gs.ecs.register::<WantsToAssignKey>();
     gs.ecs.register::<Key>();
     gs.ecs.register::<WantsToRemoveKey>();
     gs.ecs.register::<WantsToDelete>();
+    gs.ecs.register::<Purse>();
+    gs.ecs.register::<Currency>();
+    gs.ecs.register::<Shopkeeper>();
+    gs.ecs.register::<ForSale>();
+    gs.ecs.register::<WantsToBuy>();
+    gs.ecs.register::<WantsToSell>();
+    gs.ecs.register::<Container>();
+    gs.ecs.register::<InContainer>();
+    gs.ecs.register::<Locked>();
+    gs.ecs.register::<Trapped>();
+    gs.ecs.register::<Trapdoor>();
+    gs.ecs.register::<WantsToPutInContainer>();
+    gs.ecs.register::<WantsToTakeFromContainer>();
     gs.ecs.register::<SimpleMarker<SerializeMe>>();
     gs.ecs.register::<SerializationHelper>();
     gs.ecs.register::<DMSerializationHelper>();
@@ -127,6 +164,8 @@ fn main() -> BError {
     // Insert calls
     gs.ecs.insert(RandomNumberGenerator::new());
     gs.ecs.insert(map::MasterDungeonMap::new()); // Master map list
+    gs.ecs.insert(quests::QuestLog::new()); // Quests taken this run
+    gs.ecs.insert(ai::FlowFields::new()); // Shared monster pathing
     gs.ecs.insert(Map::new(true, 1, 64, 64, 0, "New Map", "N", 0)); // Map
     gs.ecs.insert(Point::new(0, 0)); // Player pos
     gs.ecs.insert(gui::Ancestry::Human); // ancestry
@@ -1,11 +1,33 @@
-use super::{ Map, TileType };
-use crate::{ gamelog, map_builders, OtherLevelPosition, Position, Telepath, Viewshed };
+use super::{ tile_walkable, Map, TileType };
+use crate::{
+    gamelog,
+    map_builders,
+    Ally,
+    AllyCommand,
+    BlocksTile,
+    Chasing,
+    OtherLevelPosition,
+    Position,
+    Telepath,
+    Viewshed,
+};
+use crate::data::entity::ALLY_TRAVEL_DISTANCE;
 use bracket_lib::prelude::*;
 use serde::{ Deserialize, Serialize };
 use specs::prelude::*;
 use std::collections::{ HashMap, HashSet };
 use crate::data::events::*;
 
+/// What's become of a unique mob, once it's been spawned. Slain records the id of
+/// the level it died on.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum UniqueState {
+    Alive,
+    Slain {
+        map_id: i32,
+    },
+}
+
 #[derive(Default, Serialize, Deserialize, Clone)]
 pub struct MasterDungeonMap {
     maps: HashMap<i32, Map>,
@@ -13,6 +35,8 @@ pub struct MasterDungeonMap {
     pub scroll_map: HashMap<String, String>,
     pub potion_map: HashMap<String, String>,
     pub wand_map: HashMap<String, String>,
+    uniques: HashMap<String, UniqueState>,
+    place_names: HashMap<String, String>,
 }
 
 impl MasterDungeonMap {
@@ -24,6 +48,8 @@ impl MasterDungeonMap {
             scroll_map: HashMap::new(),
             potion_map: HashMap::new(),
             wand_map: HashMap::new(),
+            uniques: HashMap::new(),
+            place_names: HashMap::new(),
         };
         // TODO: Use stored RNG
         let mut rng = RandomNumberGenerator::new();
@@ -41,6 +67,11 @@ impl MasterDungeonMap {
             let unid_singular = make_wand_name(&mut rng, &mut used_wand_names);
             dm.wand_map.insert(wand_tag.to_string(), unid_singular);
         }
+        let mut used_place_names: HashSet<String> = HashSet::new();
+        for branch in crate::raws::get_named_branches().iter() {
+            let place_name = make_place_name(&mut rng, &mut used_place_names);
+            dm.place_names.insert(branch.to_string(), place_name);
+        }
 
         return dm;
     }
@@ -57,6 +88,41 @@ impl MasterDungeonMap {
             return None;
         }
     }
+    /// Marks a unique mob as spawned, returning false if it already has been - in which
+    /// case, it mustn't be spawned again.
+    pub fn spawn_unique(&mut self, id: &str) -> bool {
+        if self.uniques.contains_key(id) {
+            return false;
+        }
+        self.uniques.insert(id.to_string(), UniqueState::Alive);
+        return true;
+    }
+    /// Marks a unique mob as slain, on the level with the given id.
+    pub fn slay_unique(&mut self, id: &str, map_id: i32) {
+        self.uniques.insert(id.to_string(), UniqueState::Slain { map_id });
+    }
+    /// Gets the state of a unique mob, or None if it hasn't been spawned yet.
+    pub fn unique_state(&self, id: &str) -> Option<UniqueState> {
+        self.uniques.get(id).copied()
+    }
+    /// Gets the place name generated for a branch this game, if it has one.
+    pub fn place_name(&self, branch: &str) -> Option<&str> {
+        self.place_names.get(branch).map(|name| name.as_str())
+    }
+    /// Every unique that's been slain so far, and the id of the level it died on.
+    pub fn uniques_slain(&self) -> Vec<(String, i32)> {
+        let mut slain: Vec<(String, i32)> = self.uniques
+            .iter()
+            .filter_map(|(id, state)| {
+                match state {
+                    UniqueState::Slain { map_id } => Some((id.clone(), *map_id)),
+                    UniqueState::Alive => None,
+                }
+            })
+            .collect();
+        slain.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+        return slain;
+    }
 }
 
 fn make_scroll_name(rng: &mut RandomNumberGenerator) -> String {
@@ -195,6 +261,54 @@ fn make_wand_name(rng: &mut RandomNumberGenerator, used_names: &mut HashSet<Stri
     }
 }
 
+const PLACE_NAME_STARTS: &[&str] = &[
+    "Saff",
+    "Bel",
+    "Dun",
+    "Ash",
+    "Mor",
+    "Kel",
+    "Tor",
+    "Wyn",
+    "Hal",
+    "Bram",
+    "Ost",
+    "Gar",
+    "Fen",
+    "Lor",
+    "Mill",
+    "Crag",
+];
+const PLACE_NAME_ENDS: &[&str] = &[
+    "",
+    "ford",
+    "holm",
+    "wick",
+    "by",
+    "mere",
+    "stead",
+    "ton",
+    "mouth",
+    "dale",
+    "moor",
+    "haven",
+];
+
+fn make_place_name(rng: &mut RandomNumberGenerator, used_names: &mut HashSet<String>) -> String {
+    loop {
+        let mut name: String =
+            PLACE_NAME_STARTS[
+                (rng.roll_dice(1, PLACE_NAME_STARTS.len() as i32) as usize) - 1
+            ].to_string();
+        name += PLACE_NAME_ENDS[(rng.roll_dice(1, PLACE_NAME_ENDS.len() as i32) as usize) - 1];
+
+        if !used_names.contains(&name) {
+            used_names.insert(name.clone());
+            return name;
+        }
+    }
+}
+
 pub fn level_transition(ecs: &mut World, new_id: i32, dest_tile: TileType) -> Option<Vec<Map>> {
     // Obtain master
     let dungeon_master = ecs.read_resource::<MasterDungeonMap>();
@@ -269,12 +383,21 @@ fn transition_to_new_map(ecs: &mut World, new_id: i32, _dest_tile: TileType) ->
     let mut builder = map_builders::level_builder(new_id, &mut rng, 100, 50, player_level);
     builder.build_map(&mut rng);
     std::mem::drop(rng);
+    if
+        let Some((name, short_name)) = crate::raws::get_level_names(
+            &crate::raws::RAWS.lock().unwrap(),
+            &ecs.fetch::<MasterDungeonMap>(),
+            new_id
+        )
+    {
+        builder.build_data.map.name = name;
+        builder.build_data.map.short_name = short_name;
+    }
     let mapgen_history = builder.build_data.history.clone();
     let player_start;
     let old_map: Map;
     {
-        let mut worldmap_resource = ecs.write_resource::<Map>();
-        old_map = worldmap_resource.clone();
+        old_map = ecs.fetch::<Map>().clone();
         // If there is zero overmap involvement, place an upstair where we ended up.
         // Otherwise, this should be hand-placed.
         if !old_map.overmap && !builder.build_data.map.overmap {
@@ -282,13 +405,23 @@ fn transition_to_new_map(ecs: &mut World, new_id: i32, _dest_tile: TileType) ->
                 let up_idx = builder.build_data.map.xy_idx(pos.x, pos.y);
                 builder.build_data.map.tiles[up_idx] = TileType::UpStair;
             }
+        } else if old_map.overmap && !builder.build_data.map.tiles.contains(&TileType::ToOvermap(new_id)) {
+            // Coming in from the overmap to a level with no way back out, e.g. the first
+            // level of a branch - so leave one where we arrived.
+            if let Some(pos) = &builder.build_data.starting_position {
+                let exit_idx = builder.build_data.map.xy_idx(pos.x, pos.y);
+                builder.build_data.map.tiles[exit_idx] = TileType::ToOvermap(new_id);
+            }
         }
-        *worldmap_resource = builder.build_data.map.clone();
-        // Unwrap so we get a CTD if there's no starting pos.
-        player_start = builder.build_data.starting_position.as_mut().unwrap().clone();
     }
-    // Spawn entities
+    // Hide any quest items meant for this level - this can build onto it, so it happens
+    // before the map is stored - and then spawn entities.
+    crate::quests::on_new_level(ecs, &mut builder.build_data);
+    *ecs.write_resource::<Map>() = builder.build_data.map.clone();
+    // Unwrap so we get a CTD if there's no starting pos.
+    player_start = builder.build_data.starting_position.as_mut().unwrap().clone();
     builder.spawn_entities(ecs);
+    crate::ai::form_groups(ecs);
     // Place player and update resources
     let mut player_position = ecs.write_resource::<Point>();
     *player_position = Point::new(player_start.x, player_start.y);
@@ -318,49 +451,200 @@ fn transition_to_new_map(ecs: &mut World, new_id: i32, _dest_tile: TileType) ->
 }
 
 /// Iterate through entities on the current level, save the current position and floor
-/// of each entity-to-be-frozen, and then delete their current position.
-pub fn freeze_entities(ecs: &mut World) {
+/// of each entity-to-be-frozen, and then delete their current position. Unless the player
+/// is leaving without them (i.e. by falling), following allies close enough to the player
+/// aren't frozen, and are returned so they can be brought along.
+pub fn freeze_entities(ecs: &mut World, bring_allies: bool) -> Vec<Entity> {
     // Obtain reqs from ECS
     let entities = ecs.entities();
     let mut positions = ecs.write_storage::<Position>();
     let mut other_positions = ecs.write_storage::<OtherLevelPosition>();
+    let allies = ecs.read_storage::<Ally>();
     let player_entity = ecs.fetch::<Entity>();
+    let player_pos = *ecs.fetch::<Point>();
     let map_id = ecs.fetch::<Map>().id;
     // Save Positions and mark for deletion
     let mut pos_to_delete: Vec<Entity> = Vec::new();
+    let mut travelling: Vec<Entity> = Vec::new();
     for (entity, pos) in (&entities, &positions).join() {
-        if entity != *player_entity {
-            other_positions
-                .insert(entity, OtherLevelPosition { x: pos.x, y: pos.y, id: map_id })
-                .expect("Failed to insert OtherLevelPosition");
-            pos_to_delete.push(entity);
+        if entity == *player_entity {
+            continue;
+        }
+        if let Some(ally) = allies.get(entity).filter(|_| bring_allies) {
+            let distance = DistanceAlg::Pythagoras.distance2d(player_pos, Point::new(pos.x, pos.y));
+            if
+                ally.owner == *player_entity &&
+                ally.command != AllyCommand::Stay &&
+                distance <= ALLY_TRAVEL_DISTANCE
+            {
+                travelling.push(entity);
+                continue;
+            }
         }
+        other_positions
+            .insert(entity, OtherLevelPosition { x: pos.x, y: pos.y, id: map_id })
+            .expect("Failed to insert OtherLevelPosition");
+        pos_to_delete.push(entity);
     }
     for p in pos_to_delete.iter() {
         positions.remove(*p);
     }
+    return travelling;
+}
+
+/// Puts each of the given allies on a free tile as close to the player as possible.
+/// Used to bring allies along after a level transition has placed the player.
+pub fn place_allies(ecs: &mut World, allies: &[Entity]) {
+    let entities = ecs.entities();
+    let map = ecs.fetch::<Map>();
+    let player_pos = *ecs.fetch::<Point>();
+    let blockers = ecs.read_storage::<BlocksTile>();
+    let mut ally_components = ecs.write_storage::<Ally>();
+    let mut positions = ecs.write_storage::<Position>();
+    let mut viewsheds = ecs.write_storage::<Viewshed>();
+    let mut telepaths = ecs.write_storage::<Telepath>();
+    let mut chasing = ecs.write_storage::<Chasing>();
+    // Anything else already blocking a tile on this map is in the way.
+    let mut occupied: HashSet<usize> = HashSet::new();
+    occupied.insert(map.xy_idx(player_pos.x, player_pos.y));
+    for (entity, pos, _blocks) in (&entities, &positions, &blockers).join() {
+        if !allies.contains(&entity) {
+            occupied.insert(map.xy_idx(pos.x, pos.y));
+        }
+    }
+    for ally_entity in allies.iter() {
+        let mut destination = player_pos;
+        'search: for radius in 1..=3 {
+            for y in player_pos.y - radius..=player_pos.y + radius {
+                for x in player_pos.x - radius..=player_pos.x + radius {
+                    if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 {
+                        continue;
+                    }
+                    let idx = map.xy_idx(x, y);
+                    if tile_walkable(map.tiles[idx]) && !occupied.contains(&idx) {
+                        destination = Point::new(x, y);
+                        occupied.insert(idx);
+                        break 'search;
+                    }
+                }
+            }
+        }
+        positions
+            .insert(*ally_entity, Position { x: destination.x, y: destination.y })
+            .expect("Unable to insert Position");
+        // Whatever they were fighting got left behind.
+        chasing.remove(*ally_entity);
+        if let Some(ally) = ally_components.get_mut(*ally_entity) {
+            if ally.command == AllyCommand::Attack {
+                ally.command = AllyCommand::Follow;
+            }
+        }
+        if let Some(viewshed) = viewsheds.get_mut(*ally_entity) {
+            viewshed.dirty = true;
+        }
+        if let Some(telepath) = telepaths.get_mut(*ally_entity) {
+            telepath.dirty = true;
+        }
+    }
 }
 
 /// Iterate through entities, and insert a Position component if the
-/// entity has an OtherLevelPosition for the new map id.
+/// entity has an OtherLevelPosition for the new map id. Anything that fell here before the
+/// level was built has nowhere to be yet, so it lands somewhere random instead.
 pub fn thaw_entities(ecs: &mut World) {
     // Obtain reqs from ECS
     let entities = ecs.entities();
     let mut positions = ecs.write_storage::<Position>();
     let mut other_positions = ecs.write_storage::<OtherLevelPosition>();
     let player_entity = ecs.fetch::<Entity>();
-    let map_id = ecs.fetch::<Map>().id;
-    // Save Positions and mark for deletion
-    let mut pos_to_delete: Vec<Entity> = Vec::new();
-    for (entity, pos) in (&entities, &other_positions).join() {
-        if entity != *player_entity && pos.id == map_id {
-            positions
-                .insert(entity, Position { x: pos.x, y: pos.y })
-                .expect("Failed to insert OtherLevelPosition");
-            pos_to_delete.push(entity);
-        }
+    let map = ecs.fetch::<Map>();
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let arriving: Vec<(Entity, OtherLevelPosition)> = (&entities, &other_positions)
+        .join()
+        .filter(|(entity, pos)| *entity != *player_entity && pos.id == map.id)
+        .map(|(entity, pos)| (entity, pos.clone()))
+        .collect();
+    let in_bounds = |pos: &OtherLevelPosition| {
+        pos.x >= 0 && pos.x < map.width && pos.y >= 0 && pos.y < map.height
+    };
+    // Anything that knows where it was goes back there first, so that anything that fell
+    // here before the level was built doesn't land on top of it - or on anything else.
+    for (entity, pos) in arriving.iter().filter(|(_entity, pos)| in_bounds(pos)) {
+        positions.insert(*entity, Position { x: pos.x, y: pos.y }).expect("Failed to insert Position");
     }
-    for p in pos_to_delete.iter() {
-        other_positions.remove(*p);
+    let mut occupied: HashSet<usize> = (&positions)
+        .join()
+        .map(|pos| map.xy_idx(pos.x, pos.y))
+        .collect();
+    for (entity, pos) in arriving.iter().filter(|(_entity, pos)| !in_bounds(pos)) {
+        let (x, y) = if let Some(idx) = landing_spot(&map, &occupied, &mut rng) {
+            occupied.insert(idx);
+            ((idx as i32) % map.width, (idx as i32) / map.width)
+        } else {
+            (pos.x, pos.y)
+        };
+        positions.insert(*entity, Position { x, y }).expect("Failed to insert Position");
+    }
+    for (entity, _pos) in arriving.iter() {
+        other_positions.remove(*entity);
+    }
+}
+
+/// Whether there's a level below this one to fall to. There isn't one below the overmap,
+/// or below the last level of a branch that leads nowhere.
+pub fn has_level_below(map: &Map) -> bool {
+    if map.overmap {
+        return false;
+    }
+    let raws = &crate::raws::RAWS.lock().unwrap();
+    return crate::raws::get_branch(raws, map.id).map_or(true, |branch| !branch.is_dead_end(map.id));
+}
+
+/// A random tile on the map for something falling from above to land on - anywhere that
+/// can be stood on, isn't occupied, and won't give way underneath it again.
+pub fn landing_spot(
+    map: &Map,
+    occupied: &HashSet<usize>,
+    rng: &mut RandomNumberGenerator
+) -> Option<usize> {
+    let spots: Vec<usize> = (0..map.tiles.len())
+        .filter(|idx| {
+            tile_walkable(map.tiles[*idx]) &&
+                map.tiles[*idx] != TileType::ThinFloor &&
+                !occupied.contains(idx)
+        })
+        .collect();
+    if spots.is_empty() {
+        return None;
+    }
+    return Some(spots[(rng.roll_dice(1, spots.len() as i32) - 1) as usize]);
+}
+
+/// Moves the player somewhere random on the current map, for when they've fallen in from
+/// above rather than taking the stairs.
+pub fn place_fallen_player(ecs: &mut World) {
+    let entities = ecs.entities();
+    let map = ecs.fetch::<Map>();
+    let player_entity = ecs.fetch::<Entity>();
+    let blockers = ecs.read_storage::<BlocksTile>();
+    let mut positions = ecs.write_storage::<Position>();
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let occupied: HashSet<usize> = (&entities, &positions, &blockers)
+        .join()
+        .filter(|(entity, _pos, _blocks)| *entity != *player_entity)
+        .map(|(_entity, pos, _blocks)| map.xy_idx(pos.x, pos.y))
+        .collect();
+    let idx = if let Some(idx) = landing_spot(&map, &occupied, &mut rng) { idx } else { return };
+    let (x, y) = ((idx as i32) % map.width, (idx as i32) / map.width);
+    if let Some(pos) = positions.get_mut(*player_entity) {
+        pos.x = x;
+        pos.y = y;
+    }
+    *ecs.write_resource::<Point>() = Point::new(x, y);
+    if let Some(viewshed) = ecs.write_storage::<Viewshed>().get_mut(*player_entity) {
+        viewshed.dirty = true;
+    }
+    if let Some(telepath) = ecs.write_storage::<Telepath>().get_mut(*player_entity) {
+        telepath.dirty = true;
     }
 }
@@ -81,7 +81,7 @@ fn spawn_random_mob_in_free_nonvisible_tile(ecs: &mut World) {
     }
     let mut spawn_locations: Vec<(i32, i32)> = Vec::new();
     let mut rng = ecs.write_resource::<RandomNumberGenerator>();
-    let key = spawner::mob_table(Some(difficulty)).roll(&mut rng);
+    let key = spawner::level_table(&map, "mobs", Some(difficulty)).roll(&mut rng);
     let spawn_type = raws::get_mob_spawn_type(&raws::RAWS.lock().unwrap(), &key);
     let roll = raws::get_mob_spawn_amount(&mut rng, &spawn_type, player_level);
     for _i in 0..roll {
@@ -105,6 +105,7 @@ fn spawn_random_mob_in_free_nonvisible_tile(ecs: &mut World) {
             difficulty
         );
     }
+    crate::ai::form_groups(ecs);
 }
 
 /// Returns a Vec<usize> of every tile that is not blocked, and is not currently in the player's view.
@@ -2,11 +2,21 @@ use bracket_lib::prelude::*;
 use serde::{ Deserialize, Serialize };
 use std::collections::{ HashSet, HashMap };
 mod tiletype;
-pub use tiletype::{ tile_cost, tile_opaque, tile_walkable, TileType, get_dest, Destination };
+pub use tiletype::{
+    tile_cost,
+    tile_deep,
+    tile_hazardous,
+    tile_opaque,
+    tile_passable,
+    tile_walkable,
+    TileType,
+    get_dest,
+    Destination,
+};
 mod interval_spawning_system;
 pub use interval_spawning_system::{ maybe_map_message, try_spawn_interval };
 pub mod dungeon;
-pub use dungeon::{ level_transition, MasterDungeonMap };
+pub use dungeon::{ has_level_below, level_transition, MasterDungeonMap, UniqueState };
 pub mod themes;
 use super::data::visuals::{
     BRIGHTEN_FG_COLOUR_BY,
@@ -103,6 +103,10 @@ pub fn get_default_theme_renderables(idx: usize, map: &Map, debug: Option<bool>)
         TileType::Sand => { glyph = to_cp437(SAND_GLYPH); bg = RGB::named(SAND_COLOUR); offsets = SAND_OFFSETS; }
         TileType::ShallowWater => { glyph = to_cp437(SHALLOW_WATER_GLYPH); bg = RGB::named(SHALLOW_WATER_COLOUR); offsets = SHALLOW_WATER_OFFSETS; }
         TileType::DeepWater => { glyph = to_cp437(DEEP_WATER_GLYPH); bg = RGB::named(DEEP_WATER_COLOUR); offsets = DEEP_WATER_OFFSETS; }
+        TileType::Lava => { glyph = to_cp437(LAVA_GLYPH); bg = RGB::named(LAVA_COLOUR); offsets = LAVA_OFFSETS; }
+        TileType::Chasm => { glyph = to_cp437(CHASM_GLYPH); bg = RGB::named(CHASM_COLOUR); offsets = CHASM_OFFSETS; }
+        TileType::Ice => { glyph = to_cp437(ICE_GLYPH); bg = RGB::named(ICE_COLOUR); offsets = ICE_OFFSETS; }
+        TileType::ThinFloor => { glyph = to_cp437(THIN_FLOOR_GLYPH); fg = RGB::named(THIN_FLOOR_COLOUR); bg = RGB::named(DEFAULT_BG_COLOUR); offsets = THIN_FLOOR_OFFSETS; }
         TileType::Bars => { glyph = to_cp437(BARS_GLYPH); fg = RGB::named(BARS_COLOUR); bg = RGB::named(FLOOR_COLOUR); }
         TileType::ImpassableMountain => { glyph = to_cp437(IMPASSABLE_MOUNTAIN_GLYPH); bg = RGB::named(IMPASSABLE_MOUNTAIN_COLOUR); offsets = IMPASSABLE_MOUNTAIN_OFFSETS }
         TileType::ToOvermap(_) => { glyph = to_cp437(TO_OVERMAP_GLYPH); fg = RGB::named(TO_OVERMAP_COLOUR); bg = RGB::named(GRASS_COLOUR); }
@@ -20,19 +20,45 @@ pub enum TileType {
     Sand,
     ShallowWater,
     Bridge,
+    // Hazards (entering them has consequences)
+    Lava,
+    Chasm,
+    Ice,
+    ThinFloor,
     // Stairs (changes floor)
     DownStair,
     UpStair,
-    // To/From Overmap - ids are in src/data/ids.rs, are used in try_change_level() in src/player.rs
+    // To/From Overmap - ids are the branches in raws/dungeon.json, are used in try_change_level() in src/player.rs
     ToOvermap(i32),
     ToLocal(i32),
 }
 pub fn tile_walkable(tt: TileType) -> bool {
     match tt {
         TileType::ImpassableMountain | TileType::Wall | TileType::DeepWater | TileType::Fence | TileType::Bars => false,
+        TileType::Lava | TileType::Chasm => false,
         _ => true,
     }
 }
+/// Lava and chasms are never pathed through, and nothing is ever placed on them, but
+/// there's nothing stopping the player from stepping in on purpose.
+pub fn tile_hazardous(tt: TileType) -> bool {
+    match tt {
+        TileType::Lava | TileType::Chasm => true,
+        _ => false,
+    }
+}
+/// Deep water can't be stood in, but it can be swum across.
+pub fn tile_deep(tt: TileType) -> bool {
+    match tt {
+        TileType::DeepWater => true,
+        _ => false,
+    }
+}
+/// Anything that can be walked on or swum through. Moving and pathing go by this, and
+/// anything that places things on the map goes by tile_walkable.
+pub fn tile_passable(tt: TileType) -> bool {
+    return tile_walkable(tt) || tile_deep(tt);
+}
 pub fn tile_opaque(tt: TileType) -> bool {
     match tt {
         TileType::ImpassableMountain => true,
@@ -45,6 +71,9 @@ pub fn tile_cost(tt: TileType) -> f32 {
         TileType::Road => 0.75,
         TileType::Grass => 1.2,
         TileType::ShallowWater => 1.5,
+        TileType::DeepWater => 4.0,
+        TileType::Ice => 1.5,
+        TileType::ThinFloor => 2.0,
         _ => 1.0,
     }
 }
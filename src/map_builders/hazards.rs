@@ -0,0 +1,170 @@
+use super::{ BuilderMap, MetaMapBuilder, TileType };
+use crate::tile_walkable;
+use bracket_lib::prelude::*;
+use std::collections::VecDeque;
+
+/// How many spots to try for the middle of each patch before giving up on it.
+const PATCH_TRIES: i32 = 20;
+/// Patches aren't put down any closer than this to wherever the player starts.
+const START_CLEARANCE: f32 = 6.0;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum HazardMode {
+    Lava,
+    Chasm,
+    Ice,
+}
+
+/// Scatters a few patches of dangerous ground over the open floor of a level - pools of
+/// lava, chasms ringed by thin floor, or sheets of ice. Lava and chasms are never allowed
+/// to cut anywhere off from where the player starts; a patch that would is taken back up.
+pub struct Hazards {
+    mode: HazardMode,
+}
+
+impl MetaMapBuilder for Hazards {
+    #[allow(dead_code)]
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.apply(rng, build_data);
+    }
+}
+
+impl Hazards {
+    #[allow(dead_code)]
+    pub fn lava() -> Box<Hazards> {
+        return Box::new(Hazards { mode: HazardMode::Lava });
+    }
+
+    /// Chasms drop anyone who steps in to the level below, so only use this where there is one.
+    #[allow(dead_code)]
+    pub fn chasm() -> Box<Hazards> {
+        return Box::new(Hazards { mode: HazardMode::Chasm });
+    }
+
+    #[allow(dead_code)]
+    pub fn ice() -> Box<Hazards> {
+        return Box::new(Hazards { mode: HazardMode::Ice });
+    }
+
+    fn apply(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let patches = rng.roll_dice(1, 3);
+        for _i in 0..patches {
+            let radius = rng.roll_dice(1, 3) + 1;
+            let centre = if let Some(centre) = self.find_centre(rng, build_data, radius) {
+                centre
+            } else {
+                continue;
+            };
+            let before = build_data.map.tiles.clone();
+            let reachable_before = reachable(build_data);
+            self.patch(build_data, centre, radius);
+            if self.mode != HazardMode::Ice {
+                let reachable_after = reachable(build_data);
+                let tiles = &build_data.map.tiles;
+                let cut_off = (0..tiles.len()).any(
+                    |idx| reachable_before[idx] && tile_walkable(tiles[idx]) && !reachable_after[idx]
+                );
+                if cut_off {
+                    build_data.map.tiles = before;
+                    continue;
+                }
+            }
+            build_data.take_snapshot();
+        }
+        // Nothing gets placed on ground that can't be stood on.
+        let tiles = &build_data.map.tiles;
+        build_data.spawn_list.retain(|(idx, _name)| tile_walkable(tiles[*idx]));
+    }
+
+    fn find_centre(
+        &mut self,
+        rng: &mut RandomNumberGenerator,
+        build_data: &BuilderMap,
+        radius: i32
+    ) -> Option<Point> {
+        let (width, height) = (build_data.map.width, build_data.map.height);
+        for _i in 0..PATCH_TRIES {
+            let x = rng.roll_dice(1, width - radius * 2 - 4) + radius + 1;
+            let y = rng.roll_dice(1, height - radius * 2 - 4) + radius + 1;
+            let near_start = build_data.starting_position.as_ref().map_or(false, |pos| {
+                DistanceAlg::Pythagoras.distance2d(Point::new(x, y), Point::new(pos.x, pos.y)) <
+                    START_CLEARANCE
+            });
+            if !near_start && can_cover(build_data, build_data.map.xy_idx(x, y)) {
+                return Some(Point::new(x, y));
+            }
+        }
+        return None;
+    }
+
+    /// A round patch of the hazard around centre. Chasms get a rim of thin floor.
+    fn patch(&mut self, build_data: &mut BuilderMap, centre: Point, radius: i32) {
+        let (core, rim) = match self.mode {
+            HazardMode::Lava => (TileType::Lava, None),
+            HazardMode::Chasm => (TileType::Chasm, Some(TileType::ThinFloor)),
+            HazardMode::Ice => (TileType::Ice, None),
+        };
+        for y in centre.y - radius - 1..=centre.y + radius + 1 {
+            for x in centre.x - radius - 1..=centre.x + radius + 1 {
+                if x < 1 || y < 1 || x > build_data.map.width - 2 || y > build_data.map.height - 2 {
+                    continue;
+                }
+                let idx = build_data.map.xy_idx(x, y);
+                if !can_cover(build_data, idx) {
+                    continue;
+                }
+                let distance = DistanceAlg::Pythagoras.distance2d(centre, Point::new(x, y));
+                if distance < (radius as f32) {
+                    build_data.map.tiles[idx] = core;
+                } else if let Some(rim) = rim {
+                    if distance < (radius as f32) + 1.0 {
+                        build_data.map.tiles[idx] = rim;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Plain open ground can be covered, but not stairs, water, or wherever the player starts.
+fn can_cover(build_data: &BuilderMap, idx: usize) -> bool {
+    let start = build_data.starting_position
+        .as_ref()
+        .map_or(false, |pos| build_data.map.xy_idx(pos.x, pos.y) == idx);
+    let open = matches!(
+        build_data.map.tiles[idx],
+        TileType::Floor | TileType::WoodFloor | TileType::Gravel | TileType::Grass | TileType::Sand
+    );
+    return open && !start;
+}
+
+/// Which tiles can be walked to from wherever the player starts.
+fn reachable(build_data: &BuilderMap) -> Vec<bool> {
+    let map = &build_data.map;
+    let mut seen: Vec<bool> = vec![false; map.tiles.len()];
+    let start = if let Some(pos) = build_data.starting_position.as_ref() {
+        map.xy_idx(pos.x, pos.y)
+    } else {
+        return seen;
+    };
+    let mut open: VecDeque<usize> = VecDeque::new();
+    seen[start] = true;
+    open.push_back(start);
+    while let Some(idx) = open.pop_front() {
+        let (x, y) = ((idx as i32) % map.width, (idx as i32) / map.width);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx > map.width - 1 || ny > map.height - 1 {
+                    continue;
+                }
+                let next = map.xy_idx(nx, ny);
+                if !seen[next] && tile_walkable(map.tiles[next]) {
+                    seen[next] = true;
+                    open.push_back(next);
+                }
+            }
+        }
+    }
+    return seen;
+}
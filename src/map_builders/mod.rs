@@ -1,4 +1,4 @@
-use super::{ spawner, Map, Position, Rect, TileType };
+use super::{ raws, spawner, Map, Position, Rect, TileType };
 use bracket_lib::prelude::*;
 
 mod bsp_dungeon;
@@ -38,9 +38,9 @@ use common::*;
 use specs::prelude::*;
 use voronoi_spawning::VoronoiSpawning;
 use super::config::CONFIG;
-use super::data::ids::*;
 use super::data::names::*;
-//use wfc::WaveFunctionCollapseBuilder;
+use wfc::WaveFunctionCollapseBuilder;
+pub use wfc::connect_regions;
 mod room_exploder;
 use room_exploder::RoomExploder;
 mod room_corner_rounding;
@@ -69,6 +69,12 @@ mod forest;
 use forest::forest_builder;
 mod foliage;
 use foliage::Foliage;
+mod water;
+use water::Water;
+mod hazards;
+use hazards::Hazards;
+mod overmap;
+use overmap::overmap_builder;
 mod room_themer;
 use room_themer::ThemeRooms;
 
@@ -299,7 +305,7 @@ fn random_shape_builder(
     end: bool
 ) -> bool {
     // Pick an initial builder
-    let builder_roll = rng.roll_dice(1, 16);
+    let builder_roll = rng.roll_dice(1, 17);
     let mut want_doors = true;
     match builder_roll {
         1 => builder.start_with(CellularAutomataBuilder::new()),
@@ -318,6 +324,7 @@ fn random_shape_builder(
         11 => builder.start_with(DLABuilder::insectoid()),
         12 => builder.start_with(VoronoiBuilder::pythagoras()),
         13 => builder.start_with(VoronoiBuilder::manhattan()),
+        14 => builder.start_with(WaveFunctionCollapseBuilder::random_rex(rng)),
         _ =>
             builder.start_with(
                 PrefabBuilder::constant(prefab_builder::prefab_levels::WFC_POPULATED)
@@ -341,20 +348,27 @@ fn random_shape_builder(
     return want_doors;
 }
 
-fn overmap_builder() -> BuilderChain {
+fn boss_builder(
+    new_id: i32,
+    difficulty: i32,
+    depth: i32,
+    initial_player_level: i32,
+    boss: String
+) -> BuilderChain {
+    console::log(format!("DEBUGINFO: Building boss level (ID:{}, BOSS:{})", new_id, boss));
+    let arena = prefab_builder::prefab_levels::BOSS_ARENA;
     let mut builder = BuilderChain::new(
-        true,
-        ID_OVERMAP,
-        69,
-        41,
-        0,
-        NAME_OVERMAP,
-        SHORTNAME_OVERMAP,
-        0,
-        1
+        false,
+        new_id,
+        arena.width as i32,
+        arena.height as i32,
+        difficulty,
+        NAME_DUNGEON_RANDOM,
+        SHORTNAME_DUNGEON_RANDOM,
+        depth,
+        initial_player_level
     );
-    builder.start_with(PrefabBuilder::overmap());
-    builder.with(Foliage::percent(TileType::Grass, 30));
+    builder.start_with(PrefabBuilder::boss(arena, boss));
     return builder;
 }
 
@@ -364,6 +378,21 @@ pub enum BuildType {
     Any = 3,
 }
 
+/// Builds a cache with a quest item locked away inside it onto a level that's being generated.
+/// Returns false if there was nowhere on the level for it to go.
+pub fn build_quest_cache(rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap, item: &str) -> bool {
+    let placed = |build_data: &BuilderMap| {
+        build_data.spawn_list
+            .iter()
+            .filter(|(_idx, name)| name == item)
+            .count()
+    };
+    let before = placed(build_data);
+    let mut cache: Box<dyn MetaMapBuilder> = PrefabBuilder::cache(item.to_string());
+    cache.build_map(rng, build_data);
+    return placed(build_data) > before;
+}
+
 pub fn random_builder(
     new_id: i32,
     rng: &mut RandomNumberGenerator,
@@ -404,22 +433,21 @@ pub fn random_builder(
         }
     }
 
-    /*
-    WFC needs polishing up before it makes good maps. Right now it leaves too much unusable area,
-    by making disconnected sections and having no methods to connect them.
-
-    if rng.roll_dice(1, 1) == 1 {
-        builder.with(WaveFunctionCollapseBuilder::new());
-
-        // Now set the start to a random starting area
-        let (start_x, start_y) = random_start_position(rng);
-        builder.with(AreaStartingPosition::new(start_x, start_y));
+    // Now and then, a river runs through the level, or it's been flooded by a lake.
+    match rng.roll_dice(1, 12) {
+        1 => builder.with(Water::river()),
+        2 => builder.with(Water::lake()),
+        _ => {}
+    }
 
-        // Setup an exit and spawn mobs
-        builder.with(VoronoiSpawning::new());
-        builder.with(DistantExit::new());
+    // Some levels have hazards underfoot. Lava only turns up a few levels down, and chasms
+    // need a level below them to fall to.
+    match rng.roll_dice(1, 12) {
+        1 if depth >= 4 => builder.with(Hazards::lava()),
+        2 => builder.with(Hazards::ice()),
+        3 if !end => builder.with(Hazards::chasm()),
+        _ => {}
     }
-    */
 
     if want_doors {
         builder.with(DoorPlacement::new());
@@ -437,6 +465,8 @@ pub fn random_builder(
     builder
 }
 
+/// Builds the level with the given id, following the recipe of the branch it's in. The
+/// level is named after its branch once it's been built.
 pub fn level_builder(
     id: i32,
     rng: &mut RandomNumberGenerator,
@@ -444,49 +474,56 @@ pub fn level_builder(
     height: i32,
     initial_player_level: i32
 ) -> BuilderChain {
-    match id {
-        ID_OVERMAP => overmap_builder(),
-        ID_TOWN => town_builder(id, rng, width, height, 0, initial_player_level),
-        ID_TOWN2 => forest_builder(id, rng, width, height, 1, initial_player_level),
-        ID_TOWN3 =>
-            random_builder(
-                id,
-                rng,
-                width,
-                height,
-                2,
-                1,
-                initial_player_level,
-                true,
-                BuildType::Room
-            ),
-        _ if id >= ID_INFINITE =>
-            random_builder(
-                id,
-                rng,
-                width,
-                height,
-                4 + diff(ID_INFINITE, id),
-                1 + diff(ID_INFINITE, id),
-                initial_player_level,
-                false,
-                BuildType::Room
-            ),
-        _ => // This should be unreachable!() eventually. Right now it's reachable with the debug/cheat menu. It should not be in normal gameplay.
+    let branch = raws::get_branch(&raws::RAWS.lock().unwrap(), id).cloned();
+    let branch = if let Some(branch) = branch {
+        branch
+    } else {
+        // Only reachable with the debug/cheat menu, or a gap between branches in the raws.
+        console::log(format!("DEBUGINFO: No branch contains level {}", id));
+        return random_builder(
+            id,
+            rng,
+            width,
+            height,
+            1,
+            404,
+            initial_player_level,
+            false,
+            BuildType::Room
+        );
+    };
+    let (difficulty, depth) = (branch.difficulty_of(id), branch.depth_of(id));
+    let end = branch.is_dead_end(id);
+    if let Some(boss) = branch.boss_for(id) {
+        return boss_builder(id, difficulty, depth, initial_player_level, boss.to_string());
+    }
+    match branch.builder.as_str() {
+        "overmap" => overmap_builder(),
+        "town" => town_builder(id, rng, width, height, difficulty, initial_player_level),
+        "forest" => forest_builder(id, rng, width, height, difficulty, initial_player_level),
+        recipe => {
+            let build_type = match recipe {
+                "rooms" => BuildType::Room,
+                "shapes" => BuildType::Shape,
+                "any" => BuildType::Any,
+                _ => {
+                    console::log(
+                        format!("DEBUGINFO: Branch [{}] has an unknown builder [{}]", branch.id, recipe)
+                    );
+                    BuildType::Room
+                }
+            };
             random_builder(
                 id,
                 rng,
                 width,
                 height,
-                1,
-                404,
+                difficulty,
+                depth,
                 initial_player_level,
-                false,
-                BuildType::Room
-            ),
+                end,
+                build_type
+            )
+        }
     }
 }
-
-fn diff(branch_id: i32, lvl_id: i32) -> i32 {
-    return lvl_id - branch_id;
-}
@@ -0,0 +1,256 @@
+use super::{ BuilderChain, BuilderMap, Foliage, InitialMapBuilder, Position, TileType };
+use crate::data::ids::*;
+use crate::data::names::*;
+use crate::raws;
+use bracket_lib::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+const OVERMAP_WIDTH: i32 = 69;
+const OVERMAP_HEIGHT: i32 = 41;
+/// How far apart destinations try to be from each other.
+const DESTINATION_SPACING: f32 = 10.0;
+/// How many spots to try for each destination before settling for any bit of land.
+const DESTINATION_TRIES: i32 = 200;
+
+pub fn overmap_builder() -> BuilderChain {
+    let mut builder = BuilderChain::new(
+        true,
+        ID_OVERMAP,
+        OVERMAP_WIDTH,
+        OVERMAP_HEIGHT,
+        0,
+        NAME_OVERMAP,
+        SHORTNAME_OVERMAP,
+        0,
+        1
+    );
+    builder.start_with(OvermapBuilder::new());
+    builder.with(Foliage::percent(TileType::Grass, 10));
+    return builder;
+}
+
+/// Generates the overmap: noise decides where the mountains, forests, and water are, every
+/// branch with an entrance gets a spot on the land, and roads join them all up. The player
+/// starts beside the first entrance listed in the raws.
+pub struct OvermapBuilder {}
+
+impl InitialMapBuilder for OvermapBuilder {
+    #[allow(dead_code)]
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl OvermapBuilder {
+    pub fn new() -> Box<OvermapBuilder> {
+        return Box::new(OvermapBuilder {});
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.biomes(rng, build_data);
+        build_data.take_snapshot();
+        let destinations = self.destinations(rng, build_data);
+        build_data.take_snapshot();
+        self.roads(&destinations, build_data);
+        build_data.take_snapshot();
+        if let Some(first) = destinations.first() {
+            self.starting_position(*first, build_data);
+        }
+    }
+
+    /// Elevation noise makes the seas and mountains, and moisture noise decides where the
+    /// land in between is wooded.
+    fn biomes(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let mut elevation = FastNoise::seeded(rng.roll_dice(1, 65536) as u64);
+        elevation.set_noise_type(NoiseType::SimplexFractal);
+        elevation.set_fractal_type(FractalType::FBM);
+        elevation.set_fractal_octaves(4);
+        elevation.set_frequency(0.06);
+        let mut moisture = FastNoise::seeded(rng.roll_dice(1, 65536) as u64);
+        moisture.set_noise_type(NoiseType::SimplexFractal);
+        moisture.set_fractal_octaves(2);
+        moisture.set_frequency(0.1);
+
+        let (width, height) = (build_data.map.width, build_data.map.height);
+        for y in 0..height {
+            for x in 0..width {
+                let idx = build_data.map.xy_idx(x, y);
+                let edge = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+                let altitude = elevation.get_noise(x as f32, y as f32);
+                let wet = moisture.get_noise(x as f32, y as f32);
+                build_data.map.tiles[idx] = match altitude {
+                    _ if edge => TileType::ImpassableMountain,
+                    h if h < -0.2 => TileType::DeepWater,
+                    h if h < -0.12 => TileType::ShallowWater,
+                    h if h < -0.08 => TileType::Sand,
+                    h if h > 0.22 => TileType::ImpassableMountain,
+                    _ if wet > 0.25 => TileType::HeavyFoliage,
+                    _ if wet > 0.1 => TileType::Foliage,
+                    _ => TileType::Grass,
+                };
+            }
+        }
+    }
+
+    /// Finds a spot on the land for each branch entrance, spread out as much as possible.
+    fn destinations(
+        &mut self,
+        rng: &mut RandomNumberGenerator,
+        build_data: &mut BuilderMap
+    ) -> Vec<usize> {
+        let entrances = raws::get_branch_entrances(&raws::RAWS.lock().unwrap());
+        let mut placed: Vec<usize> = Vec::new();
+        for id in entrances {
+            let mut spot: Option<usize> = None;
+            let mut fallback: Option<usize> = None;
+            for _i in 0..DESTINATION_TRIES {
+                let x = rng.roll_dice(1, build_data.map.width - 4) + 1;
+                let y = rng.roll_dice(1, build_data.map.height - 4) + 1;
+                let idx = build_data.map.xy_idx(x, y);
+                if !is_land(build_data.map.tiles[idx]) {
+                    continue;
+                }
+                fallback = Some(idx);
+                let crowded = placed.iter().any(|other| {
+                    let other = Point::new(
+                        (*other as i32) % build_data.map.width,
+                        (*other as i32) / build_data.map.width
+                    );
+                    DistanceAlg::Pythagoras.distance2d(Point::new(x, y), other) < DESTINATION_SPACING
+                });
+                if !crowded {
+                    spot = Some(idx);
+                    break;
+                }
+            }
+            // If there's no land to be found at all, make some.
+            let idx = spot.or(fallback).unwrap_or_else(|| {
+                let x = rng.roll_dice(1, build_data.map.width - 4) + 1;
+                let y = rng.roll_dice(1, build_data.map.height - 4) + 1;
+                build_data.map.xy_idx(x, y)
+            });
+            build_data.map.tiles[idx] = TileType::ToLocal(id);
+            placed.push(idx);
+        }
+        return placed;
+    }
+
+    /// Joins every destination to the nearest one that's already on the road network,
+    /// bridging water and cutting passes through the mountains where it has to.
+    fn roads(&mut self, destinations: &[usize], build_data: &mut BuilderMap) {
+        if destinations.is_empty() {
+            return;
+        }
+        let width = build_data.map.width;
+        let point = |idx: usize| Point::new((idx as i32) % width, (idx as i32) / width);
+        let mut connected: Vec<usize> = vec![destinations[0]];
+        let mut remaining: Vec<usize> = destinations[1..].to_vec();
+        while !remaining.is_empty() {
+            let mut best: Option<(usize, usize, f32)> = None;
+            for (i, from) in remaining.iter().enumerate() {
+                for to in connected.iter() {
+                    let distance = DistanceAlg::Pythagoras.distance2d(point(*from), point(*to));
+                    if best.map_or(true, |(_, _, closest)| distance < closest) {
+                        best = Some((i, *to, distance));
+                    }
+                }
+            }
+            let (i, to, _) = best.unwrap();
+            let from = remaining.remove(i);
+            for idx in road_path(build_data, from, to) {
+                let tile = &mut build_data.map.tiles[idx];
+                *tile = match *tile {
+                    TileType::ToLocal(_) | TileType::Road | TileType::Bridge => *tile,
+                    TileType::DeepWater | TileType::ShallowWater => TileType::Bridge,
+                    _ => TileType::Road,
+                };
+            }
+            connected.push(from);
+        }
+    }
+
+    /// Puts the player on a free tile beside the given destination.
+    fn starting_position(&mut self, destination: usize, build_data: &mut BuilderMap) {
+        let x = (destination as i32) % build_data.map.width;
+        let y = (destination as i32) / build_data.map.width;
+        let mut start = (x, y + 1);
+        for (dx, dy) in [(0, 1), (1, 0), (-1, 0), (0, -1), (1, 1), (-1, 1), (1, -1), (-1, -1)] {
+            let idx = build_data.map.xy_idx(x + dx, y + dy);
+            if is_land(build_data.map.tiles[idx]) || build_data.map.tiles[idx] == TileType::Road {
+                start = (x + dx, y + dy);
+                break;
+            }
+        }
+        let idx = build_data.map.xy_idx(start.0, start.1);
+        if !matches!(build_data.map.tiles[idx], TileType::Road | TileType::Bridge) {
+            build_data.map.tiles[idx] = TileType::Grass;
+        }
+        build_data.starting_position = Some(Position { x: start.0, y: start.1 });
+    }
+}
+
+fn is_land(tile: TileType) -> bool {
+    matches!(tile, TileType::Grass | TileType::Foliage | TileType::HeavyFoliage | TileType::Sand)
+}
+
+/// How much a road would rather not go through a tile. Existing roads are cheapest, so
+/// roads join up rather than running side by side.
+fn road_cost(tile: TileType) -> i32 {
+    match tile {
+        TileType::Road | TileType::Bridge => 1,
+        TileType::Grass | TileType::Sand => 2,
+        TileType::Foliage => 3,
+        TileType::HeavyFoliage => 4,
+        TileType::ShallowWater => 6,
+        TileType::DeepWater => 10,
+        TileType::ImpassableMountain => 12,
+        _ => 2,
+    }
+}
+
+/// The cheapest way for a road to get from start to end, not counting either end.
+fn road_path(build_data: &BuilderMap, start: usize, end: usize) -> Vec<usize> {
+    let map = &build_data.map;
+    let mut cost: Vec<i32> = vec![i32::MAX; map.tiles.len()];
+    let mut came_from: Vec<Option<usize>> = vec![None; map.tiles.len()];
+    let mut open: BinaryHeap<Reverse<(i32, usize)>> = BinaryHeap::new();
+    cost[start] = 0;
+    open.push(Reverse((0, start)));
+    while let Some(Reverse((so_far, idx))) = open.pop() {
+        if idx == end {
+            break;
+        }
+        if so_far > cost[idx] {
+            continue;
+        }
+        let (x, y) = ((idx as i32) % map.width, (idx as i32) / map.width);
+        for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+            let (nx, ny) = (x + dx, y + dy);
+            // Keep off the edges of the map, and out of anywhere else we're going.
+            if nx < 1 || ny < 1 || nx > map.width - 2 || ny > map.height - 2 {
+                continue;
+            }
+            let next = map.xy_idx(nx, ny);
+            if next != end && matches!(map.tiles[next], TileType::ToLocal(_)) {
+                continue;
+            }
+            let next_cost = so_far + road_cost(map.tiles[next]);
+            if next_cost < cost[next] {
+                cost[next] = next_cost;
+                came_from[next] = Some(idx);
+                open.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+    let mut path: Vec<usize> = Vec::new();
+    let mut step = came_from[end];
+    while let Some(idx) = step {
+        if idx == start {
+            break;
+        }
+        path.push(idx);
+        step = came_from[idx];
+    }
+    return path;
+}
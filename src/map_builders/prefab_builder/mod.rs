@@ -4,12 +4,10 @@ pub mod prefab_levels;
 pub mod prefab_sections;
 pub mod prefab_vaults;
 use std::collections::HashSet;
-use crate::data::ids::*;
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Clone)]
 #[allow(dead_code)]
 pub enum PrefabMode {
-    Overmap,
     Constant {
         level: prefab_levels::PrefabLevel,
     },
@@ -17,6 +15,13 @@ pub enum PrefabMode {
         section: prefab_sections::PrefabSection,
     },
     RoomVaults,
+    Boss {
+        level: prefab_levels::PrefabLevel,
+        boss: String,
+    },
+    Cache {
+        item: String,
+    },
 }
 
 #[allow(dead_code)]
@@ -43,11 +48,6 @@ impl PrefabBuilder {
         Box::new(PrefabBuilder { mode: PrefabMode::RoomVaults })
     }
 
-    #[allow(dead_code)]
-    pub fn overmap() -> Box<PrefabBuilder> {
-        Box::new(PrefabBuilder { mode: PrefabMode::Overmap })
-    }
-
     #[allow(dead_code)]
     pub fn constant(level: prefab_levels::PrefabLevel) -> Box<PrefabBuilder> {
         Box::new(PrefabBuilder { mode: PrefabMode::Constant { level } })
@@ -63,13 +63,23 @@ impl PrefabBuilder {
         Box::new(PrefabBuilder { mode: PrefabMode::RoomVaults })
     }
 
+    /// A constant level, with the given boss spawned wherever the template has a 'B'.
+    pub fn boss(level: prefab_levels::PrefabLevel, boss: String) -> Box<PrefabBuilder> {
+        Box::new(PrefabBuilder { mode: PrefabMode::Boss { level, boss } })
+    }
+
+    /// A quest cache somewhere on the level, with the given item inside it.
+    pub fn cache(item: String) -> Box<PrefabBuilder> {
+        Box::new(PrefabBuilder { mode: PrefabMode::Cache { item } })
+    }
+
     fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
         match self.mode {
-            PrefabMode::Overmap =>
-                self.load_ascii_map(&prefab_levels::OVERMAP, rng, build_data, true),
-            PrefabMode::Constant { level } => self.load_ascii_map(&level, rng, build_data, false),
+            PrefabMode::Constant { level } => self.load_ascii_map(&level, rng, build_data),
             PrefabMode::Sectional { section } => self.apply_sectional(&section, rng, build_data),
             PrefabMode::RoomVaults => self.apply_room_vaults(rng, build_data),
+            PrefabMode::Boss { level, .. } => self.load_ascii_map(&level, rng, build_data),
+            PrefabMode::Cache { .. } => self.apply_cache(rng, build_data),
         }
         build_data.take_snapshot();
     }
@@ -120,6 +130,16 @@ impl PrefabBuilder {
                 build_data.map.tiles[idx] = TileType::Floor;
                 build_data.spawn_list.push((idx, "orc".to_string()));
             }
+            'k' => {
+                build_data.map.tiles[idx] = TileType::Floor;
+                build_data.spawn_list.push((idx, "kobold".to_string()));
+            }
+            'B' => {
+                build_data.map.tiles[idx] = TileType::Floor;
+                if let PrefabMode::Boss { boss, .. } = &self.mode {
+                    build_data.spawn_list.push((idx, boss.clone()));
+                }
+            }
             '^' => {
                 build_data.map.tiles[idx] = TileType::Floor;
                 build_data.spawn_list.push((idx, "trap_bear".to_string()));
@@ -153,55 +173,17 @@ impl PrefabBuilder {
                     spawner::equipment_table(Some(difficulty)).roll(rng),
                 ));
             }
-            _ => {
-                console::log(format!("Unknown glyph '{}' when loading prefab", ch as u8 as char));
-            }
-        }
-    }
-
-    fn overmap_char_to_map(
-        &mut self,
-        ch: char,
-        idx: usize,
-        _rng: &mut RandomNumberGenerator,
-        build_data: &mut BuilderMap
-    ) {
-        match ch {
-            ' ' => {
+            'C' => {
                 build_data.map.tiles[idx] = TileType::Floor;
-            }
-            '.' => {
-                build_data.map.tiles[idx] = TileType::Grass;
-            }
-            '#' => {
-                build_data.map.tiles[idx] = TileType::Wall;
-            }
-            '>' => {
-                build_data.map.tiles[idx] = TileType::DownStair;
-            }
-            '~' => {
-                build_data.map.tiles[idx] = TileType::ShallowWater;
-            }
-            '≈' => {
-                build_data.map.tiles[idx] = TileType::DeepWater;
-            }
-            '@' => {
-                let x = (idx as i32) % build_data.map.width;
-                let y = (idx as i32) / build_data.map.width;
-                build_data.map.tiles[idx] = TileType::Grass;
-                build_data.starting_position = Some(Position { x: x as i32, y: y as i32 });
-            }
-            '^' => {
-                build_data.map.tiles[idx] = TileType::ImpassableMountain;
-            }
-            '1' => {
-                build_data.map.tiles[idx] = TileType::ToLocal(ID_TOWN);
-            }
-            '2' => {
-                build_data.map.tiles[idx] = TileType::ToLocal(ID_INFINITE);
+                let chest = match rng.roll_dice(1, 4) {
+                    1 => "prop_chest_locked",
+                    2 => "prop_chest_trapped",
+                    _ => "prop_chest",
+                };
+                build_data.spawn_list.push((idx, chest.to_string()));
             }
             _ => {
-                console::log(format!("Unknown glyph '{}' when loading overmap", ch as u8 as char));
+                console::log(format!("Unknown glyph '{}' when loading prefab", ch as u8 as char));
             }
         }
     }
@@ -247,8 +229,7 @@ impl PrefabBuilder {
         &mut self,
         level: &prefab_levels::PrefabLevel,
         rng: &mut RandomNumberGenerator,
-        build_data: &mut BuilderMap,
-        overmap: bool
+        build_data: &mut BuilderMap
     ) {
         let string_vec = PrefabBuilder::read_ascii_to_vec(level.template);
 
@@ -258,11 +239,7 @@ impl PrefabBuilder {
                 if tx < (build_data.map.width as usize) && ty < (build_data.map.height as usize) {
                     let idx = build_data.map.xy_idx(tx as i32, ty as i32);
                     if i < string_vec.len() {
-                        if overmap {
-                            self.overmap_char_to_map(string_vec[i], idx, rng, build_data);
-                        } else {
-                            self.char_to_map(string_vec[i], idx, rng, build_data);
-                        }
+                        self.char_to_map(string_vec[i], idx, rng, build_data);
                     }
                 }
                 i += 1;
@@ -363,6 +340,31 @@ impl PrefabBuilder {
         // Apply the previous builder, and keep all entities it spawns (for now)
         self.apply_previous_iteration(|_x, _y| true, rng, build_data);
 
+        // Lairs are guaranteed, so they go down first, while there's the most room for them.
+        let mut used_tiles: HashSet<usize> = HashSet::new();
+        for lair in LAIRS.iter() {
+            if build_data.map.id < lair.vault.first_id || build_data.map.id > lair.vault.last_id {
+                continue;
+            }
+            if !self.place_vault(&lair.vault, Some(lair.unique), &mut used_tiles, rng, build_data) {
+                // If there's nowhere to put the lair, the unique turns up somewhere regardless.
+                let free: Vec<usize> = build_data.map.tiles
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, tile)| {
+                        **tile == TileType::Floor &&
+                            !used_tiles.contains(idx) &&
+                            !build_data.spawn_list.iter().any(|(spawn_idx, _)| spawn_idx == idx)
+                    })
+                    .map(|(idx, _)| idx)
+                    .collect();
+                if !free.is_empty() {
+                    let idx = free[(rng.roll_dice(1, free.len() as i32) - 1) as usize];
+                    build_data.spawn_list.push((idx, lair.unique.to_string()));
+                }
+            }
+        }
+
         // Do we want a vault at all?
         let vault_roll = rng.roll_dice(1, 6) + build_data.map.difficulty;
         if vault_roll < 4 {
@@ -382,7 +384,8 @@ impl PrefabBuilder {
             FLUFF2_6X3,
             HOUSE_NOTRAP_7X7,
             HOUSE_TRAP_7X7,
-            ORC_HOUSE_8X8
+            ORC_HOUSE_8X8,
+            TREASURE_ROOM_7X5
         ];
 
         // Filter the vault list down to ones that are applicable to the current id
@@ -396,7 +399,6 @@ impl PrefabBuilder {
         } // Bail out if there's nothing to build
 
         let n_vaults = i32::min(rng.roll_dice(1, 3), possible_vaults.len() as i32);
-        let mut used_tiles: HashSet<usize> = HashSet::new();
 
         for _i in 0..n_vaults {
             let vault_index = if possible_vaults.len() == 1 {
@@ -405,137 +407,171 @@ impl PrefabBuilder {
                 (rng.roll_dice(1, possible_vaults.len() as i32) - 1) as usize
             };
             let vault = possible_vaults[vault_index];
-            // Decide if we want to flip the vault
-            let mut flip_x: bool = false;
-            let mut flip_y: bool = false;
-            match vault.can_flip {
-                // Equal chance at every orientation
-                Flipping::None => {}
-                Flipping::Horizontal => {
-                    flip_x = rng.roll_dice(1, 2) == 1;
-                }
-                Flipping::Vertical => {
-                    flip_y = rng.roll_dice(1, 2) == 1;
-                }
-                Flipping::Both => {
-                    let roll = rng.roll_dice(1, 4);
-                    match roll {
-                        1 => {}
-                        2 => {
-                            flip_x = true;
-                        }
-                        3 => {
-                            flip_y = true;
-                        }
-                        _ => {
-                            flip_x = true;
-                            flip_y = true;
-                        }
-                    }
-                }
+            if self.place_vault(vault, None, &mut used_tiles, rng, build_data) {
+                possible_vaults.remove(vault_index);
             }
+        }
+    }
 
-            // We'll make a list of places in which the vault could fit
-            let mut vault_positions: Vec<Position> = Vec::new();
+    fn apply_cache(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let item = if let PrefabMode::Cache { item } = &self.mode {
+            item.clone()
+        } else {
+            return;
+        };
+        let mut used_tiles: HashSet<usize> = HashSet::new();
+        self.place_vault(&prefab_vaults::QUEST_CACHE_7X5, Some(&item), &mut used_tiles, rng, build_data);
+    }
 
-            let mut idx = 0usize;
-            loop {
-                let x = (idx % (build_data.map.width as usize)) as i32;
-                let y = (idx / (build_data.map.width as usize)) as i32;
+    /// Picks an orientation and a free spot for the vault, and builds it there. If the
+    /// vault has an occupant, it's spawned wherever the template has a 'U'. Returns false
+    /// if there was nowhere the vault would fit.
+    fn place_vault(
+        &mut self,
+        vault: &prefab_vaults::PrefabVault,
+        occupant: Option<&str>,
+        used_tiles: &mut HashSet<usize>,
+        rng: &mut RandomNumberGenerator,
+        build_data: &mut BuilderMap
+    ) -> bool {
+        use prefab_vaults::*;
 
-                // Check that we won't overflow the map
-                if
-                    x > 1 &&
-                    x + (vault.width as i32) < build_data.map.width - 2 &&
-                    y > 1 &&
-                    y + (vault.height as i32) < build_data.map.height - 2
-                {
-                    let mut possible = true;
-                    for ty in 0..vault.height as i32 {
-                        for tx in 0..vault.width as i32 {
-                            let idx = build_data.map.xy_idx(tx + x, ty + y);
-                            if build_data.map.tiles[idx] != TileType::Floor {
-                                possible = false;
-                            }
-                            if used_tiles.contains(&idx) {
-                                possible = false;
-                            }
-                        }
+        // Decide if we want to flip the vault
+        let mut flip_x: bool = false;
+        let mut flip_y: bool = false;
+        match vault.can_flip {
+            // Equal chance at every orientation
+            Flipping::None => {}
+            Flipping::Horizontal => {
+                flip_x = rng.roll_dice(1, 2) == 1;
+            }
+            Flipping::Vertical => {
+                flip_y = rng.roll_dice(1, 2) == 1;
+            }
+            Flipping::Both => {
+                let roll = rng.roll_dice(1, 4);
+                match roll {
+                    1 => {}
+                    2 => {
+                        flip_x = true;
                     }
+                    3 => {
+                        flip_y = true;
+                    }
+                    _ => {
+                        flip_x = true;
+                        flip_y = true;
+                    }
+                }
+            }
+        }
 
-                    if possible {
-                        vault_positions.push(Position { x, y });
-                        break;
+        // We'll make a list of places in which the vault could fit
+        let mut vault_positions: Vec<Position> = Vec::new();
+
+        let mut idx = 0usize;
+        loop {
+            let x = (idx % (build_data.map.width as usize)) as i32;
+            let y = (idx / (build_data.map.width as usize)) as i32;
+
+            // Check that we won't overflow the map
+            if
+                x > 1 &&
+                x + (vault.width as i32) < build_data.map.width - 2 &&
+                y > 1 &&
+                y + (vault.height as i32) < build_data.map.height - 2
+            {
+                let mut possible = true;
+                for ty in 0..vault.height as i32 {
+                    for tx in 0..vault.width as i32 {
+                        let idx = build_data.map.xy_idx(tx + x, ty + y);
+                        if build_data.map.tiles[idx] != TileType::Floor {
+                            possible = false;
+                        }
+                        if used_tiles.contains(&idx) {
+                            possible = false;
+                        }
                     }
                 }
 
-                idx += 1;
-                if idx >= build_data.map.tiles.len() - 1 {
+                if possible {
+                    vault_positions.push(Position { x, y });
                     break;
                 }
             }
 
-            if !vault_positions.is_empty() {
-                let pos_idx = if vault_positions.len() == 1 {
-                    0
-                } else {
-                    (rng.roll_dice(1, vault_positions.len() as i32) - 1) as usize
-                };
-                let pos = &vault_positions[pos_idx];
-
-                let chunk_x = pos.x;
-                let chunk_y = pos.y;
-
-                let width = build_data.map.width; // The borrow checker really doesn't like it
-                let height = build_data.map.height; // when we access `self` inside the `retain`
-                build_data.spawn_list.retain(|e| {
-                    let idx = e.0 as i32;
-                    let x = idx % width;
-                    let y = idx / height;
-                    x < chunk_x ||
-                        x > chunk_x + (vault.width as i32) ||
-                        y < chunk_y ||
-                        y > chunk_y + (vault.height as i32)
-                });
-
-                let string_vec = PrefabBuilder::read_ascii_to_vec(vault.template);
-                let mut i = 0;
-                for tile_y in 0..vault.height {
-                    for tile_x in 0..vault.width {
-                        let mut x_: i32 = tile_x as i32;
-                        let mut y_: i32 = tile_y as i32;
-                        // Handle flipping
-                        if flip_x {
-                            x_ = (vault.width as i32) - 1 - x_;
-                        }
-                        if flip_y {
-                            y_ = (vault.height as i32) - 1 - y_;
-                        }
-                        if x_ < 0 || y_ < 0 {
-                            // If either of these go below 0, we run the risk of CTD, so just panic.
-                            // Something went wrong with flipping/rotating/defining a vault.
-                            panic!(
-                                "X or Y went below 0 when trying to place a vault! DEBUGINFO == [H: {}, W: {}; FLIPPED X: {}, FLIPPED Y: {}; X_: {}, Y_: {}]",
-                                vault.width,
-                                vault.height,
-                                flip_x,
-                                flip_y,
-                                x_,
-                                y_
-                            );
-                        }
-                        let idx = build_data.map.xy_idx(x_ + chunk_x, y_ + chunk_y);
-                        if i < string_vec.len() {
-                            self.char_to_map(string_vec[i], idx, rng, build_data);
+            idx += 1;
+            if idx >= build_data.map.tiles.len() - 1 {
+                break;
+            }
+        }
+
+        if !vault_positions.is_empty() {
+            let pos_idx = if vault_positions.len() == 1 {
+                0
+            } else {
+                (rng.roll_dice(1, vault_positions.len() as i32) - 1) as usize
+            };
+            let pos = &vault_positions[pos_idx];
+
+            let chunk_x = pos.x;
+            let chunk_y = pos.y;
+
+            let width = build_data.map.width; // The borrow checker really doesn't like it
+            let height = build_data.map.height; // when we access `self` inside the `retain`
+            build_data.spawn_list.retain(|e| {
+                let idx = e.0 as i32;
+                let x = idx % width;
+                let y = idx / height;
+                x < chunk_x ||
+                    x > chunk_x + (vault.width as i32) ||
+                    y < chunk_y ||
+                    y > chunk_y + (vault.height as i32)
+            });
+
+            let string_vec = PrefabBuilder::read_ascii_to_vec(vault.template);
+            let mut i = 0;
+            for tile_y in 0..vault.height {
+                for tile_x in 0..vault.width {
+                    let mut x_: i32 = tile_x as i32;
+                    let mut y_: i32 = tile_y as i32;
+                    // Handle flipping
+                    if flip_x {
+                        x_ = (vault.width as i32) - 1 - x_;
+                    }
+                    if flip_y {
+                        y_ = (vault.height as i32) - 1 - y_;
+                    }
+                    if x_ < 0 || y_ < 0 {
+                        // If either of these go below 0, we run the risk of CTD, so just panic.
+                        // Something went wrong with flipping/rotating/defining a vault.
+                        panic!(
+                            "X or Y went below 0 when trying to place a vault! DEBUGINFO == [H: {}, W: {}; FLIPPED X: {}, FLIPPED Y: {}; X_: {}, Y_: {}]",
+                            vault.width,
+                            vault.height,
+                            flip_x,
+                            flip_y,
+                            x_,
+                            y_
+                        );
+                    }
+                    let idx = build_data.map.xy_idx(x_ + chunk_x, y_ + chunk_y);
+                    if i < string_vec.len() {
+                        match (string_vec[i], occupant) {
+                            ('U', Some(occupant)) => {
+                                build_data.map.tiles[idx] = TileType::Floor;
+                                build_data.spawn_list.push((idx, occupant.to_string()));
+                            }
+                            _ => self.char_to_map(string_vec[i], idx, rng, build_data),
                         }
-                        used_tiles.insert(idx);
-                        i += 1;
                     }
+                    used_tiles.insert(idx);
+                    i += 1;
                 }
-                build_data.take_snapshot();
-
-                possible_vaults.remove(vault_index);
             }
+            build_data.take_snapshot();
+            return true;
         }
+        false
     }
 }
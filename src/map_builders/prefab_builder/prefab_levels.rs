@@ -7,7 +7,9 @@ pub struct PrefabLevel {
 
 #[allow(dead_code)]
 pub const WFC_POPULATED: PrefabLevel = PrefabLevel { template: LEVEL_MAP, width: 80, height: 43 };
-pub const OVERMAP: PrefabLevel = PrefabLevel { template: OVERMAP_TEMPLATE, width: 69, height: 41 };
+/// Every boss level is this arena. The boss waits at the far end, between the player
+/// and the way down.
+pub const BOSS_ARENA: PrefabLevel = PrefabLevel { template: BOSS_ARENA_TEMPLATE, width: 80, height: 43 };
 
 #[allow(dead_code)]
 const LEVEL_MAP: &str =
@@ -56,46 +58,48 @@ const LEVEL_MAP: &str =
 # %^##  ^   ###     ############### ########      #####       ##################
 ################################################################################";
 
-const OVERMAP_TEMPLATE: &str =
+const BOSS_ARENA_TEMPLATE: &str =
     "
-^^^^^^^^^^^^^^^^^^^^^^^^^^^≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈........≈≈≈≈≈≈≈≈
-^^^^^^^^^^^^^^^^^^^^^^^^^^≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈...........≈≈≈≈≈≈≈
-^^^^^^^^^^^^^^^....^^^^^^≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈.............≈≈≈≈≈
-^^^^^^^^^^^^^^...........≈≈≈≈≈≈≈≈≈........≈≈≈≈≈≈≈≈..............≈≈≈≈≈
-^^^^^^^^^^^^^............≈≈≈≈≈≈≈≈...........≈≈≈≈≈..............≈≈≈≈≈≈
-^^^^^^^^^^^.............≈≈≈≈≈≈≈≈≈...........≈≈≈≈≈.............≈≈≈≈≈≈≈
-^^^^^^^^^...............≈≈≈≈≈≈≈≈≈............≈≈≈≈............≈≈≈≈≈≈≈≈
-^^^^^^^^................≈≈≈≈≈≈≈≈≈............≈≈≈≈............≈≈≈≈≈≈≈≈
-^^^^^^^..................≈≈≈≈≈≈≈≈≈...........≈≈≈.............≈≈≈≈≈≈≈≈
-^^^^.....................≈≈≈≈≈≈≈≈≈≈≈........≈≈≈≈..............≈≈≈≈≈≈≈
-^^.........................≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈..............≈≈≈≈≈≈≈
-^^..............................≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈................≈≈≈≈≈≈
-^.................................≈≈≈≈≈≈≈≈≈≈≈≈≈.................≈≈≈≈≈
-^..................................≈≈≈≈≈≈≈≈≈≈≈≈.................≈≈≈≈≈
-^....................................≈≈≈≈≈≈≈≈≈..................≈≈≈≈≈
-^......................................≈≈≈≈≈≈...................≈≈≈≈≈
-^........................................≈≈.....................≈≈≈≈≈
-^..............................................................≈≈≈≈≈≈
-^..............................................................≈≈≈≈≈≈
-^^.............................................................≈≈≈≈≈≈
-^^.............................................................≈≈≈≈≈≈
-^^.............................................................≈≈≈≈≈≈
-^^^............................................................≈≈≈≈≈≈
-^^^^............................................................≈≈≈≈≈
-^^^^^...........................................................≈≈≈≈≈
-^^^^^.^^........................................................≈≈≈≈≈
-^^^^..^^^.......................................................≈≈≈≈≈
-^^^...^^^..............................≈≈........................≈≈≈≈
-^^^2.^^^^.............................≈≈≈≈≈≈≈≈≈...................≈≈≈
-^^^^^^^^..............................≈≈≈≈≈≈≈≈≈≈......≈............≈≈
-^^^^^^^..............................≈≈≈≈≈≈≈≈≈≈≈≈≈...≈≈..........≈..≈
-^^^^^^^..........................≈≈.≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈....≈≈≈≈
-^^^^^^^........................≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈.....≈
-^^^^^^^^.......................≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈....≈
-^^^^^^^^......@...........≈...≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈...≈≈≈...≈≈≈≈≈≈≈≈.≈≈
-^^^^^^^^^.................≈≈....≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈....≈≈......≈≈≈≈≈≈≈≈≈
-^^^^^^^^^........≈≈≈≈1..≈≈≈≈....≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈..≈≈≈......≈≈≈≈≈≈≈≈≈
-^^^^^^^^^^......≈≈≈≈≈..≈≈≈≈≈≈..≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈........≈≈≈≈≈≈≈≈
-^^^^^^^^^^.....≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈........≈≈≈≈≈≈≈≈≈
-^^^^^^^^^^....≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈....≈≈≈≈≈≈≈≈≈≈
-^^^^^^^^^^^^.≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈≈";
+################################################################################
+################################################################################
+################################################################################
+################################################################################
+########################################                 #######################
+#####################################                       ####################
+##################################                             #################
+################################                                 ###############
+###############################                 ##                ##############
+#############################                   ##                  ############
+############################                                         ###########
+###########################                                           ##########
+##########################        ##                          ##       #########
+#########################         ##                          ##        ########
+#########################                                               ########
+########################                                                 #######
+########################                                                 #######
+#######################                 ##              ##                ######
+##            #########                 ##              ##                ######
+## !          #########                             ^                         ##
+##            #########                                                      ?##
+##  @                +                      ^                     B         > ##
+##            #########                                                      )##
+## %          #########                             ^                         ##
+##            #########                                                   ######
+#######################                 ##              ##                ######
+########################                ##              ##               #######
+########################                                                 #######
+#########################                                               ########
+#########################                                               ########
+##########################        ##                          ##       #########
+###########################       ##                          ##      ##########
+############################                                         ###########
+#############################                                       ############
+###############################                 ##                ##############
+################################                ##               ###############
+##################################                             #################
+#####################################                       ####################
+########################################                 #######################
+################################################################################
+################################################################################
+################################################################################
+################################################################################";
@@ -1,3 +1,5 @@
+use crate::data::ids::ID_INFINITE;
+
 #[allow(dead_code)]
 #[derive(PartialEq, Copy, Clone)]
 pub enum Flipping {
@@ -167,3 +169,86 @@ const ORC_HOUSE_8X8_V: &str = "
  #   ?# 
  ##+### 
         ";
+
+pub const TREASURE_ROOM_7X5: PrefabVault = PrefabVault {
+    template: TREASURE_ROOM_7X5_V,
+    width: 7,
+    height: 5,
+    first_id: 2,
+    last_id: 100,
+    can_flip: Flipping::Both,
+};
+const TREASURE_ROOM_7X5_V: &str = "
+       
+ ##+## 
+ #C^C# 
+ ##### 
+       
+";
+
+/// A little locked-away room to hide a quest item in, which is placed wherever the template
+/// has a 'U'. Quests decide which levels get one, so it isn't in the random vault list.
+pub const QUEST_CACHE_7X5: PrefabVault = PrefabVault {
+    template: QUEST_CACHE_7X5_V,
+    width: 7,
+    height: 5,
+    first_id: 0,
+    last_id: i32::MAX,
+    can_flip: Flipping::Both,
+};
+const QUEST_CACHE_7X5_V: &str = "
+       
+ ##+## 
+ # U # 
+ ##### 
+       
+";
+
+/// A vault built around a unique mob, which is spawned wherever the template has a 'U'.
+/// Lairs aren't left to chance like other vaults - every level in their id range gets one.
+#[derive(PartialEq, Copy, Clone)]
+pub struct PrefabLair {
+    pub unique: &'static str,
+    pub vault: PrefabVault,
+}
+
+pub const LAIRS: [PrefabLair; 2] = [
+    PrefabLair { unique: "kobold_chief_snagtooth", vault: SNAGTOOTH_LAIR_9X7 },
+    PrefabLair { unique: "rat_queen_mottle", vault: MOTTLE_LAIR_9X7 },
+];
+
+const SNAGTOOTH_LAIR_9X7: PrefabVault = PrefabVault {
+    template: SNAGTOOTH_LAIR_9X7_V,
+    width: 9,
+    height: 7,
+    first_id: ID_INFINITE + 2,
+    last_id: ID_INFINITE + 2,
+    can_flip: Flipping::Both,
+};
+const SNAGTOOTH_LAIR_9X7_V: &str = "
+         
+ ###+### 
+ #k   k# 
+ #  U  # 
+ #k C k# 
+ ####### 
+         
+";
+
+const MOTTLE_LAIR_9X7: PrefabVault = PrefabVault {
+    template: MOTTLE_LAIR_9X7_V,
+    width: 9,
+    height: 7,
+    first_id: ID_INFINITE + 6,
+    last_id: ID_INFINITE + 6,
+    can_flip: Flipping::Both,
+};
+const MOTTLE_LAIR_9X7_V: &str = "
+         
+ ##% ### 
+ #     % 
+ %  U  # 
+ #     # 
+ ### %## 
+         
+";
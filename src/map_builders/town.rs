@@ -44,6 +44,7 @@ enum BuildingTag {
     PlayerHouse,
     NPCHouse,
     Mine,
+    Shop,
     Abandoned,
     Unassigned,
 }
@@ -110,7 +111,8 @@ impl TownBuilder {
         building_size[1].2 = BuildingTag::Temple;
         building_size[2].2 = BuildingTag::Mine;
         building_size[3].2 = BuildingTag::PlayerHouse;
-        for b in building_size.iter_mut().skip(4) {
+        building_size[4].2 = BuildingTag::Shop;
+        for b in building_size.iter_mut().skip(5) {
             b.2 = BuildingTag::NPCHouse;
         }
         let last_idx = building_size.len() - 1;
@@ -133,6 +135,7 @@ impl TownBuilder {
                 BuildingTag::Temple => self.build_temple(&building, build_data, rng),
                 BuildingTag::Mine => self.build_mine(&building, build_data, rng),
                 BuildingTag::PlayerHouse => self.build_playerhouse(&building, build_data, rng),
+                BuildingTag::Shop => self.build_shop(&building, build_data, rng),
                 BuildingTag::NPCHouse => self.build_npchouse(&building, build_data, rng),
                 BuildingTag::Abandoned => self.build_abandoned(&building, build_data, rng),
                 _ => {}
@@ -230,6 +233,7 @@ impl TownBuilder {
             "npc_townsperson",
             "npc_drunk",
             "npc_drunk",
+            "npc_mercenary",
             "prop_keg",
             "prop_table",
             "prop_table",
@@ -291,6 +295,17 @@ impl TownBuilder {
         self.random_building_spawn(building, build_data, rng, &mut to_place, 0);
     }
 
+    fn build_shop(
+        &mut self,
+        building: &(i32, i32, i32, i32),
+        build_data: &mut BuilderMap,
+        rng: &mut RandomNumberGenerator
+    ) {
+        // The shopkeeper lays out their own stock when they're spawned.
+        let mut to_place: Vec<&str> = vec!["npc_shopkeeper", "prop_chair"];
+        self.random_building_spawn(building, build_data, rng, &mut to_place, 0);
+    }
+
     fn build_npchouse(
         &mut self,
         building: &(i32, i32, i32, i32),
@@ -0,0 +1,262 @@
+use super::{ BuilderMap, MetaMapBuilder, TileType };
+use crate::{ tile_deep, tile_walkable };
+use bracket_lib::prelude::*;
+use std::cmp::{ max, min };
+use std::collections::VecDeque;
+
+/// Dry patches smaller than this, cut off by deep water, are flooded rather than bridged.
+const MIN_ISLAND_SIZE: usize = 4;
+/// How many spots to try for the middle of a lake before giving up on it.
+const LAKE_TRIES: i32 = 20;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum WaterMode {
+    River,
+    Lake,
+}
+
+/// Floods part of the level, with a river running from one side of the map to the other,
+/// or a lake somewhere in the middle. Water only fills open ground, so it comes and goes
+/// through the walls. Anywhere left cut off by deep water is bridged back to wherever the
+/// player starts, so nobody has to swim to get anywhere.
+pub struct Water {
+    mode: WaterMode,
+}
+
+impl MetaMapBuilder for Water {
+    #[allow(dead_code)]
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.apply(rng, build_data);
+    }
+}
+
+impl Water {
+    #[allow(dead_code)]
+    pub fn river() -> Box<Water> {
+        return Box::new(Water { mode: WaterMode::River });
+    }
+
+    #[allow(dead_code)]
+    pub fn lake() -> Box<Water> {
+        return Box::new(Water { mode: WaterMode::Lake });
+    }
+
+    fn apply(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        match self.mode {
+            WaterMode::River => self.river_course(rng, build_data),
+            WaterMode::Lake => self.lake_bed(rng, build_data),
+        }
+        build_data.take_snapshot();
+        self.bridge(build_data);
+        // Nothing gets left at the bottom of the water.
+        let tiles = &build_data.map.tiles;
+        build_data.spawn_list.retain(|(idx, _name)| !tile_deep(tiles[*idx]));
+        build_data.take_snapshot();
+    }
+
+    /// A channel of deep water with shallow banks, wandering from one edge of the map to
+    /// the opposite one.
+    fn river_course(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let breadth = rng.roll_dice(1, 2) + 1;
+        let across = rng.roll_dice(1, 2) == 1;
+        let (length, span) = if across {
+            (build_data.map.width, build_data.map.height)
+        } else {
+            (build_data.map.height, build_data.map.width)
+        };
+        let mut offset = rng.roll_dice(1, span / 2) + span / 4;
+        for step in 1..length - 1 {
+            if rng.roll_dice(1, 3) == 1 {
+                offset = max(2, min(span - 3 - breadth, offset + rng.roll_dice(1, 3) - 2));
+            }
+            for o in offset - 1..=offset + breadth {
+                let (x, y) = if across { (step, o) } else { (o, step) };
+                let bank = o == offset - 1 || o == offset + breadth;
+                flood(build_data, x, y, if bank { TileType::ShallowWater } else { TileType::DeepWater });
+            }
+        }
+    }
+
+    /// A rough circle of deep water with a shallow shore, centred on open ground.
+    fn lake_bed(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let radius = rng.roll_dice(1, 4) + 3;
+        let (width, height) = (build_data.map.width, build_data.map.height);
+        let mut centre: Option<Point> = None;
+        for _i in 0..LAKE_TRIES {
+            let x = rng.roll_dice(1, width - radius * 2 - 2) + radius;
+            let y = rng.roll_dice(1, height - radius * 2 - 2) + radius;
+            if can_flood(build_data, build_data.map.xy_idx(x, y)) {
+                centre = Some(Point::new(x, y));
+                break;
+            }
+        }
+        let centre = if let Some(centre) = centre { centre } else { return };
+        let mut noise = FastNoise::seeded(rng.roll_dice(1, 65536) as u64);
+        noise.set_noise_type(NoiseType::Simplex);
+        noise.set_frequency(0.2);
+        for y in centre.y - radius - 2..=centre.y + radius + 2 {
+            for x in centre.x - radius - 2..=centre.x + radius + 2 {
+                let distance =
+                    DistanceAlg::Pythagoras.distance2d(centre, Point::new(x, y)) +
+                    noise.get_noise(x as f32, y as f32) * 2.0;
+                if distance < (radius as f32) - 1.5 {
+                    flood(build_data, x, y, TileType::DeepWater);
+                } else if distance < (radius as f32) {
+                    flood(build_data, x, y, TileType::ShallowWater);
+                }
+            }
+        }
+    }
+
+    /// Finds every patch of dry land, and joins each one to the mainland - wherever the
+    /// player starts - with the shortest bridge over the deep water between them.
+    fn bridge(&mut self, build_data: &mut BuilderMap) {
+        let map_size = build_data.map.tiles.len();
+        let regions = dry_regions(build_data);
+        if regions.is_empty() {
+            return;
+        }
+        let mut region_of: Vec<Option<usize>> = vec![None; map_size];
+        for (i, region) in regions.iter().enumerate() {
+            for idx in region.iter() {
+                region_of[*idx] = Some(i);
+            }
+        }
+        let largest = (0..regions.len()).max_by_key(|i| regions[*i].len()).unwrap();
+        let mainland = build_data.starting_position
+            .as_ref()
+            .and_then(|pos| region_of[build_data.map.xy_idx(pos.x, pos.y)])
+            .unwrap_or(largest);
+
+        let mut connected: Vec<bool> = vec![false; regions.len()];
+        connected[mainland] = true;
+        for (i, region) in regions.iter().enumerate() {
+            let tiny = region.len() < MIN_ISLAND_SIZE;
+            if i != mainland && tiny && region.iter().all(|idx| can_flood(build_data, *idx)) {
+                for idx in region.iter() {
+                    build_data.map.tiles[*idx] = TileType::DeepWater;
+                    region_of[*idx] = None;
+                }
+                connected[i] = true;
+            }
+        }
+
+        loop {
+            // Search out over the water from everywhere that's connected, until we
+            // reach somewhere that isn't.
+            let mut seen: Vec<bool> = vec![false; map_size];
+            let mut came_from: Vec<Option<usize>> = vec![None; map_size];
+            let mut open: VecDeque<usize> = VecDeque::new();
+            for idx in 0..map_size {
+                if region_of[idx].map_or(false, |r| connected[r]) {
+                    seen[idx] = true;
+                    open.push_back(idx);
+                }
+            }
+            let mut landfall: Option<(usize, usize)> = None;
+            'search: while let Some(idx) = open.pop_front() {
+                for next in neighbours(build_data, idx) {
+                    if seen[next] {
+                        continue;
+                    }
+                    seen[next] = true;
+                    if let Some(region) = region_of[next] {
+                        landfall = Some((region, idx));
+                        break 'search;
+                    }
+                    if tile_deep(build_data.map.tiles[next]) {
+                        came_from[next] = Some(idx);
+                        open.push_back(next);
+                    }
+                }
+            }
+            let (region, last) = if let Some(landfall) = landfall { landfall } else { break };
+            let mut step = Some(last);
+            while let Some(idx) = step {
+                if !tile_deep(build_data.map.tiles[idx]) {
+                    break;
+                }
+                build_data.map.tiles[idx] = TileType::Bridge;
+                step = came_from[idx];
+            }
+            connected[region] = true;
+        }
+    }
+}
+
+/// Open ground can be flooded, but not the edges of the map, anything special like stairs,
+/// or wherever the player starts.
+fn can_flood(build_data: &BuilderMap, idx: usize) -> bool {
+    let x = (idx as i32) % build_data.map.width;
+    let y = (idx as i32) / build_data.map.width;
+    if x < 1 || y < 1 || x > build_data.map.width - 2 || y > build_data.map.height - 2 {
+        return false;
+    }
+    let start = build_data.starting_position
+        .as_ref()
+        .map_or(false, |pos| build_data.map.xy_idx(pos.x, pos.y) == idx);
+    let open = matches!(
+        build_data.map.tiles[idx],
+        TileType::Floor |
+            TileType::WoodFloor |
+            TileType::Gravel |
+            TileType::Grass |
+            TileType::Foliage |
+            TileType::HeavyFoliage |
+            TileType::Sand |
+            TileType::ShallowWater
+    );
+    return open && !start;
+}
+
+fn flood(build_data: &mut BuilderMap, x: i32, y: i32, tile: TileType) {
+    if x < 0 || y < 0 || x >= build_data.map.width || y >= build_data.map.height {
+        return;
+    }
+    let idx = build_data.map.xy_idx(x, y);
+    if can_flood(build_data, idx) {
+        build_data.map.tiles[idx] = tile;
+    }
+}
+
+/// The tiles above, below, and to either side of idx, that aren't on the edge of the map.
+fn neighbours(build_data: &BuilderMap, idx: usize) -> Vec<usize> {
+    let x = (idx as i32) % build_data.map.width;
+    let y = (idx as i32) / build_data.map.width;
+    let mut result = Vec::new();
+    for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+        let (nx, ny) = (x + dx, y + dy);
+        if nx < 1 || ny < 1 || nx > build_data.map.width - 2 || ny > build_data.map.height - 2 {
+            continue;
+        }
+        result.push(build_data.map.xy_idx(nx, ny));
+    }
+    return result;
+}
+
+/// Every separate patch of ground that can be walked on, as lists of tile indices.
+fn dry_regions(build_data: &BuilderMap) -> Vec<Vec<usize>> {
+    let tiles = &build_data.map.tiles;
+    let mut seen: Vec<bool> = vec![false; tiles.len()];
+    let mut regions: Vec<Vec<usize>> = Vec::new();
+    for start in 0..tiles.len() {
+        if seen[start] || !tile_walkable(tiles[start]) {
+            continue;
+        }
+        let mut region: Vec<usize> = Vec::new();
+        let mut open: VecDeque<usize> = VecDeque::new();
+        seen[start] = true;
+        open.push_back(start);
+        while let Some(idx) = open.pop_front() {
+            region.push(idx);
+            for next in neighbours(build_data, idx) {
+                if !seen[next] && tile_walkable(tiles[next]) {
+                    seen[next] = true;
+                    open.push_back(next);
+                }
+            }
+        }
+        regions.push(region);
+    }
+    return regions;
+}
@@ -1,5 +1,8 @@
-use super::{ BuilderMap, Map, MetaMapBuilder, TileType };
+use super::{ draw_corridor, BuilderMap, InitialMapBuilder, Map, MetaMapBuilder, TileType };
+use crate::config::CONFIG;
+use crate::rex_assets::WFC_SOURCES;
 use bracket_lib::prelude::*;
+use std::collections::VecDeque;
 mod common;
 use common::*;
 mod constraints;
@@ -7,8 +10,26 @@ use constraints::*;
 mod solver;
 use solver::*;
 
-/// Provides a map builder using the Wave Function Collapse algorithm.
-pub struct WaveFunctionCollapseBuilder {}
+const CHUNK_SIZE: i32 = 8;
+/// How many times to start over after running into a contradiction, before making do
+/// with whatever got filled in on the last try.
+const MAX_ATTEMPTS: i32 = 10;
+/// Open areas smaller than this are filled in, rather than joined up to the rest.
+const MIN_REGION_SIZE: usize = 16;
+
+/// Provides a map builder using the Wave Function Collapse algorithm. Patterns are taken
+/// either from a REXPaint map, or from whatever map is already there. Once the map is
+/// solved, tiny pockets are filled in and everything else is joined up with corridors.
+pub struct WaveFunctionCollapseBuilder {
+    source: Option<String>,
+}
+
+impl InitialMapBuilder for WaveFunctionCollapseBuilder {
+    #[allow(dead_code)]
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
 
 impl MetaMapBuilder for WaveFunctionCollapseBuilder {
     fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
@@ -17,21 +38,28 @@ impl MetaMapBuilder for WaveFunctionCollapseBuilder {
 }
 
 impl WaveFunctionCollapseBuilder {
-    /// Constructor for wfc.
+    /// Constructor for wfc, using the map built so far as the source of patterns.
     #[allow(dead_code)]
     pub fn new() -> Box<WaveFunctionCollapseBuilder> {
-        Box::new(WaveFunctionCollapseBuilder {})
+        Box::new(WaveFunctionCollapseBuilder { source: None })
     }
 
-    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
-        const CHUNK_SIZE: i32 = 8;
-        build_data.take_snapshot();
+    /// Constructor for wfc, taking its patterns from the REXPaint map at path.
+    #[allow(dead_code)]
+    pub fn from_rex(path: &str) -> Box<WaveFunctionCollapseBuilder> {
+        Box::new(WaveFunctionCollapseBuilder { source: Some(path.to_string()) })
+    }
 
-        let patterns = build_patterns(&build_data.map, CHUNK_SIZE, true, true);
-        let constraints = patterns_to_constraints(patterns, CHUNK_SIZE);
-        self.render_tile_gallery(&constraints, CHUNK_SIZE, build_data);
+    /// Constructor for wfc, taking its patterns from a random one of the REXPaint maps.
+    #[allow(dead_code)]
+    pub fn random_rex(rng: &mut RandomNumberGenerator) -> Box<WaveFunctionCollapseBuilder> {
+        let path = WFC_SOURCES[(rng.roll_dice(1, WFC_SOURCES.len() as i32) - 1) as usize];
+        WaveFunctionCollapseBuilder::from_rex(path)
+    }
 
-        build_data.map = Map::new(
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        build_data.take_snapshot();
+        let blank = Map::new(
             build_data.map.overmap,
             build_data.map.id,
             build_data.map.width,
@@ -41,16 +69,42 @@ impl WaveFunctionCollapseBuilder {
             &build_data.map.short_name,
             build_data.map.depth
         );
+        let sample = match &self.source {
+            Some(path) => load_rex_sample(path, &blank),
+            None => build_data.map.clone(),
+        };
+
+        let patterns = build_patterns(&sample, CHUNK_SIZE, true, true);
+        let constraints = patterns_to_constraints(patterns, CHUNK_SIZE);
+        if CONFIG.logging.show_mapgen {
+            self.render_tile_gallery(&constraints, CHUNK_SIZE, build_data);
+        }
+
+        let mut attempts = 0;
         loop {
+            // Start from a clean slate every time, so a failed try doesn't leave anything behind.
+            build_data.map = blank.clone();
             let mut solver = Solver::new(constraints.clone(), CHUNK_SIZE, &build_data.map);
             while !solver.iteration(&mut build_data.map, rng) {
                 build_data.take_snapshot();
             }
             build_data.take_snapshot();
+            attempts += 1;
             if solver.possible {
                 break;
-            } // If it has hit an impossible condition, try again
+            }
+            if attempts >= MAX_ATTEMPTS {
+                console::log(
+                    format!(
+                        "DEBUGINFO: WFC hit a contradiction on every one of {} attempts, using the last",
+                        MAX_ATTEMPTS
+                    )
+                );
+                break;
+            }
         }
+        connect_regions(&mut build_data.map);
+        build_data.take_snapshot();
         build_data.spawn_list.clear();
     }
 
@@ -106,3 +160,99 @@ impl WaveFunctionCollapseBuilder {
         build_data.take_snapshot();
     }
 }
+
+/// Reads a REXPaint map into a map the same shape as template, to take patterns from.
+fn load_rex_sample(path: &str, template: &Map) -> Map {
+    let mut sample = template.clone();
+    let xp_file = XpFile::from_resource(path).unwrap();
+    if let Some(layer) = xp_file.layers.first() {
+        for y in 0..layer.height {
+            for x in 0..layer.width {
+                if x < (sample.width as usize) && y < (sample.height as usize) {
+                    let idx = sample.xy_idx(x as i32, y as i32);
+                    let cell = layer.get(x, y).unwrap();
+                    sample.tiles[idx] = if (cell.ch as u8 as char) == '#' {
+                        TileType::Wall
+                    } else {
+                        TileType::Floor
+                    };
+                }
+            }
+        }
+    }
+    return sample;
+}
+
+/// Fills in any open area too small to be worth visiting, and joins every other one to
+/// the nearest part of the map that's already connected, biggest areas first.
+pub fn connect_regions(map: &mut Map) {
+    let mut regions = find_regions(map);
+    regions.sort_by(|a, b| b.len().cmp(&a.len()));
+    // Always keep the biggest, even if it's tiny - a tiny map beats having nowhere to stand.
+    let mut kept: Vec<Vec<usize>> = Vec::new();
+    for (i, region) in regions.into_iter().enumerate() {
+        if i == 0 || region.len() >= MIN_REGION_SIZE {
+            kept.push(region);
+        } else {
+            for idx in region {
+                map.tiles[idx] = TileType::Wall;
+            }
+        }
+    }
+    if kept.is_empty() {
+        return;
+    }
+
+    let width = map.width;
+    let point = |idx: usize| Point::new((idx as i32) % width, (idx as i32) / width);
+    let mut connected: Vec<usize> = kept.remove(0);
+    for region in kept {
+        let mut best: Option<(usize, usize, f32)> = None;
+        for from in region.iter() {
+            for to in connected.iter() {
+                let distance = DistanceAlg::PythagorasSquared.distance2d(point(*from), point(*to));
+                if best.map_or(true, |(_, _, closest)| distance < closest) {
+                    best = Some((*from, *to, distance));
+                }
+            }
+        }
+        if let Some((from, to, _)) = best {
+            let (start, end) = (point(from), point(to));
+            let corridor = draw_corridor(map, start.x, start.y, end.x, end.y);
+            connected.extend(corridor);
+        }
+        connected.extend(region);
+    }
+}
+
+/// Every separate open area of the map, as lists of tile indices.
+fn find_regions(map: &Map) -> Vec<Vec<usize>> {
+    let mut seen: Vec<bool> = vec![false; map.tiles.len()];
+    let mut regions: Vec<Vec<usize>> = Vec::new();
+    for start in 0..map.tiles.len() {
+        if seen[start] || map.tiles[start] == TileType::Wall {
+            continue;
+        }
+        let mut region: Vec<usize> = Vec::new();
+        let mut open: VecDeque<usize> = VecDeque::new();
+        seen[start] = true;
+        open.push_back(start);
+        while let Some(idx) = open.pop_front() {
+            region.push(idx);
+            let (x, y) = ((idx as i32) % map.width, (idx as i32) / map.width);
+            for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= map.width || ny >= map.height {
+                    continue;
+                }
+                let next = map.xy_idx(nx, ny);
+                if !seen[next] && map.tiles[next] != TileType::Wall {
+                    seen[next] = true;
+                    open.push_back(next);
+                }
+            }
+        }
+        regions.push(region);
+    }
+    return regions;
+}
@@ -1,6 +1,5 @@
 use super::{ Map, MapChunk };
 use std::collections::HashSet;
-use bracket_lib::prelude::*;
 
 pub struct Solver {
     constraints: Vec<MapChunk>,
@@ -48,7 +47,7 @@ impl Solver {
                 }
             }
         }
-        if chunk_x > self.chunks_x - 1 {
+        if chunk_x < self.chunks_x - 1 {
             let right_idx = self.chunk_idx(chunk_x + 1, chunk_y);
             match self.chunks[right_idx] {
                 None => {}
@@ -66,7 +65,7 @@ impl Solver {
                 }
             }
         }
-        if chunk_y > self.chunks_y - 1 {
+        if chunk_y < self.chunks_y - 1 {
             let down_idx = self.chunk_idx(chunk_x, chunk_y + 1);
             match self.chunks[down_idx] {
                 None => {}
@@ -199,17 +198,16 @@ impl Solver {
             }
 
             if possible_options.is_empty() {
-                console::log("Oh no! It's not possible!");
                 self.possible = false;
                 return true;
             } else {
                 let new_chunk_idx = if possible_options.len() == 1 {
-                    0
+                    possible_options[0]
                 } else {
-                    rng.roll_dice(1, possible_options.len() as i32) - 1
+                    possible_options[(rng.roll_dice(1, possible_options.len() as i32) - 1) as usize]
                 };
 
-                self.chunks[chunk_index] = Some(new_chunk_idx as usize);
+                self.chunks[chunk_index] = Some(new_chunk_idx);
                 let left_x = (chunk_x as i32) * (self.chunk_size as i32);
                 let right_x = ((chunk_x as i32) + 1) * (self.chunk_size as i32);
                 let top_y = (chunk_y as i32) * (self.chunk_size as i32);
@@ -219,7 +217,7 @@ impl Solver {
                 for y in top_y..bottom_y {
                     for x in left_x..right_x {
                         let mapidx = map.xy_idx(x, y);
-                        let tile = self.constraints[new_chunk_idx as usize].pattern[i];
+                        let tile = self.constraints[new_chunk_idx].pattern[i];
                         map.tiles[mapidx] = tile;
                         i += 1;
                     }
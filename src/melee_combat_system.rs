@@ -2,10 +2,12 @@ use super::{
     effects::{ add_effect, EffectType, Targets },
     gamelog,
     gamesystem,
+    data::messages,
     gui::renderable_colour,
     ArmourClassBonus,
     Attributes,
     Blind,
+    Enchantment,
     EquipmentSlot,
     Equipped,
     HungerClock,
@@ -23,10 +25,12 @@ use super::{
     ToHitBonus,
     WantsToMelee,
     WeaponAttribute,
+    WeaponSkill,
     config::CONFIG,
 };
 use bracket_lib::prelude::*;
 use specs::prelude::*;
+use crate::data::entity::{ DUAL_WIELD_MAIN_PENALTY, DUAL_WIELD_OFF_PENALTY };
 
 pub struct MeleeCombatSystem {}
 
@@ -38,7 +42,7 @@ impl<'a> System<'a> for MeleeCombatSystem {
         WriteStorage<'a, WantsToMelee>,
         ReadStorage<'a, Name>,
         ReadStorage<'a, Attributes>,
-        ReadStorage<'a, Skills>,
+        WriteStorage<'a, Skills>,
         ReadStorage<'a, Pools>,
         WriteExpect<'a, ParticleBuilder>,
         ReadStorage<'a, Position>,
@@ -50,6 +54,8 @@ impl<'a> System<'a> for MeleeCombatSystem {
         ReadStorage<'a, HungerClock>,
         ReadStorage<'a, MultiAttack>,
         ReadStorage<'a, Blind>,
+        ReadStorage<'a, Enchantment>,
+        ReadStorage<'a, WeaponSkill>,
         WriteExpect<'a, RandomNumberGenerator>,
     );
 
@@ -61,7 +67,7 @@ impl<'a> System<'a> for MeleeCombatSystem {
             mut wants_melee,
             names,
             attributes,
-            skills,
+            mut skills,
             pools,
             mut particle_builder,
             positions,
@@ -73,6 +79,8 @@ impl<'a> System<'a> for MeleeCombatSystem {
             hunger_clock,
             multi_attackers,
             blind_entities,
+            enchantments,
+            weapon_skills,
             mut rng,
         ) = data;
 
@@ -91,6 +99,8 @@ impl<'a> System<'a> for MeleeCombatSystem {
 
         let mut logger = gamelog::Logger::new();
         let mut something_to_log = false;
+        // Weapon skills used successfully this turn, and who by.
+        let mut trained: Vec<(Entity, Skill)> = Vec::new();
 
         for (entity, wants_melee, name, attacker_attributes, attacker_skills, attacker_pools) in (
             &entities,
@@ -101,17 +111,38 @@ impl<'a> System<'a> for MeleeCombatSystem {
             &pools,
         ).join() {
             // Create blank vector of attacks being attempted.
-            let mut attacks: Vec<(MeleeWeapon, String)> = Vec::new();
+            let mut attacks: Vec<(MeleeWeapon, String, Option<Skill>)> = Vec::new();
             let mut multi_attack = false;
             // Check if attacker can multi-attack.
             if let Some(_) = multi_attackers.get(entity) {
                 multi_attack = true;
             }
-            // Check if attacker is using a weapon.
+            // Check if attacker is using a weapon. Fighting with a weapon in each
+            // hand gets an attack from both, but each is harder to land.
             let mut using_weapon = false;
-            for (wielded, melee) in (&equipped, &melee_weapons).join() {
-                if wielded.owner == entity && wielded.slot == EquipmentSlot::Melee {
-                    using_weapon = get_weapon_attack(wielded, melee, entity, &mut attacks);
+            let dual_wielding =
+                (&equipped, &melee_weapons)
+                    .join()
+                    .filter(|(wielded, _melee)| wielded.owner == entity && wielded.slot.holds_weapon())
+                    .count() > 1;
+            for (weapon, wielded, melee) in (&entities, &equipped, &melee_weapons).join() {
+                if wielded.owner == entity && wielded.slot.holds_weapon() {
+                    let enchantment = enchantments.get(weapon);
+                    let skill = weapon_skills.get(weapon).map(|s| s.skill);
+                    let penalty = match (dual_wielding, wielded.slot) {
+                        (false, _) => 0,
+                        (true, EquipmentSlot::OffHand) => DUAL_WIELD_OFF_PENALTY,
+                        (true, _) => DUAL_WIELD_MAIN_PENALTY,
+                    };
+                    using_weapon = get_weapon_attack(
+                        wielded,
+                        melee,
+                        enchantment,
+                        penalty,
+                        skill,
+                        entity,
+                        &mut attacks
+                    );
                 }
             }
             // If not using a weapon, get natural attacks. If we
@@ -131,6 +162,7 @@ impl<'a> System<'a> for MeleeCombatSystem {
                             hit_bonus: 0,
                         },
                         "punches".to_string(),
+                        Some(Skill::Unarmed),
                     ));
                 }
             }
@@ -147,10 +179,16 @@ impl<'a> System<'a> for MeleeCombatSystem {
                 }
                 let weapon_info = attack.0;
                 let attack_verb = attack.1;
+                let attack_skill = attack.2;
+                // Weapon skills add on top of general melee skill.
+                let weapon_skill_bonus = attack_skill
+                    .map(|skill| gamesystem::skill_bonus(skill, &*attacker_skills))
+                    .unwrap_or(0);
                 // Get all offensive bonuses
                 let d20 = rng.roll_dice(1, 20);
                 let attribute_hit_bonus = attacker_attributes.dexterity.bonus;
-                let skill_hit_bonus = gamesystem::skill_bonus(Skill::Melee, &*attacker_skills);
+                let skill_hit_bonus =
+                    gamesystem::skill_bonus(Skill::Melee, &*attacker_skills) + weapon_skill_bonus;
                 let mut equipment_hit_bonus = weapon_info.hit_bonus;
                 for (wielded, to_hit) in (&equipped, &to_hit).join() {
                     if wielded.owner == entity {
@@ -189,12 +227,24 @@ impl<'a> System<'a> for MeleeCombatSystem {
                 let bac = target_pools.bac;
                 let attribute_ac_bonus = target_attributes.dexterity.bonus / 2;
                 let skill_ac_bonus = gamesystem::skill_bonus(Skill::Defence, &*target_skills);
-                let mut armour_ac_bonus = 0;
+                let target_shield = (&equipped)
+                    .join()
+                    .any(|eq| eq.owner == wants_melee.target && eq.slot == EquipmentSlot::Shield);
+                let mut armour_ac_bonus = if target_shield {
+                    gamesystem::skill_bonus(Skill::Shield, &*target_skills)
+                } else {
+                    0
+                };
                 for (wielded, ac) in (&equipped, &ac).join() {
                     if wielded.owner == wants_melee.target {
                         armour_ac_bonus += ac.amount;
                     }
                 }
+                for (wielded, _ac, enchantment) in (&equipped, &ac, &enchantments).join() {
+                    if wielded.owner == wants_melee.target {
+                        armour_ac_bonus += enchantment.amount;
+                    }
+                }
                 let actual_armour_class =
                     bac - attribute_ac_bonus - skill_ac_bonus - armour_ac_bonus;
                 let mut armour_class_roll = actual_armour_class;
@@ -238,10 +288,11 @@ impl<'a> System<'a> for MeleeCombatSystem {
                         weapon_info.damage_n_dice,
                         weapon_info.damage_die_type
                     );
-                    let skill_damage_bonus = gamesystem::skill_bonus(
-                        Skill::Melee,
-                        &*attacker_skills
-                    );
+                    let skill_damage_bonus =
+                        gamesystem::skill_bonus(Skill::Melee, &*attacker_skills) + weapon_skill_bonus;
+                    if let Some(skill) = attack_skill {
+                        trained.push((entity, skill));
+                    }
                     let mut attribute_damage_bonus = weapon_info.damage_bonus;
                     match weapon_info.attribute {
                         WeaponAttribute::Dexterity => {
@@ -341,6 +392,10 @@ impl<'a> System<'a> for MeleeCombatSystem {
                     if CONFIG.logging.log_combat {
                         console::log(format!("ATTACKLOG: {} *MISSED*", &name.name));
                     }
+                    // A missed attack against someone with a shield trains them with it.
+                    if target_shield {
+                        trained.push((wants_melee.target, Skill::Shield));
+                    }
 
                     let pos = positions.get(wants_melee.target);
                     if let Some(pos) = pos {
@@ -379,6 +434,20 @@ impl<'a> System<'a> for MeleeCombatSystem {
         if something_to_log {
             logger.log();
         }
+        for (entity, skill) in trained {
+            if let Some(entity_skills) = skills.get_mut(entity) {
+                if gamesystem::train_skill(skill, entity_skills) && entity == *player_entity {
+                    gamelog::Logger
+                        ::new()
+                        .append(messages::SKILL_TRAINED)
+                        .colour(YELLOW)
+                        .append(skill.string())
+                        .colour(WHITE)
+                        .append("skill!")
+                        .log();
+                }
+            }
+        }
     }
 }
 
@@ -386,7 +455,7 @@ fn get_natural_attacks(
     rng: &mut RandomNumberGenerator,
     nat: NaturalAttacks,
     multi_attack: bool,
-    attacks: &mut Vec<(MeleeWeapon, String)>
+    attacks: &mut Vec<(MeleeWeapon, String, Option<Skill>)>
 ) {
     if !nat.attacks.is_empty() {
         if multi_attack {
@@ -401,6 +470,7 @@ fn get_natural_attacks(
                         damage_bonus: a.damage_bonus,
                     },
                     a.name.to_string(),
+                    None,
                 ));
             }
         } else {
@@ -419,6 +489,7 @@ fn get_natural_attacks(
                     damage_bonus: nat.attacks[attack_index].damage_bonus,
                 },
                 nat.attacks[attack_index].name.to_string(),
+                None,
             ));
         }
     }
@@ -427,11 +498,21 @@ fn get_natural_attacks(
 fn get_weapon_attack(
     wielded: &Equipped,
     melee: &MeleeWeapon,
+    enchantment: Option<&Enchantment>,
+    hit_penalty: i32,
+    skill: Option<Skill>,
     entity: Entity,
-    attacks: &mut Vec<(MeleeWeapon, String)>
+    attacks: &mut Vec<(MeleeWeapon, String, Option<Skill>)>
 ) -> bool {
-    if wielded.owner == entity && wielded.slot == EquipmentSlot::Melee {
-        attacks.push((melee.clone(), "hits".to_string()));
+    if wielded.owner == entity && wielded.slot.holds_weapon() {
+        let mut weapon = melee.clone();
+        // Enchantment adds to both to-hit and damage.
+        if let Some(enchantment) = enchantment {
+            weapon.hit_bonus += enchantment.amount;
+            weapon.damage_bonus += enchantment.amount;
+        }
+        weapon.hit_bonus += hit_penalty;
+        attacks.push((weapon, "hits".to_string(), skill));
         return true;
     }
     return false;
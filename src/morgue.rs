@@ -1,10 +1,10 @@
 use std::fs::{ File, create_dir_all };
 use std::io::{ self, Write };
 use std::time::SystemTime;
-use super::Map;
+use super::{ Map, MasterDungeonMap };
 use crate::gamelog;
 use crate::components::*;
-use crate::gui::{ Class, Ancestry, unobf_name_ecs };
+use crate::gui::{ Class, Ancestry, unobf_name_ecs, describe_level_up };
 use specs::prelude::*;
 use bracket_lib::prelude::*;
 use to_char;
@@ -88,6 +88,10 @@ fn create_morgue_string(ecs: &World) -> String {
     morgue_info.push_str(&draw_equipment(ecs));
     morgue_info.push_str(&create_boxed_text("Backpack", None));
     morgue_info.push_str(&draw_backpack(ecs));
+    morgue_info.push_str(&create_boxed_text("Level-ups", None));
+    morgue_info.push_str(&draw_level_ups(ecs));
+    morgue_info.push_str(&create_boxed_text("Uniques Slain", None));
+    morgue_info.push_str(&draw_uniques_slain(ecs));
     morgue_info.push_str(&create_boxed_text("Significant Events", None));
     morgue_info.push_str(&draw_events_list());
 
@@ -212,6 +216,9 @@ fn draw_equipment(ecs: &World) -> String {
             EquipmentSlot::Hands => "hands -",
             EquipmentSlot::Back => "back -",
             EquipmentSlot::Neck => "neck -",
+            EquipmentSlot::OffHand => "r-hand -",
+            EquipmentSlot::LeftRing => "l-ring -",
+            EquipmentSlot::RightRing => "r-ring -",
         };
         let name = if item.1 != 1 {
             unobf_name_ecs(ecs, item.0).1
@@ -254,6 +261,30 @@ fn draw_backpack(ecs: &World) -> String {
     return result;
 }
 
+fn draw_level_ups(ecs: &World) -> String {
+    let mut result: String = Default::default();
+    if let Some(history) = ecs.read_storage::<LevelHistory>().get(*ecs.fetch::<Entity>()) {
+        for level_up in history.level_ups.iter() {
+            result.push_str(&format!("- {}\n", describe_level_up(level_up)));
+        }
+    }
+    result.push_str("\n");
+    return result;
+}
+
+fn draw_uniques_slain(ecs: &World) -> String {
+    let mut result: String = Default::default();
+    let raws = &crate::raws::RAWS.lock().unwrap();
+    let dm = ecs.fetch::<MasterDungeonMap>();
+    for (id, map_id) in dm.uniques_slain() {
+        let name = crate::raws::get_mob_name(raws, &id).unwrap_or(id);
+        let level = crate::raws::get_level_label(raws, &dm, map_id);
+        result.push_str(&format!("- {}, on {}\n", name, level));
+    }
+    result.push_str("\n");
+    return result;
+}
+
 fn draw_events_list() -> String {
     // Initialise default (empty) string
     let mut result: String = Default::default();
@@ -1,37 +1,59 @@
 use super::{
     effects::{ add_effect, EffectType, Targets },
     gamelog,
+    gui,
     gui::obfuscate_name_ecs,
     gui::renderable_colour_ecs,
     gui::item_colour_ecs,
+    gui::ShopMode,
+    gui::ContainerMode,
     camera::get_screen_bounds,
     raws::Reaction,
+    spawner,
+    Ally,
     Attributes,
     BlocksTile,
     BlocksVisibility,
+    Container,
+    Currency,
+    Dialogue,
     Door,
     EntityMoved,
+    EntryTrigger,
     Faction,
     HasAncestry,
     Hidden,
+    Hireling,
     HungerClock,
     HungerState,
+    InBackpack,
     Item,
+    ItemType,
+    Locked,
     Map,
     Name,
     Player,
     Pools,
     Position,
+    Prop,
     Renderable,
     RunState,
+    Shopkeeper,
+    Stackable,
     states::state::*,
+    Tameable,
     Telepath,
     TileType,
+    Trapped,
     Viewshed,
     WantsToMelee,
     WantsToPickupItem,
     WantsToAssignKey,
+    WantsToDelete,
+    WantsToRemoveKey,
     get_dest,
+    tile_deep,
+    tile_hazardous,
     Destination,
     DamageType,
 };
@@ -40,6 +62,8 @@ use specs::prelude::*;
 use std::cmp::{ max, min };
 use crate::data::events::*;
 use crate::data::ids::*;
+use crate::data::messages;
+use crate::data::entity::TAME_DIFFICULTY;
 
 pub fn try_door(i: i32, j: i32, ecs: &mut World) -> RunState {
     let mut positions = ecs.write_storage::<Position>();
@@ -420,6 +444,194 @@ pub fn kick(i: i32, j: i32, ecs: &mut World) -> RunState {
     return RunState::Ticking;
 }
 
+/// Offers some food to a tameable animal. If it takes to the player, it becomes their
+/// ally - otherwise, the food is wasted.
+pub fn tame(i: i32, j: i32, ecs: &mut World) -> RunState {
+    let player_entity = *ecs.fetch::<Entity>();
+    let player_pos = *ecs.fetch::<Point>();
+    let mut target: Option<Entity> = None;
+    {
+        let map = ecs.fetch::<Map>();
+        let (x, y) = (player_pos.x + i, player_pos.y + j);
+        if x < 0 || x > map.width - 1 || y < 0 || y > map.height - 1 {
+            return RunState::AwaitingInput;
+        }
+        let tameables = ecs.read_storage::<Tameable>();
+        crate::spatial::for_each_tile_content(map.xy_idx(x, y), |entity| {
+            if tameables.get(entity).is_some() {
+                target = Some(entity);
+            }
+        });
+    }
+    let target = if let Some(target) = target {
+        target
+    } else {
+        gamelog::Logger::new().append(messages::TAME_NOTHING).period().log();
+        return RunState::AwaitingInput;
+    };
+    let food = {
+        let entities = ecs.entities();
+        let items = ecs.read_storage::<Item>();
+        let backpack = ecs.read_storage::<InBackpack>();
+        (&entities, &items, &backpack)
+            .join()
+            .find(|(_, item, pack)| {
+                pack.owner == player_entity && item.category == ItemType::Comestible
+            })
+            .map(|(entity, _, _)| entity)
+    };
+    let food = if let Some(food) = food {
+        food
+    } else {
+        gamelog::Logger::new().append(messages::TAME_NO_FOOD).period().log();
+        return RunState::AwaitingInput;
+    };
+    // Use up one of the food, deleting it if it was the last of the stack.
+    let last_of_stack = match ecs.write_storage::<Stackable>().get_mut(food) {
+        Some(stack) if stack.quantity > 1 => {
+            stack.quantity -= 1;
+            false
+        }
+        _ => true,
+    };
+    if last_of_stack {
+        ecs.write_storage::<WantsToRemoveKey>()
+            .insert(food, WantsToRemoveKey {})
+            .expect("Unable to insert WantsToRemoveKey");
+        ecs.write_storage::<WantsToDelete>()
+            .insert(food, WantsToDelete {})
+            .expect("Unable to insert WantsToDelete");
+    }
+    let (player_level, charisma) = {
+        let pools = ecs.read_storage::<Pools>();
+        let attributes = ecs.read_storage::<Attributes>();
+        (
+            pools
+                .get(player_entity)
+                .map(|p| p.level)
+                .unwrap_or(1),
+            attributes
+                .get(player_entity)
+                .map(|a| a.charisma.bonus)
+                .unwrap_or(0),
+        )
+    };
+    let target_level = ecs
+        .read_storage::<Pools>()
+        .get(target)
+        .map(|p| p.level)
+        .unwrap_or(0);
+    let roll = ecs.write_resource::<RandomNumberGenerator>().roll_dice(1, 20);
+    let tamed = roll + charisma + player_level > TAME_DIFFICULTY + target_level;
+    gamelog::Logger
+        ::new()
+        .append("The")
+        .colour(renderable_colour_ecs(ecs, target))
+        .append(obfuscate_name_ecs(ecs, target).0)
+        .colour(WHITE)
+        .append_n(if tamed { messages::TAME_SUCCESS } else { messages::TAME_FAIL })
+        .period()
+        .log();
+    if tamed {
+        spawner::make_ally(ecs, target, player_entity);
+    }
+    return RunState::Ticking;
+}
+
+/// Strikes up a conversation with whoever's in the given direction, if they've got
+/// anything to say.
+pub fn talk(i: i32, j: i32, ecs: &mut World) -> RunState {
+    let player_pos = *ecs.fetch::<Point>();
+    let mut speaker: Option<Entity> = None;
+    let mut quiet: Option<Entity> = None;
+    {
+        let map = ecs.fetch::<Map>();
+        let (x, y) = (player_pos.x + i, player_pos.y + j);
+        if x < 0 || x > map.width - 1 || y < 0 || y > map.height - 1 {
+            return RunState::AwaitingInput;
+        }
+        let dialogue = ecs.read_storage::<Dialogue>();
+        let pools = ecs.read_storage::<Pools>();
+        crate::spatial::for_each_tile_content(map.xy_idx(x, y), |entity| {
+            if dialogue.get(entity).is_some() {
+                speaker = Some(entity);
+            } else if pools.get(entity).is_some() {
+                quiet = Some(entity);
+            }
+        });
+    }
+    if let Some(speaker) = speaker {
+        return RunState::ShowDialogue { speaker, node: 0 };
+    }
+    if let Some(quiet) = quiet {
+        gamelog::Logger
+            ::new()
+            .append("The")
+            .colour(renderable_colour_ecs(ecs, quiet))
+            .append(obfuscate_name_ecs(ecs, quiet).0)
+            .colour(WHITE)
+            .append(messages::TALK_NOTHING_TO_SAY)
+            .period()
+            .log();
+    } else {
+        gamelog::Logger::new().append(messages::TALK_NOTHING).period().log();
+    }
+    return RunState::AwaitingInput;
+}
+
+/// Tries to open a container prop. Locked containers have to be forced open first, and
+/// trapped ones go off in the opener's face. Otherwise, the container menu is shown.
+fn open_container(ecs: &World, opener: Entity, container: Entity) -> RunState {
+    let colour = renderable_colour_ecs(ecs, container);
+    let name = obfuscate_name_ecs(ecs, container).0;
+    if ecs.read_storage::<Locked>().get(container).is_some() {
+        gamelog::Logger
+            ::new()
+            .append("The")
+            .colour(colour)
+            .append(&name)
+            .colour(WHITE)
+            .append_n(messages::CONTAINER_LOCKED)
+            .period()
+            .log();
+        let strength_bonus = ecs
+            .read_storage::<Attributes>()
+            .get(opener)
+            .map(|a| a.strength.bonus)
+            .unwrap_or(0);
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        if rng.roll_dice(1, 10) + strength_bonus > 6 {
+            ecs.write_storage::<Locked>().remove(container);
+            gamelog::Logger
+                ::new()
+                .append(messages::CONTAINER_FORCE_SUCCESS)
+                .colour(colour)
+                .append_n(&name)
+                .colour(WHITE)
+                .period()
+                .log();
+        } else {
+            gamelog::Logger
+                ::new()
+                .append(messages::CONTAINER_FORCE_FAIL)
+                .colour(colour)
+                .append_n(&name)
+                .colour(WHITE)
+                .period()
+                .log();
+        }
+        return RunState::Ticking;
+    }
+    if ecs.write_storage::<Trapped>().remove(container).is_some() {
+        gamelog::Logger::new().append(messages::CONTAINER_TRAPPED).log();
+        add_effect(Some(container), EffectType::TriggerFire { trigger: container }, Targets::Entity {
+            target: opener,
+        });
+        return RunState::Ticking;
+    }
+    return RunState::ShowContainer { container, mode: ContainerMode::Take };
+}
+
 pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
     let mut positions = ecs.write_storage::<Position>();
     let mut players = ecs.write_storage::<Player>();
@@ -434,6 +646,12 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState
     let mut wants_to_melee = ecs.write_storage::<WantsToMelee>();
     let mut doors = ecs.write_storage::<Door>();
     let names = ecs.read_storage::<Name>();
+    let shopkeepers = ecs.read_storage::<Shopkeeper>();
+    let containers = ecs.read_storage::<Container>();
+    let props = ecs.read_storage::<Prop>();
+    let hirelings = ecs.read_storage::<Hireling>();
+    let allies = ecs.read_storage::<Ally>();
+    let entry_triggers = ecs.read_storage::<EntryTrigger>();
     let mut swap_entities: Vec<(Entity, i32, i32)> = Vec::new();
     let mut result: Option<RunState>;
 
@@ -452,6 +670,7 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState
             return RunState::AwaitingInput;
         }
         let destination_idx = map.xy_idx(pos.x + delta_x, pos.y + delta_y);
+        let standing_on_trap = crate::ai::is_trapped(map.xy_idx(pos.x, pos.y), &entry_triggers);
 
         result = crate::spatial::for_each_tile_content_with_runstate(
             destination_idx,
@@ -472,11 +691,39 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState
                         hostile = false;
                     }
                 }
+                // Bumping into a friendly shopkeeper opens their shop.
+                if !hostile && shopkeepers.get(potential_target).is_some() {
+                    return Some(RunState::ShowShop { shopkeeper: potential_target, mode: ShopMode::Buy });
+                }
+                // Bumping into a friendly hireling asks if we want to hire them.
+                if !hostile && hirelings.get(potential_target).is_some() {
+                    return Some(RunState::ShowHire { hireling: potential_target });
+                }
+                // Allies won't swap places with us if it'd put them on a trap.
+                if !hostile && allies.get(potential_target).is_some() && standing_on_trap {
+                    gamelog::Logger
+                        ::new()
+                        .append("The")
+                        .colour(renderable_colour_ecs(ecs, potential_target))
+                        .append(obfuscate_name_ecs(ecs, potential_target).0)
+                        .colour(WHITE)
+                        .append_n(messages::ALLY_REFUSES_TRAP)
+                        .period()
+                        .log();
+                    return Some(RunState::AwaitingInput);
+                }
+                // Bumping into a container prop (i.e. a chest) tries to open it.
+                if containers.get(potential_target).is_some() && props.get(potential_target).is_some() {
+                    return Some(open_container(ecs, entity, potential_target));
+                }
                 if !hostile {
                     swap_entities.push((potential_target, pos.x, pos.y));
+                    let old_idx = map.xy_idx(pos.x, pos.y);
                     pos.x = min(map.width - 1, max(0, pos.x + delta_x));
                     pos.y = min(map.height - 1, max(0, pos.y + delta_y));
-                    entity_moved.insert(entity, EntityMoved {}).expect("Unable to insert marker");
+                    entity_moved
+                        .insert(entity, EntityMoved { from: old_idx })
+                        .expect("Unable to insert marker");
                     viewshed.dirty = true;
                     let mut ppos = ecs.write_resource::<Point>();
                     ppos.x = pos.x;
@@ -522,7 +769,11 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState
         }
 
         if swap_entities.len() <= 0 {
-            if crate::spatial::is_blocked(destination_idx) {
+            // Lava and chasms block pathing, but the player can still walk into them.
+            let hazard =
+                tile_hazardous(map.tiles[destination_idx]) &&
+                !crate::spatial::is_blocked_by_entity(destination_idx);
+            if crate::spatial::is_blocked(destination_idx) && !hazard {
                 gamelog::Logger::new().append("You can't move there.").log();
                 return RunState::AwaitingInput;
             }
@@ -540,8 +791,17 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState
             });
             match map.tiles[destination_idx] {
                 TileType::ToLocal(id) => {
-                    let name = get_local_desc(id);
-                    let colour = rgb_to_u8(get_local_col(id));
+                    let (name, colour) = {
+                        let raws = &crate::raws::RAWS.lock().unwrap();
+                        (
+                            crate::raws::get_level_name(
+                                raws,
+                                &ecs.fetch::<crate::map::MasterDungeonMap>(),
+                                id
+                            ),
+                            rgb_to_u8(crate::raws::get_level_colour(raws, id)),
+                        )
+                    };
                     gamelog::Logger
                         ::new()
                         .append("You see")
@@ -574,7 +834,9 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState
             pos.x = min(map.width - 1, max(0, pos.x + delta_x));
             pos.y = min(map.height - 1, max(0, pos.y + delta_y));
             let new_idx = map.xy_idx(pos.x, pos.y);
-            entity_moved.insert(entity, EntityMoved {}).expect("Unable to insert marker");
+            entity_moved
+                .insert(entity, EntityMoved { from: old_idx })
+                .expect("Unable to insert marker");
             crate::spatial::move_entity(entity, old_idx, new_idx);
             // Dirty viewsheds, and check only now if telepath viewshed exists
             viewshed.dirty = true;
@@ -584,6 +846,9 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState
             let mut ppos = ecs.write_resource::<Point>();
             ppos.x = pos.x;
             ppos.y = pos.y;
+            if tile_deep(map.tiles[new_idx]) && !tile_deep(map.tiles[old_idx]) {
+                gamelog::Logger::new().append(messages::SWIM_ENTER).log();
+            }
             if map.tiles[new_idx] == TileType::ToOvermap(map.id) {
                 return RunState::GoToLevel(ID_OVERMAP, TileType::ToLocal(map.id));
             }
@@ -636,7 +901,10 @@ fn get_item(ecs: &mut World) -> RunState {
         Some(item) => {
             let mut assignkey = ecs.write_storage::<WantsToAssignKey>();
             let mut pickup = ecs.write_storage::<WantsToPickupItem>();
-            assignkey.insert(item, WantsToAssignKey {}).expect("Unable to insert WantsToAssignKey");
+            // Currency goes straight into the purse, so it doesn't need a key.
+            if ecs.read_storage::<Currency>().get(item).is_none() {
+                assignkey.insert(item, WantsToAssignKey {}).expect("Unable to insert WantsToAssignKey");
+            }
             pickup
                 .insert(*player_entity, WantsToPickupItem { collected_by: *player_entity, item })
                 .expect("Unable to insert want to pickup item.");
@@ -755,6 +1023,33 @@ pub fn player_input(gs: &mut State, ctx: &mut BTerm, on_overmap: bool) -> RunSta
                 VirtualKeyCode::R => {
                     return RunState::ShowRemoveItem;
                 }
+                VirtualKeyCode::S => {
+                    if ctx.shift {
+                        return RunState::ShowSkills;
+                    }
+                }
+                VirtualKeyCode::Q => {
+                    if ctx.shift {
+                        return RunState::ShowQuestLog;
+                    }
+                }
+                VirtualKeyCode::T => {
+                    if !on_overmap {
+                        if ctx.shift {
+                            return RunState::ActionWithDirection { function: talk };
+                        }
+                        return RunState::ActionWithDirection { function: tame };
+                    }
+                }
+                VirtualKeyCode::A => {
+                    if ctx.shift {
+                        if gui::player_allies(&gs.ecs).is_empty() {
+                            gamelog::Logger::new().append(messages::ALLY_NO_ALLIES).period().log();
+                            return RunState::AwaitingInput;
+                        }
+                        return RunState::ShowAllyCommands;
+                    }
+                }
                 // Other
                 VirtualKeyCode::Minus => {
                     return RunState::ShowCheatMenu;
@@ -831,6 +1126,9 @@ fn skip_turn(ecs: &mut World) -> RunState {
             HungerState::Fainting => {
                 can_heal = false;
             }
+            HungerState::Starving => {
+                can_heal = false;
+            }
             _ => {}
         }
     }
@@ -0,0 +1,460 @@
+use crate::{
+    gamelog,
+    map_builders::{ build_quest_cache, BuilderMap },
+    raws::{ self, QuestObjectiveData, RawMaster, SpawnType },
+    reputation,
+    tile_walkable,
+    InBackpack,
+    Map,
+    MasterDungeonMap,
+    Name,
+    OtherLevelPosition,
+    Position,
+    Purse,
+    TileType,
+    WantsToDelete,
+    WantsToRemoveKey,
+};
+use crate::data::entity::*;
+use crate::data::events::EVENT;
+use crate::data::ids::ID_INFINITE;
+use crate::data::messages;
+use bracket_lib::prelude::*;
+use serde::{ Deserialize, Serialize };
+use specs::prelude::*;
+use std::collections::HashMap;
+
+const BOUNTY_PREFIX: &str = "bounty_";
+const BOUNTY_ITEM: &str = "quest_idol";
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum QuestStatus {
+    Active,
+    Completed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum Objective {
+    /// Kill `count` of a mob, counting from when the quest was taken.
+    Kill {
+        name: String,
+        count: i32,
+        baseline: i32,
+    },
+    /// Set foot on the level with this id.
+    Reach {
+        level: i32,
+    },
+    /// Find an item that's locked away in a cache on the level with this id.
+    Retrieve {
+        item: String,
+        name: String,
+        level: i32,
+        spawned: bool,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Quest {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub objectives: Vec<Objective>,
+    pub gold: i32,
+    pub items: Vec<String>,
+    pub reputation: HashMap<String, i32>,
+    pub status: QuestStatus,
+}
+
+/// Every quest the player has taken on this run. Saved in the DMSerializationHelper.
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct QuestLog {
+    pub quests: Vec<Quest>,
+}
+
+impl QuestLog {
+    pub fn new() -> QuestLog {
+        QuestLog { quests: Vec::new() }
+    }
+    /// Returns the status of a quest, or None if it hasn't been taken.
+    pub fn status(&self, id: &str) -> Option<QuestStatus> {
+        self.quests
+            .iter()
+            .find(|q| q.id == id)
+            .map(|q| q.status)
+    }
+}
+
+/// Returns (progress, needed) for an objective.
+pub fn objective_progress(ecs: &World, objective: &Objective) -> (i32, i32) {
+    match objective {
+        Objective::Kill { name, count, baseline } => {
+            let killed = gamelog::get_event_count(EVENT::count_killed(name)) - baseline;
+            (i32::min(killed, *count), *count)
+        }
+        Objective::Reach { level } => {
+            let here = ecs.fetch::<Map>().id == *level;
+            (if here { 1 } else { 0 }, 1)
+        }
+        Objective::Retrieve { name, .. } => {
+            let found = find_carried(ecs, name).is_some();
+            (if found { 1 } else { 0 }, 1)
+        }
+    }
+}
+
+/// e.g. "Kill jackal: 2/4", "Reach D4", "Find the jade idol on D3"
+pub fn describe_objective(ecs: &World, objective: &Objective) -> String {
+    match objective {
+        Objective::Kill { name, .. } => {
+            let (progress, needed) = objective_progress(ecs, objective);
+            format!("Kill {}: {}/{}", name, progress, needed)
+        }
+        Objective::Reach { level } => format!("Reach {}", level_label(ecs, *level)),
+        Objective::Retrieve { name, level, .. } => {
+            format!("Find the {} on {}", name, level_label(ecs, *level))
+        }
+    }
+}
+
+/// e.g. "D3", or the name of the town
+fn level_label(ecs: &World, level: i32) -> String {
+    raws::get_level_label(&raws::RAWS.lock().unwrap(), &ecs.fetch::<MasterDungeonMap>(), level)
+}
+
+/// Finds something in the player's backpack by its (unobfuscated) name.
+pub fn find_carried(ecs: &World, name: &str) -> Option<Entity> {
+    let player_entity = ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let names = ecs.read_storage::<Name>();
+    let backpack = ecs.read_storage::<InBackpack>();
+    (&entities, &names, &backpack)
+        .join()
+        .find(|(_, n, pack)| pack.owner == *player_entity && n.name == name)
+        .map(|(entity, _, _)| entity)
+}
+
+fn objective_from_data(raws: &RawMaster, data: &QuestObjectiveData) -> Option<Objective> {
+    if let Some(name) = &data.kill {
+        return Some(Objective::Kill {
+            name: name.clone(),
+            count: data.count.unwrap_or(1),
+            baseline: gamelog::get_event_count(EVENT::count_killed(name)),
+        });
+    }
+    if let Some(level) = data.reach {
+        return Some(Objective::Reach { level });
+    }
+    if let Some(item) = &data.retrieve {
+        return Some(Objective::Retrieve {
+            item: item.clone(),
+            name: raws::get_item_name(raws, item).unwrap_or_default(),
+            level: data.level.unwrap_or(ID_INFINITE),
+            spawned: false,
+        });
+    }
+    None
+}
+
+/// Takes on a quest from quests.json. Quests can only be taken once.
+pub fn start_quest(ecs: &mut World, raws: &RawMaster, id: &str) {
+    if ecs.fetch::<QuestLog>().status(id).is_some() {
+        return;
+    }
+    let data = if let Some(data) = raws::get_quest(raws, id) {
+        data
+    } else {
+        console::log(format!("DEBUGINFO: Tried to start unknown quest [{}]", id));
+        return;
+    };
+    let quest = Quest {
+        id: data.id.clone(),
+        name: data.name.clone(),
+        description: data.description.clone(),
+        objectives: data.objectives
+            .iter()
+            .filter_map(|o| objective_from_data(raws, o))
+            .collect(),
+        gold: data.reward.gold.unwrap_or(0),
+        items: data.reward.items.clone().unwrap_or_default(),
+        reputation: data.reward.reputation.clone().unwrap_or_default(),
+        status: QuestStatus::Active,
+    };
+    add_quest(ecs, raws, quest);
+}
+
+fn add_quest(ecs: &mut World, raws: &RawMaster, mut quest: Quest) {
+    // Levels that don't exist yet get their quest items when they're generated.
+    for objective in quest.objectives.iter_mut() {
+        if let Objective::Retrieve { item, level, spawned, .. } = objective {
+            *spawned = hide_on_existing_level(ecs, raws, item, *level);
+        }
+    }
+    gamelog::Logger
+        ::new()
+        .append(messages::QUEST_STARTED)
+        .colour(YELLOW)
+        .append_n(&quest.name)
+        .colour(WHITE)
+        .period()
+        .log();
+    gamelog::record_event(EVENT::StartedQuest(quest.name.clone()));
+    ecs.write_resource::<QuestLog>().quests.push(quest);
+}
+
+/// Returns the walkable tile furthest from start_idx - somewhere out of the way.
+fn furthest_tile(map: &mut Map, start_idx: usize) -> Option<usize> {
+    map.populate_blocked();
+    let dijkstra_map = DijkstraMap::new(
+        map.width as usize,
+        map.height as usize,
+        &[start_idx],
+        &*map,
+        1000.0
+    );
+    let mut furthest: Option<(usize, f32)> = None;
+    for (i, tile) in map.tiles.iter().enumerate() {
+        let distance = dijkstra_map.map[i];
+        if !tile_walkable(*tile) || distance == f32::MAX {
+            continue;
+        }
+        if furthest.map(|f| distance > f.1).unwrap_or(true) {
+            furthest = Some((i, distance));
+        }
+    }
+    furthest.map(|f| f.0)
+}
+
+/// Hides a quest item on a level that's already been generated, returning true if it was.
+/// It's too late to build a cache there, so it's just left somewhere out of the way.
+fn hide_on_existing_level(ecs: &mut World, raws: &RawMaster, item: &str, level: i32) -> bool {
+    let current = ecs.fetch::<Map>().id == level;
+    let (mut map, start_idx) = if current {
+        let map = ecs.fetch::<Map>().clone();
+        let player_pos = *ecs.fetch::<Point>();
+        let start_idx = map.xy_idx(player_pos.x, player_pos.y);
+        (map, start_idx)
+    } else {
+        let map = if let Some(map) = ecs.fetch::<MasterDungeonMap>().get_map(level) {
+            map
+        } else {
+            return false;
+        };
+        let start_idx = map.tiles
+            .iter()
+            .position(|t| *t == TileType::UpStair)
+            .or_else(|| map.tiles.iter().position(|t| tile_walkable(*t)))
+            .unwrap_or(0);
+        (map, start_idx)
+    };
+    let idx = if let Some(idx) = furthest_tile(&mut map, start_idx) { idx } else { return false };
+    let (x, y) = ((idx as i32) % map.width, (idx as i32) / map.width);
+    let entity = raws::spawn_named_entity(raws, ecs, item, None, SpawnType::AtPosition { x, y }, 0);
+    if let Some(entity) = entity {
+        if !current {
+            // It's on another level, so it waits there until that level is thawed.
+            ecs.write_storage::<Position>().remove(entity);
+            ecs.write_storage::<OtherLevelPosition>()
+                .insert(entity, OtherLevelPosition { x, y, id: level })
+                .expect("Unable to insert OtherLevelPosition");
+        }
+    }
+    entity.is_some()
+}
+
+/// Called as a new level is generated, before anything's spawned on it. Builds a cache for
+/// any quest items meant for this level, and - in the endless dungeon - posts a bounty if
+/// the player doesn't already have one.
+pub fn on_new_level(ecs: &mut World, build_data: &mut BuilderMap) {
+    let level = build_data.map.id;
+    let start_idx = build_data.starting_position
+        .as_ref()
+        .map(|pos| build_data.map.xy_idx(pos.x, pos.y));
+    {
+        let mut quest_log = ecs.write_resource::<QuestLog>();
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        for quest in quest_log.quests.iter_mut() {
+            if quest.status != QuestStatus::Active {
+                continue;
+            }
+            for objective in quest.objectives.iter_mut() {
+                if let Objective::Retrieve { item, level: item_level, spawned, .. } = objective {
+                    if *item_level != level || *spawned {
+                        continue;
+                    }
+                    if build_quest_cache(&mut rng, build_data, item) {
+                        *spawned = true;
+                        continue;
+                    }
+                    // If there's no room anywhere for a cache, it's left out of the way instead.
+                    let idx = start_idx.and_then(|s| furthest_tile(&mut build_data.map, s));
+                    if let Some(idx) = idx {
+                        build_data.spawn_list.push((idx, item.clone()));
+                        *spawned = true;
+                    }
+                }
+            }
+        }
+    }
+    if level >= ID_INFINITE {
+        post_bounty(ecs, build_data);
+    }
+}
+
+/// The endless dungeon has no hand-written quests, so each new level of it offers a bounty
+/// instead: kill some of what lives here, delve deeper, or bring back an idol from below.
+fn post_bounty(ecs: &mut World, build_data: &BuilderMap) {
+    let has_bounty = ecs
+        .fetch::<QuestLog>()
+        .quests.iter()
+        .any(|q| q.status == QuestStatus::Active && q.id.starts_with(BOUNTY_PREFIX));
+    if has_bounty {
+        return;
+    }
+    let raws = &raws::RAWS.lock().unwrap();
+    let level = build_data.map.id;
+    let mob_names: Vec<String> = build_data.spawn_list
+        .iter()
+        .filter_map(|(_, key)| raws::get_mob_name(raws, key))
+        .collect();
+    let (roll, pick) = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let pick = if mob_names.is_empty() {
+            0
+        } else {
+            rng.roll_dice(1, mob_names.len() as i32) - 1
+        };
+        (rng.roll_dice(1, 3), pick as usize)
+    };
+    let (name, objective) = match roll {
+        1 if !mob_names.is_empty() => {
+            let mob = mob_names[pick].clone();
+            let here = mob_names
+                .iter()
+                .filter(|n| **n == mob)
+                .count() as i32;
+            let objective = Objective::Kill {
+                name: mob.clone(),
+                count: i32::min(here, BOUNTY_MAX_KILLS),
+                baseline: gamelog::get_event_count(EVENT::count_killed(&mob)),
+            };
+            (format!("Bounty on the {}", mob), objective)
+        }
+        2 => {
+            let target = level + BOUNTY_DELVE_LEVELS;
+            let label = raws::get_level_label(raws, &ecs.fetch::<MasterDungeonMap>(), target);
+            (format!("Delve to {}", label), Objective::Reach { level: target })
+        }
+        _ => {
+            let item_name = raws::get_item_name(raws, BOUNTY_ITEM).unwrap_or_default();
+            let objective = Objective::Retrieve {
+                item: BOUNTY_ITEM.to_string(),
+                name: item_name.clone(),
+                level: level + BOUNTY_RETRIEVE_LEVELS,
+                spawned: false,
+            };
+            (format!("Recover the {}", item_name), objective)
+        }
+    };
+    gamelog::Logger::new().append(messages::QUEST_BOUNTY_NOTICE).log();
+    let label = raws::get_level_label(raws, &ecs.fetch::<MasterDungeonMap>(), level);
+    let quest = Quest {
+        id: format!("{}{}", BOUNTY_PREFIX, level),
+        description: format!("A bounty posted on {}.", label),
+        name,
+        objectives: vec![objective],
+        gold: BOUNTY_GOLD_PER_DEPTH * i32::max(1, build_data.map.depth),
+        items: Vec::new(),
+        reputation: HashMap::new(),
+        status: QuestStatus::Active,
+    };
+    add_quest(ecs, raws, quest);
+}
+
+/// Checks over every active quest, and pays out for any that have been completed.
+pub fn update_quests(ecs: &mut World) {
+    let completed: Vec<usize> = {
+        let quest_log = ecs.fetch::<QuestLog>();
+        quest_log.quests
+            .iter()
+            .enumerate()
+            .filter(|(_, q)| {
+                q.status == QuestStatus::Active &&
+                    q.objectives.iter().all(|o| {
+                        let (progress, needed) = objective_progress(ecs, o);
+                        progress >= needed
+                    })
+            })
+            .map(|(i, _)| i)
+            .collect()
+    };
+    for idx in completed {
+        complete_quest(ecs, idx);
+    }
+}
+
+fn complete_quest(ecs: &mut World, idx: usize) {
+    let quest = {
+        let mut quest_log = ecs.write_resource::<QuestLog>();
+        quest_log.quests[idx].status = QuestStatus::Completed;
+        quest_log.quests[idx].clone()
+    };
+    let player_entity = *ecs.fetch::<Entity>();
+    gamelog::Logger
+        ::new()
+        .append(messages::QUEST_COMPLETED)
+        .colour(YELLOW)
+        .append_n(&quest.name)
+        .colour(WHITE)
+        .append("!")
+        .log();
+    gamelog::record_event(EVENT::CompletedQuest(quest.name.clone()));
+    // Anything that had to be found is handed over.
+    for objective in quest.objectives.iter() {
+        if let Objective::Retrieve { name, .. } = objective {
+            if let Some(item) = find_carried(ecs, name) {
+                ecs.write_storage::<WantsToRemoveKey>()
+                    .insert(item, WantsToRemoveKey {})
+                    .expect("Unable to insert WantsToRemoveKey");
+                ecs.write_storage::<WantsToDelete>()
+                    .insert(item, WantsToDelete {})
+                    .expect("Unable to insert WantsToDelete");
+                gamelog::Logger
+                    ::new()
+                    .append(messages::QUEST_HAND_OVER)
+                    .append_n(format!("the {}", name))
+                    .period()
+                    .log();
+            }
+        }
+    }
+    if quest.gold > 0 {
+        let mut purses = ecs.write_storage::<Purse>();
+        if let Some(purse) = purses.get_mut(player_entity) {
+            purse.gold += quest.gold;
+        } else {
+            purses
+                .insert(player_entity, Purse { gold: quest.gold })
+                .expect("Unable to insert Purse");
+        }
+        gamelog::Logger
+            ::new()
+            .append(messages::QUEST_REWARD_GOLD)
+            .colour(GOLD)
+            .append(format!("{} gold", quest.gold))
+            .colour(WHITE)
+            .period()
+            .log();
+    }
+    if !quest.reputation.is_empty() {
+        for (faction, n) in quest.reputation.iter() {
+            reputation::modify_reputation(faction, *n);
+        }
+        gamelog::Logger::new().append(messages::QUEST_REWARD_REPUTATION).period().log();
+    }
+    let raws = &raws::RAWS.lock().unwrap();
+    for item in quest.items.iter() {
+        let by = player_entity;
+        raws::spawn_named_entity(raws, ecs, item, None, SpawnType::Carried { by }, 0);
+    }
+}
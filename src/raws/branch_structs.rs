@@ -0,0 +1,90 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A branch of the dungeon - a run of levels from first_id to last_id, built from the same
+/// recipe. A branch without a last_id goes on forever. Going down a level always goes to
+/// the next id, so a branch carries on into whichever branch starts at the id after it.
+/// Any "{}" in the names is filled in with a place name generated for the branch.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Branch {
+    pub id: String,
+    pub name: String,
+    pub short_name: String,
+    pub first_id: i32,
+    pub last_id: Option<i32>,
+    pub depth: i32,
+    pub builder: String,
+    pub difficulty: DifficultyCurve,
+    pub colour: Option<String>,
+    pub entrance: Option<bool>,
+    pub dead_end: Option<bool>,
+    pub spawn_tables: Option<HashMap<String, String>>,
+    pub bosses: Option<BranchBosses>,
+}
+
+/// The difficulty of a branch's first level, and how much harder each level after it gets.
+#[derive(Deserialize, Debug, Clone)]
+pub struct DifficultyCurve {
+    pub base: i32,
+    pub per_level: i32,
+}
+
+/// Every interval levels into the branch, the next of these mobs waits in a boss arena.
+#[derive(Deserialize, Debug, Clone)]
+pub struct BranchBosses {
+    pub interval: i32,
+    pub mobs: Vec<String>,
+}
+
+impl Branch {
+    pub fn contains(&self, id: i32) -> bool {
+        id >= self.first_id && self.last_id.map_or(true, |last_id| id <= last_id)
+    }
+
+    /// How many levels into the branch the given id is, counting the first as 0.
+    fn offset(&self, id: i32) -> i32 {
+        id - self.first_id
+    }
+
+    pub fn depth_of(&self, id: i32) -> i32 {
+        self.depth + self.offset(id)
+    }
+
+    pub fn difficulty_of(&self, id: i32) -> i32 {
+        self.difficulty.base + self.difficulty.per_level * self.offset(id)
+    }
+
+    /// The branch's first level can be reached from somewhere on the overmap.
+    pub fn has_entrance(&self) -> bool {
+        self.entrance.unwrap_or(false)
+    }
+
+    /// The branch's names have a place name to fill in.
+    pub fn is_named_place(&self) -> bool {
+        self.name.contains("{}") || self.short_name.contains("{}")
+    }
+
+    /// The level with this id is the last of a branch that leads nowhere.
+    pub fn is_dead_end(&self, id: i32) -> bool {
+        self.dead_end.unwrap_or(false) && self.last_id == Some(id)
+    }
+
+    /// The boss waiting on the level with this id, if it's a boss level. Once every boss
+    /// has been met, the levels they'd be on are built like any other.
+    pub fn boss_for(&self, id: i32) -> Option<&str> {
+        let bosses = self.bosses.as_ref()?;
+        let level = self.offset(id) + 1;
+        if bosses.interval <= 0 || level % bosses.interval != 0 {
+            return None;
+        }
+        bosses.mobs.get((level / bosses.interval - 1) as usize).map(|boss| boss.as_str())
+    }
+
+    /// The spawn table this branch uses in place of the named one.
+    pub fn spawn_table<'a>(&'a self, table: &'a str) -> &'a str {
+        self.spawn_tables
+            .as_ref()
+            .and_then(|tables| tables.get(table))
+            .map_or(table, |table| table.as_str())
+    }
+}
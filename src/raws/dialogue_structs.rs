@@ -0,0 +1,54 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A conversation tree. The first node is where every conversation starts.
+#[derive(Deserialize, Debug)]
+pub struct DialogueTree {
+    pub id: String,
+    pub nodes: Vec<DialogueNode>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DialogueNode {
+    pub id: String,
+    pub text: String,
+    pub options: Vec<DialogueOption>,
+}
+
+/// Something the player can say. Options whose conditions aren't met aren't offered,
+/// and an option with no next node ends the conversation.
+#[derive(Deserialize, Debug)]
+pub struct DialogueOption {
+    pub text: String,
+    pub next: Option<String>,
+    pub conditions: Option<DialogueConditions>,
+    pub actions: Option<DialogueActions>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DialogueConditions {
+    pub min_level: Option<i32>,
+    pub has_item: Option<String>,
+    pub events_min: Option<HashMap<String, i32>>,
+    pub events_max: Option<HashMap<String, i32>>,
+    pub reaction: Option<String>,
+    pub quest: Option<QuestCondition>,
+}
+
+/// Checks the state of a quest: "none" if it hasn't been taken, "active", or "completed".
+#[derive(Deserialize, Debug)]
+pub struct QuestCondition {
+    pub id: String,
+    pub status: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DialogueActions {
+    pub give_item: Option<String>,
+    pub take_item: Option<String>,
+    pub heal: Option<String>,
+    pub hint: Option<String>,
+    pub reveal_stairs: Option<bool>,
+    pub start_quest: Option<String>,
+    pub record: Option<String>,
+}
@@ -13,6 +13,8 @@ pub struct Item {
     pub flags: Option<Vec<String>>,
     pub effects: Option<HashMap<String, String>>,
     pub magic: Option<MagicItem>,
+    pub food: Option<Food>,
+    pub skill: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -36,6 +38,12 @@ pub struct Renderable {
     pub order: i32,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct Food {
+    pub nutrition: i32,
+    pub turns: i32,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct MagicItem {
     pub class: String,
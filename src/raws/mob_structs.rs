@@ -11,14 +11,20 @@ pub struct Mob {
     pub level: Option<i32>,
     pub bac: Option<i32>,
     pub speed: Option<i32>,
+    pub weight: Option<f32>,
     pub attacks: Option<Vec<NaturalAttack>>,
+    pub abilities: Option<Vec<MobAbility>>,
     pub attributes: Option<MobAttributes>,
     pub skills: Option<HashMap<String, i32>>,
     pub vision_range: Option<i32>,
     pub telepathy_range: Option<i32>,
     pub equipped: Option<Vec<String>>,
     pub loot: Option<LootTableInfo>,
+    pub drops: Option<Vec<String>>,
+    pub death_message: Option<String>,
+    pub shop: Option<ShopInfo>,
     pub quips: Option<Vec<String>>,
+    pub dialogue: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -38,8 +44,31 @@ pub struct NaturalAttack {
     pub damage: String,
 }
 
+/// A spell or special attack. The effect is one of "bolt", "breath", "heal", "summon" or
+/// "blink"; dice are the damage (with an optional damage type) or healing it does, and
+/// summon is the id of the mob it calls up, count times.
+#[derive(Deserialize, Debug)]
+pub struct MobAbility {
+    pub name: String,
+    pub effect: String,
+    pub range: i32,
+    pub cooldown: i32,
+    pub mana: Option<i32>,
+    pub dice: Option<String>,
+    pub summon: Option<String>,
+    pub count: Option<i32>,
+    pub colour: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct LootTableInfo {
     pub table: String,
     pub chance: f32,
 }
+
+#[derive(Deserialize, Debug)]
+pub struct ShopInfo {
+    pub tables: Vec<String>,
+    pub stock: String,
+    pub gold: String,
+}
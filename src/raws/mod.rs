@@ -5,6 +5,7 @@ mod item_structs;
 use item_structs::*;
 mod mob_structs;
 use mob_structs::*;
+pub use mob_structs::MobAbility;
 mod prop_structs;
 use prop_structs::Prop;
 mod spawn_table_structs;
@@ -14,6 +15,12 @@ use loot_table_structs::*;
 mod reaction_structs;
 pub use reaction_structs::Reaction;
 use reaction_structs::{ AncestryData, FactionData };
+mod dialogue_structs;
+pub use dialogue_structs::*;
+mod quest_structs;
+pub use quest_structs::*;
+mod branch_structs;
+pub use branch_structs::*;
 use std::sync::Mutex;
 use bracket_lib::prelude::*;
 
@@ -30,6 +37,9 @@ pub struct Raws {
     pub loot_tables: Vec<LootTable>,
     pub factions: Vec<FactionData>,
     pub ancestries: Vec<AncestryData>,
+    pub dialogue: Vec<DialogueTree>,
+    pub quests: Vec<QuestData>,
+    pub branches: Vec<Branch>,
 }
 
 embedded_resource!(RAW_ITEMS, "../../raws/items.json");
@@ -39,6 +49,9 @@ embedded_resource!(RAW_SPAWN_TABLES, "../../raws/spawn_tables.json");
 embedded_resource!(RAW_LOOT_TABLES, "../../raws/loot_tables.json");
 embedded_resource!(RAW_FACTIONS, "../../raws/factions.json");
 embedded_resource!(RAW_ANCESTRIES, "../../raws/ancestries.json");
+embedded_resource!(RAW_DIALOGUE, "../../raws/dialogue.json");
+embedded_resource!(RAW_QUESTS, "../../raws/quests.json");
+embedded_resource!(RAW_DUNGEON, "../../raws/dungeon.json");
 
 pub fn load_raws() {
     link_resource!(RAW_ITEMS, "../../raws/items.json");
@@ -48,6 +61,9 @@ pub fn load_raws() {
     link_resource!(RAW_LOOT_TABLES, "../../raws/loot_tables.json");
     link_resource!(RAW_FACTIONS, "../../raws/factions.json");
     link_resource!(RAW_ANCESTRIES, "../../raws/ancestries.json");
+    link_resource!(RAW_DIALOGUE, "../../raws/dialogue.json");
+    link_resource!(RAW_QUESTS, "../../raws/quests.json");
+    link_resource!(RAW_DUNGEON, "../../raws/dungeon.json");
 
     let decoded_raws = get_decoded_raws();
     RAWS.lock().unwrap().load(decoded_raws);
@@ -69,8 +85,28 @@ pub fn get_decoded_raws() -> Raws {
     let ancestries: Vec<AncestryData> = ParseJson::parse_raws_into_vector(
         "../../raws/ancestries.json".to_string()
     );
+    let dialogue: Vec<DialogueTree> = ParseJson::parse_raws_into_vector(
+        "../../raws/dialogue.json".to_string()
+    );
+    let quests: Vec<QuestData> = ParseJson::parse_raws_into_vector(
+        "../../raws/quests.json".to_string()
+    );
+    let branches: Vec<Branch> = ParseJson::parse_raws_into_vector(
+        "../../raws/dungeon.json".to_string()
+    );
 
-    return Raws { items, mobs, props, spawn_tables, loot_tables, factions, ancestries };
+    return Raws {
+        items,
+        mobs,
+        props,
+        spawn_tables,
+        loot_tables,
+        factions,
+        ancestries,
+        dialogue,
+        quests,
+        branches,
+    };
 }
 
 trait ParseJson {
@@ -87,4 +123,4 @@ macro_rules! impl_ParseJson {
         })*
     };
 }
-impl_ParseJson!(for Vec<Item>, Vec<Mob>, Vec<Prop>, Vec<SpawnTable>, Vec<LootTable>, Vec<FactionData>, Vec<AncestryData>);
+impl_ParseJson!(for Vec<Item>, Vec<Mob>, Vec<Prop>, Vec<SpawnTable>, Vec<LootTable>, Vec<FactionData>, Vec<AncestryData>, Vec<DialogueTree>, Vec<QuestData>, Vec<Branch>);
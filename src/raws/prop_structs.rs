@@ -9,4 +9,11 @@ pub struct Prop {
     pub renderable: Option<Renderable>,
     pub flags: Option<Vec<String>>,
     pub effects: Option<HashMap<String, String>>,
+    pub contents: Option<ContainerContents>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ContainerContents {
+    pub table: String,
+    pub rolls: String,
 }
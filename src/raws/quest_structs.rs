@@ -0,0 +1,32 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug)]
+pub struct QuestData {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub objectives: Vec<QuestObjectiveData>,
+    pub reward: QuestRewardData,
+}
+
+/// One thing a quest asks for. Exactly one of kill, reach, or retrieve should be set:
+/// kill `count` of a mob by name, reach the map with id `reach`, or find the item
+/// `retrieve`, which is spawned somewhere out of the way on the map with id `level`.
+#[derive(Deserialize, Debug)]
+pub struct QuestObjectiveData {
+    pub kill: Option<String>,
+    pub count: Option<i32>,
+    pub reach: Option<i32>,
+    pub retrieve: Option<String>,
+    pub level: Option<i32>,
+}
+
+/// What the player gets for finishing a quest. Reputation is standing gained with
+/// each named faction.
+#[derive(Deserialize, Debug)]
+pub struct QuestRewardData {
+    pub gold: Option<i32>,
+    pub items: Option<Vec<String>>,
+    pub reputation: Option<HashMap<String, i32>>,
+}
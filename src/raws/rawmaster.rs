@@ -1,11 +1,18 @@
-use super::{ Raws, Reaction };
+use super::{ mob_structs::MobAbility, Branch, DialogueTree, QuestData, Raws, Reaction };
 use crate::components::*;
 use crate::gamesystem::*;
 use crate::gui::Ancestry;
+use crate::map::MasterDungeonMap;
 use crate::random_table::RandomTable;
 use crate::config::CONFIG;
 use crate::data::visuals::BLOODSTAIN_COLOUR;
-use crate::data::entity::DEFAULT_VIEWSHED_STANDARD;
+use crate::data::entity::{
+    CORPSE_SPEED_THRESHOLD,
+    CORPSE_WEIGHT_PER_LEVEL,
+    DEFAULT_VIEWSHED_STANDARD,
+    HIRE_COST_PER_LEVEL,
+    REPUTATION_HOSTILE,
+};
 use bracket_lib::prelude::*;
 use specs::prelude::*;
 use specs::saveload::{ MarkedBuilder, SimpleMarker };
@@ -34,6 +41,9 @@ macro_rules! apply_effects {
                 "particle_line" => $eb = $eb.with(parse_particle_line(&effect.1)),
                 "particle_burst" => $eb = $eb.with(parse_particle_burst(&effect.1)),
                 "particle" => $eb = $eb.with(parse_particle(&effect.1)),
+                "container" => $eb = $eb.with(Container { weight_multiplier: effect.1.parse::<f32>().unwrap() }),
+                // Currency is pre-rolled in spawn_named_item, so there's nothing to do here.
+                "currency" => {}
                 _ => console::log(format!("Warning: effect {} not implemented.", effect_name)),
             }
         }
@@ -53,20 +63,24 @@ macro_rules! apply_flags {
                 "BLOCKS_VISIBILITY" => $eb = $eb.with(BlocksVisibility {}),
                 "ENTRY_TRIGGER" => $eb = $eb.with(EntryTrigger {}),
                 "SINGLE_ACTIVATION" => $eb = $eb.with(SingleActivation {}),
+                "LOCKED" => $eb = $eb.with(Locked {}),
+                "TRAPPED" => $eb = $eb.with(Trapped {}),
+                "TRAPDOOR" => $eb = $eb.with(Trapdoor {}),
                 "DOOR" => {
                     $eb = $eb.with(Door { open: false });
                     $eb = $eb.with(BlocksVisibility {});
                     $eb = $eb.with(BlocksTile {});
                 }
                 // --- EFFECT FLAGS ---
-                "FOOD" => $eb = $eb.with(ProvidesNutrition {}),
                 "CONSUMABLE" => $eb = $eb.with(Consumable {}),
                 "CHARGES" => $eb = $eb.with(Charges { uses: 3, max_uses: 3 }),
                 "REMOVE_CURSE" => $eb = $eb.with(ProvidesRemoveCurse {}),
                 "IDENTIFY" => $eb = $eb.with(ProvidesIdentify {}),
+                "ENCHANT_WEAPON" => $eb = $eb.with(ProvidesEnchantWeapon {}),
+                "ENCHANT_ARMOUR" => $eb = $eb.with(ProvidesEnchantArmour {}),
                 "DIGGER" => $eb = $eb.with(Digger {}),
                 "MAGICMAP" => $eb = $eb.with(MagicMapper {}),
-                "STACKABLE" => $eb = $eb.with(Stackable {}),
+                "STACKABLE" => $eb = $eb.with(Stackable { quantity: 1 }),
                 // CAN BE DESTROYED BY DAMAGE
                 "DESTRUCTIBLE" => $eb = $eb.with(Destructible {}),
                 // --- EQUIP SLOTS ---
@@ -78,6 +92,9 @@ macro_rules! apply_flags {
                 "EQUIP_HANDS" => $eb = $eb.with(Equippable { slot: EquipmentSlot::Hands }),
                 "EQUIP_NECK" => $eb = $eb.with(Equippable { slot: EquipmentSlot::Neck }),
                 "EQUIP_BACK" => $eb = $eb.with(Equippable { slot: EquipmentSlot::Back }),
+                "EQUIP_RING" => $eb = $eb.with(Equippable { slot: EquipmentSlot::LeftRing }),
+                "TWO_HANDED" => $eb = $eb.with(TwoHanded {}),
+                "OFF_HAND" => $eb = $eb.with(OffHandable {}),
                 // --- MOB ANCESTRIES ---
                 "IS_PLAYER" => $eb = $eb.with(Player {}),
                 "IS_HUMAN" => $eb = $eb.with(HasAncestry { name: Ancestry::Human }),
@@ -108,7 +125,7 @@ macro_rules! apply_flags {
                 "POISON_RES" => { damage_modifiers.insert(DamageType::Poison, DamageModifier::Resistance); }
                 // --- MOVEMENT MODES --- ( defaults to WANDER )
                 "STATIC" => $eb = $eb.with(MoveMode { mode: Movement::Static }),
-                "RANDOM_PATH" => $eb = $eb.with(MoveMode { mode: Movement::RandomWaypoint { path: None } }),
+                "RANDOM_PATH" => $eb = $eb.with(MoveMode { mode: Movement::RandomWaypoint { target: None } }),
                 // --- RANDOM MOB ATTRIBUTES ---
                 "GREEN_BLOOD" => $eb = $eb.with(Bleeds { colour: RGB::named((0, 153, 0)) }),
                 "BLUE_BLOOD" => $eb = $eb.with(Bleeds { colour: RGB::named((0, 0, 153)) }),
@@ -116,6 +133,14 @@ macro_rules! apply_flags {
                 "LARGE_GROUP" => {} // and don't need to apply a component.
                 "MULTIATTACK" => $eb = $eb.with(MultiAttack {}),
                 "BLIND" => $eb = $eb.with(Blind {}),
+                "AQUATIC" => $eb = $eb.with(Aquatic {}),
+                "LEVITATES" => $eb = $eb.with(Levitating {}),
+                "SHOPKEEPER" => $eb = $eb.with(Shopkeeper {}),
+                "TAMEABLE" => $eb = $eb.with(Tameable {}),
+                "LEADER" => $eb = $eb.with(Leader { engaged: false }),
+                "POISONOUS" => {} // Only affects the mob's corpse.
+                "HIRELING" => {} // Hire cost depends on level, so it's applied at spawn.
+                "UNIQUE" => {} // Uniques are tracked by the dungeon master, and applied at spawn.
                 _ => console::log(format!("Unrecognised flag: {}", flag.as_str())),
             }
         }
@@ -136,6 +161,9 @@ pub enum SpawnType {
     Carried {
         by: Entity,
     },
+    Contained {
+        by: Entity,
+    },
 }
 
 pub struct RawMaster {
@@ -147,6 +175,8 @@ pub struct RawMaster {
     loot_index: HashMap<String, usize>,
     faction_index: HashMap<String, HashMap<String, Reaction>>,
     ancestry_index: HashMap<String, HashSet<String>>,
+    dialogue_index: HashMap<String, usize>,
+    quest_index: HashMap<String, usize>,
 }
 
 impl RawMaster {
@@ -160,6 +190,9 @@ impl RawMaster {
                 loot_tables: Vec::new(),
                 factions: Vec::new(),
                 ancestries: Vec::new(),
+                dialogue: Vec::new(),
+                quests: Vec::new(),
+                branches: Vec::new(),
             },
             item_index: HashMap::new(),
             mob_index: HashMap::new(),
@@ -168,6 +201,8 @@ impl RawMaster {
             loot_index: HashMap::new(),
             faction_index: HashMap::new(),
             ancestry_index: HashMap::new(),
+            dialogue_index: HashMap::new(),
+            quest_index: HashMap::new(),
         }
     }
 
@@ -218,6 +253,52 @@ impl RawMaster {
         for ancestry in self.raws.ancestries.iter() {
             self.ancestry_index.insert(ancestry.id.clone(), ancestry.allies.clone());
         }
+        for (i, tree) in self.raws.dialogue.iter().enumerate() {
+            self.dialogue_index.insert(tree.id.clone(), i);
+        }
+        for (i, quest) in self.raws.quests.iter().enumerate() {
+            self.quest_index.insert(quest.id.clone(), i);
+            for objective in quest.objectives.iter() {
+                if let Some(item) = &objective.retrieve {
+                    check_for_unspecified_entity(&used_names, item);
+                }
+            }
+        }
+        for mob in self.raws.mobs.iter() {
+            if let Some(dialogue) = &mob.dialogue {
+                if !self.dialogue_index.contains_key(dialogue) {
+                    console::log(format!("DEBUGINFO: Mob references unspecified dialogue [{}]", dialogue));
+                }
+            }
+            if let Some(drops) = &mob.drops {
+                for drop in drops.iter() {
+                    check_for_unspecified_entity(&used_names, drop);
+                }
+            }
+        }
+        for (i, branch) in self.raws.branches.iter().enumerate() {
+            for other in self.raws.branches[i + 1..].iter() {
+                if branch.contains(other.first_id) || other.contains(branch.first_id) {
+                    console::log(
+                        format!("DEBUGINFO: Branches [{}] and [{}] overlap", branch.id, other.id)
+                    );
+                }
+            }
+            if let Some(tables) = &branch.spawn_tables {
+                for table in tables.values() {
+                    if !self.table_index.contains_key(table) {
+                        console::log(
+                            format!("DEBUGINFO: Branch references unspecified spawn table [{}]", table)
+                        );
+                    }
+                }
+            }
+            if let Some(bosses) = &branch.bosses {
+                for boss in bosses.mobs.iter() {
+                    check_for_unspecified_entity(&used_names, boss);
+                }
+            }
+        }
     }
 }
 
@@ -269,6 +350,7 @@ pub fn spawn_named_item(
         // --- WE GET ALL THE VALUES FROM THE ECS WE NEED HERE, BEFORE ---
         // --- WE CREATE THE EB, TO AVOID BORROW CHECKER COMPLAINTS    ---
         let roll = ecs.write_resource::<RandomNumberGenerator>().roll_dice(1, 6);
+        let currency = roll_currency(ecs, item_template);
         let player_entity = ecs.fetch::<Entity>();
         let known_beatitude = match pos {
             SpawnType::Equipped { by } => {
@@ -285,6 +367,26 @@ pub fn spawn_named_item(
         let needs_key = is_player_owned(&player_entity, &pos);
         std::mem::drop(player_entity);
         std::mem::drop(dm);
+        // BEATITUDE
+        let buc = if let Some(buc_status) = buc {
+            buc_status
+        } else {
+            match roll {
+                1 => BUC::Cursed,
+                2 => BUC::Blessed,
+                _ => BUC::Uncursed,
+            }
+        };
+        // ENCHANTMENT - only for things that can be equipped.
+        let is_equippable = item_template.flags
+            .as_ref()
+            .map(|flags| flags.iter().any(|flag| flag.starts_with("EQUIP_")))
+            .unwrap_or(false);
+        let enchantment = if is_equippable {
+            Some(roll_enchantment(&mut ecs.write_resource::<RandomNumberGenerator>(), &buc))
+        } else {
+            None
+        };
         // -- DROP EVERYTHING THAT INVOLVES THE ECS BEFORE THIS POINT ---
         let mut eb = ecs.create_entity().marked::<SimpleMarker<SerializeMe>>();
 
@@ -292,6 +394,7 @@ pub fn spawn_named_item(
             name: item_template.name.name.clone(),
             plural: item_template.name.plural.clone(),
         });
+        eb = eb.with(RawItem { id: key.to_string() });
         eb = eb.with(Item {
             weight: item_template.weight.unwrap_or(0.0),
             value: item_template.value.unwrap_or(0.0),
@@ -305,9 +408,14 @@ pub fn spawn_named_item(
                 "potion" => ItemType::Potion,
                 "ring" => ItemType::Ring,
                 "wand" => ItemType::Wand,
+                "currency" => ItemType::Currency,
+                "tool" => ItemType::Tool,
                 _ => unreachable!("Unknown item type."),
             },
         });
+        if let Some(amount) = currency {
+            eb = eb.with(Currency { amount });
+        }
         eb = spawn_position(pos, eb, key, raws);
         if needs_key {
             eb = eb.with(WantsToAssignKey {});
@@ -315,17 +423,10 @@ pub fn spawn_named_item(
         if let Some(renderable) = &item_template.renderable {
             eb = eb.with(get_renderable_component(renderable));
         }
-        // BEATITUDE
-        let buc = if let Some(buc_status) = buc {
-            buc_status
-        } else {
-            match roll {
-                1 => BUC::Cursed,
-                2 => BUC::Blessed,
-                _ => BUC::Uncursed,
-            }
-        };
         eb = eb.with(Beatitude { buc, known: known_beatitude });
+        if let Some(amount) = enchantment {
+            eb = eb.with(Enchantment { amount, known: known_beatitude });
+        }
 
         if let Some(flags) = &item_template.flags {
             apply_flags!(flags, eb);
@@ -333,6 +434,9 @@ pub fn spawn_named_item(
         if let Some(effects_list) = &item_template.effects {
             apply_effects!(effects_list, eb);
         }
+        if let Some(food) = &item_template.food {
+            eb = eb.with(ProvidesNutrition { amount: food.nutrition, turns: food.turns });
+        }
 
         if let Some(magic_item) = &item_template.magic {
             let item_class = match magic_item.class.as_str() {
@@ -391,6 +495,13 @@ pub fn spawn_named_item(
             };
             eb = eb.with(wpn);
         }
+        if let Some(skill) = &item_template.skill {
+            if let Some(skill) = skill_from_str(skill) {
+                eb = eb.with(WeaponSkill { skill });
+            } else {
+                console::log(format!("Unknown skill referenced: [{}]", skill));
+            }
+        }
 
         return Some(eb.build());
     }
@@ -398,6 +509,18 @@ pub fn spawn_named_item(
     None
 }
 
+/// Rolls the amount for a currency item, from its "currency" effect dice.
+fn roll_currency(ecs: &mut World, item_template: &super::item_structs::Item) -> Option<i32> {
+    if let Some(effects) = &item_template.effects {
+        if let Some(dice_string) = effects.get("currency") {
+            let dice = parse_dice_string(dice_string.as_str()).expect("Failed to parse dice string");
+            let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+            return Some(i32::max(1, rng.roll_dice(dice.n_dice, dice.die_type) + dice.bonus));
+        }
+    }
+    return None;
+}
+
 pub fn spawn_named_mob(
     raws: &RawMaster,
     ecs: &mut World,
@@ -407,6 +530,12 @@ pub fn spawn_named_mob(
 ) -> Option<Entity> {
     if raws.mob_index.contains_key(key) {
         let mob_template = &raws.raws.mobs[raws.mob_index[key]];
+        // Uniques only ever spawn once, so if this one already has, don't make another.
+        let unique = is_unique(raws, key);
+        if unique && !ecs.fetch_mut::<crate::map::MasterDungeonMap>().spawn_unique(key) {
+            return None;
+        }
+        let spawn_point = if let SpawnType::AtPosition { x, y } = &pos { Some(Point::new(*x, *y)) } else { None };
         let mut player_level = 1;
         let needs_key;
         {
@@ -426,6 +555,9 @@ pub fn spawn_named_mob(
             eb = eb.with(WantsToAssignKey {});
         }
         eb = eb.with(Name { name: mob_template.name.clone(), plural: mob_template.name.clone() });
+        if unique {
+            eb = eb.with(Unique { id: key.to_string() });
+        }
         eb = eb.with(Viewshed {
             visible_tiles: Vec::new(),
             range: if let Some(range) = mob_template.vision_range {
@@ -447,6 +579,8 @@ pub fn spawn_named_mob(
         eb = eb.with(Bleeds { colour: RGB::named(BLOODSTAIN_COLOUR) });
         let mut xp_value = 1;
         let mut has_mind = true;
+        let mut poisonous = false;
+        let mut corpse_intrinsic = None;
         if let Some(flags) = &mob_template.flags {
             apply_flags!(flags, eb);
             if flags.contains(&"MULTIATTACK".to_string()) {
@@ -455,6 +589,13 @@ pub fn spawn_named_mob(
             if flags.contains(&"MINDLESS".to_string()) {
                 has_mind = false;
             }
+            if flags.contains(&"POISONOUS".to_string()) {
+                poisonous = true;
+            }
+            // Plant-like mobs regrow, and so do those who eat them.
+            if flags.contains(&"GREEN_BLOOD".to_string()) {
+                corpse_intrinsic = Some(Intrinsic::Regeneration);
+            }
         }
         if has_mind {
             eb = eb.with(Mind {});
@@ -463,6 +604,10 @@ pub fn spawn_named_mob(
         if let Some(quips) = &mob_template.quips {
             eb = eb.with(Quips { available: quips.clone() });
         }
+        // And something to talk about, if they're chatty.
+        if let Some(dialogue) = &mob_template.dialogue {
+            eb = eb.with(Dialogue { tree: dialogue.clone() });
+        }
 
         // Setup combat stats
         let mut attr = Attributes {
@@ -539,25 +684,21 @@ pub fn spawn_named_mob(
         };
         eb = eb.with(pools);
         eb = eb.with(EquipmentChanged {});
+        if let Some(flags) = &mob_template.flags {
+            if flags.contains(&"HIRELING".to_string()) {
+                eb = eb.with(Hireling { cost: HIRE_COST_PER_LEVEL * (mob_level + 1) });
+            }
+        }
 
-        let mut skills = Skills { skills: HashMap::new() };
+        let mut skills = Skills { skills: HashMap::new(), training: HashMap::new() };
         skills.skills.insert(Skill::Melee, 0);
         skills.skills.insert(Skill::Defence, 0);
         if let Some(mobskills) = &mob_template.skills {
             for sk in mobskills.iter() {
-                match sk.0.as_str() {
-                    "melee" => {
-                        skills.skills.insert(Skill::Melee, *sk.1);
-                    }
-                    "defence" => {
-                        skills.skills.insert(Skill::Defence, *sk.1);
-                    }
-                    "magic" => {
-                        skills.skills.insert(Skill::Magic, *sk.1);
-                    }
-                    _ => {
-                        console::log(format!("Unknown skill referenced: [{}]", sk.0));
-                    }
+                if let Some(skill) = skill_from_str(sk.0) {
+                    skills.skills.insert(skill, *sk.1);
+                } else {
+                    console::log(format!("Unknown skill referenced: [{}]", sk.0));
                 }
             }
         }
@@ -579,6 +720,12 @@ pub fn spawn_named_mob(
             }
             eb = eb.with(natural);
         }
+        if let Some(mob_abilities) = &mob_template.abilities {
+            let abilities = Abilities {
+                abilities: mob_abilities.iter().map(parse_ability).collect(),
+            };
+            eb = eb.with(abilities);
+        }
 
         xp_value += mob_level * mob_level;
         if speed > 18 {
@@ -602,6 +749,18 @@ pub fn spawn_named_mob(
 
         eb = eb.with(GrantsXP { amount: xp_value });
 
+        // Setup corpse
+        eb = eb.with(LeavesCorpse {
+            weight: if let Some(weight) = mob_template.weight {
+                weight
+            } else {
+                CORPSE_WEIGHT_PER_LEVEL * ((base_mob_level + 1) as f32)
+            },
+            poisonous,
+            tainted: !has_mind,
+            intrinsic: if speed > CORPSE_SPEED_THRESHOLD { Some(Intrinsic::Speed) } else { corpse_intrinsic },
+        });
+
         // Setup loot table
         if let Some(loot) = &mob_template.loot {
             eb = eb.with(LootTable { table: loot.table.clone(), chance: loot.chance });
@@ -639,12 +798,70 @@ pub fn spawn_named_mob(
                 );
             }
         }
+        // Shopkeepers get a purse, and stock laid out on the floor around them
+        if let Some(shop) = &mob_template.shop {
+            stock_shop(raws, ecs, new_mob, shop, spawn_point, map_difficulty);
+        }
 
         return Some(new_mob);
     }
     None
 }
 
+/// Gives a shopkeeper their starting gold, and spawns their stock on the free
+/// tiles they can see that share the floor type they're standing on.
+fn stock_shop(
+    raws: &RawMaster,
+    ecs: &mut World,
+    shopkeeper: Entity,
+    shop: &super::mob_structs::ShopInfo,
+    spawn_point: Option<Point>,
+    map_difficulty: i32
+) {
+    let gold_dice = parse_dice_string(shop.gold.as_str()).expect("Failed to parse dice string");
+    let stock_dice = parse_dice_string(shop.stock.as_str()).expect("Failed to parse dice string");
+    let (gold, stock) = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        (
+            rng.roll_dice(gold_dice.n_dice, gold_dice.die_type) + gold_dice.bonus,
+            rng.roll_dice(stock_dice.n_dice, stock_dice.die_type) + stock_dice.bonus,
+        )
+    };
+    ecs.write_storage::<Purse>().insert(shopkeeper, Purse { gold }).expect("Unable to insert Purse");
+    let origin = if let Some(pt) = spawn_point {
+        pt
+    } else {
+        return;
+    };
+    let mut free_tiles: Vec<Point> = {
+        let map = ecs.fetch::<crate::map::Map>();
+        let occupied: HashSet<usize> = ecs
+            .read_storage::<Position>()
+            .join()
+            .map(|pos| map.xy_idx(pos.x, pos.y))
+            .collect();
+        crate::inventory::shop_floor(&*map, origin)
+            .into_iter()
+            .filter(|pt| !occupied.contains(&map.xy_idx(pt.x, pt.y)))
+            .collect()
+    };
+    for _i in 0..stock {
+        if free_tiles.is_empty() || shop.tables.is_empty() {
+            break;
+        }
+        let (tile, key) = {
+            let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+            let tile = free_tiles.remove((rng.roll_dice(1, free_tiles.len() as i32) - 1) as usize);
+            let table = &shop.tables[(rng.roll_dice(1, shop.tables.len() as i32) - 1) as usize];
+            let key = table_by_name(raws, table, Some(i32::max(1, map_difficulty))).roll(&mut rng);
+            (tile, key)
+        };
+        if let Some(item) = spawn_named_item(raws, ecs, &key, None, SpawnType::AtPosition { x: tile.x, y: tile.y }) {
+            ecs.write_storage::<ForSale>().insert(item, ForSale { shopkeeper }).expect("Unable to insert ForSale");
+        }
+    }
+}
+
 pub fn spawn_named_prop(
     raws: &RawMaster,
     ecs: &mut World,
@@ -685,11 +902,34 @@ pub fn spawn_named_prop(
             apply_effects!(effects_list, eb);
         }
         // BUILD THE ENTITY
-        return Some(eb.build());
+        let prop = eb.build();
+        // FILL IT, IF IT'S A CONTAINER
+        if let Some(contents) = &prop_template.contents {
+            fill_container(raws, ecs, prop, contents);
+        }
+        return Some(prop);
     }
     None
 }
 
+/// Rolls on a container's loot table a number of times, spawning the results inside it.
+fn fill_container(
+    raws: &RawMaster,
+    ecs: &mut World,
+    container: Entity,
+    contents: &super::prop_structs::ContainerContents
+) {
+    let keys: Vec<String> = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let dice = parse_dice_string(contents.rolls.as_str()).expect("Failed to parse dice string");
+        let rolls = rng.roll_dice(dice.n_dice, dice.die_type) + dice.bonus;
+        (0..rolls).filter_map(|_| roll_on_loot_table(raws, &mut rng, &contents.table)).collect()
+    };
+    for key in keys {
+        spawn_named_item(raws, ecs, &key, None, SpawnType::Contained { by: container });
+    }
+}
+
 fn spawn_position<'a>(
     pos: SpawnType,
     new_entity: EntityBuilder<'a>,
@@ -709,6 +949,9 @@ fn spawn_position<'a>(
             let slot = find_slot_for_equippable_item(tag, raws);
             eb = eb.with(Equipped { owner: by, slot });
         }
+        SpawnType::Contained { by } => {
+            eb = eb.with(InContainer { owner: by });
+        }
     }
 
     eb
@@ -747,7 +990,8 @@ pub fn table_by_name(raws: &RawMaster, key: &str, optional_difficulty: Option<i3
     if optional_difficulty.is_some() {
         difficulty = optional_difficulty.unwrap();
         upper_bound = difficulty;
-        lower_bound = if key != "mobs" { 0 } else { difficulty / 6 };
+        // Branches can swap in their own mob tables, e.g. "mobs_mines".
+        lower_bound = if !key.starts_with("mobs") { 0 } else { difficulty / 6 };
     } else {
         difficulty = -1;
         upper_bound = 100;
@@ -802,6 +1046,22 @@ pub fn table_by_name(raws: &RawMaster, key: &str, optional_difficulty: Option<i3
     (n_dice, die_type, die_bonus)
 }*/
 
+/// Parses a skill name as used in the raws, e.g. "short_blade".
+pub fn skill_from_str(name: &str) -> Option<Skill> {
+    match name {
+        "melee" => Some(Skill::Melee),
+        "defence" => Some(Skill::Defence),
+        "magic" => Some(Skill::Magic),
+        "short_blade" => Some(Skill::ShortBlade),
+        "long_blade" => Some(Skill::LongBlade),
+        "axe" => Some(Skill::Axe),
+        "polearm" => Some(Skill::Polearm),
+        "shield" => Some(Skill::Shield),
+        "unarmed" => Some(Skill::Unarmed),
+        _ => None,
+    }
+}
+
 fn find_slot_for_equippable_item(tag: &str, raws: &RawMaster) -> EquipmentSlot {
     if !raws.item_index.contains_key(tag) {
         unreachable!("Tried to equip an unknown item: {}", tag);
@@ -835,6 +1095,9 @@ fn find_slot_for_equippable_item(tag: &str, raws: &RawMaster) -> EquipmentSlot {
                 "EQUIP_HANDS" => {
                     return EquipmentSlot::Hands;
                 }
+                "EQUIP_RING" => {
+                    return EquipmentSlot::LeftRing;
+                }
                 _ => {}
             }
         }
@@ -989,6 +1252,126 @@ pub fn is_tag_magic(tag: &str) -> bool {
     }
 }
 
+/// Returns the real (unobfuscated) name of the item with the given id.
+pub fn get_item_name(raws: &RawMaster, id: &str) -> Option<String> {
+    raws.item_index.get(id).map(|idx| raws.raws.items[*idx].name.name.clone())
+}
+
+/// Returns the dialogue tree with the given id, if one exists.
+pub fn get_dialogue<'a>(raws: &'a RawMaster, id: &str) -> Option<&'a DialogueTree> {
+    raws.dialogue_index.get(id).map(|idx| &raws.raws.dialogue[*idx])
+}
+
+/// Returns the quest with the given id, if one exists.
+pub fn get_quest<'a>(raws: &'a RawMaster, id: &str) -> Option<&'a QuestData> {
+    raws.quest_index.get(id).map(|idx| &raws.raws.quests[*idx])
+}
+
+/// Returns the name of the mob with the given id.
+pub fn get_mob_name(raws: &RawMaster, id: &str) -> Option<String> {
+    raws.mob_index.get(id).map(|idx| raws.raws.mobs[*idx].name.clone())
+}
+
+/// Returns true if the mob with the given id is flagged as UNIQUE.
+pub fn is_unique(raws: &RawMaster, id: &str) -> bool {
+    raws.mob_index.get(id).map_or(false, |idx| {
+        let flags = &raws.raws.mobs[*idx].flags;
+        flags.as_ref().map_or(false, |flags| flags.contains(&"UNIQUE".to_string()))
+    })
+}
+
+/// Returns what's said when the unique mob with the given id dies, and the items it
+/// always drops.
+pub fn get_unique_death(raws: &RawMaster, id: &str) -> (Option<String>, Vec<String>) {
+    if let Some(idx) = raws.mob_index.get(id) {
+        let mob = &raws.raws.mobs[*idx];
+        return (mob.death_message.clone(), mob.drops.clone().unwrap_or_default());
+    }
+    (None, Vec::new())
+}
+
+/// Returns the branch the level with the given id belongs to, if any.
+pub fn get_branch(raws: &RawMaster, id: i32) -> Option<&Branch> {
+    raws.raws.branches.iter().find(|branch| branch.contains(id))
+}
+
+/// Fills in a branch name with the place name generated for the branch, if it has one.
+fn fill_place_name(template: &str, branch: &Branch, dm: &MasterDungeonMap) -> String {
+    template.replace("{}", dm.place_name(&branch.id).unwrap_or("somewhere"))
+}
+
+/// Returns the name and short name of the level with the given id - e.g. "the port town
+/// of Saff" and "Saff".
+pub fn get_level_names(
+    raws: &RawMaster,
+    dm: &MasterDungeonMap,
+    id: i32
+) -> Option<(String, String)> {
+    let branch = get_branch(raws, id)?;
+    let name = fill_place_name(&branch.name, branch, dm);
+    let short_name = fill_place_name(&branch.short_name, branch, dm);
+    Some((name, short_name))
+}
+
+/// Returns the name of the level with the given id - e.g. "the dungeon".
+pub fn get_level_name(raws: &RawMaster, dm: &MasterDungeonMap, id: i32) -> String {
+    match get_level_names(raws, dm, id) {
+        Some((name, _)) => name,
+        None => "an unnamed overmap tile".to_string(),
+    }
+}
+
+/// Returns the colour used when naming the level with the given id.
+pub fn get_level_colour(raws: &RawMaster, id: i32) -> RGB {
+    match get_branch(raws, id).and_then(|branch| branch.colour.as_ref()) {
+        Some(colour) => RGB::from_hex(colour).expect("Invalid RGB"),
+        None => RGB::named(WHITE),
+    }
+}
+
+/// A short label for a level, in the same style as the status bar - i.e. "D3".
+pub fn get_level_label(raws: &RawMaster, dm: &MasterDungeonMap, id: i32) -> String {
+    match get_branch(raws, id) {
+        Some(branch) if branch.depth > 0 => {
+            format!("{}{}", fill_place_name(&branch.short_name, branch, dm), branch.depth_of(id))
+        }
+        Some(branch) => fill_place_name(&branch.short_name, branch, dm),
+        None => format!("level {}", id),
+    }
+}
+
+/// Returns the id of the first level of every branch with an entrance on the overmap, in
+/// the order they're listed.
+pub fn get_branch_entrances(raws: &RawMaster) -> Vec<i32> {
+    raws.raws.branches
+        .iter()
+        .filter(|branch| branch.has_entrance())
+        .map(|branch| branch.first_id)
+        .collect()
+}
+
+/// Returns the ids of every branch that needs a place name generating for it.
+pub fn get_named_branches() -> Vec<String> {
+    let raws = &super::RAWS.lock().unwrap();
+    raws.raws.branches
+        .iter()
+        .filter(|branch| branch.is_named_place())
+        .map(|branch| branch.id.clone())
+        .collect()
+}
+
+/// Rolls up the named spawn table for the level with the given id, or whichever table
+/// its branch uses in place of it.
+pub fn table_for_level(
+    raws: &RawMaster,
+    id: i32,
+    key: &str,
+    optional_difficulty: Option<i32>
+) -> RandomTable {
+    let key = get_branch(raws, id).map_or(key, |branch| branch.spawn_table(key));
+    table_by_name(raws, key, optional_difficulty)
+}
+
 /// Queries the faction index to obtain one faction's reaction to another faction.
 pub fn faction_reaction(this_faction: &str, other_faction: &str, raws: &RawMaster) -> Reaction {
     if raws.faction_index.contains_key(this_faction) {
@@ -1022,12 +1405,38 @@ pub fn ancestry_reaction(
     return None;
 }
 
+/// Returns this_entity's reaction to other_entity. Where the player is involved, their
+/// reputation with the other side's faction can override the usual reaction.
 pub fn get_reactions(
     this_entity: Entity,
     other_entity: Entity,
     factions: &ReadStorage<Faction>,
     ancestries: &ReadStorage<HasAncestry>,
     raws: &RawMaster
+) -> Reaction {
+    let reaction = base_reaction(this_entity, other_entity, factions, ancestries, raws);
+    let this_faction = factions.get(this_entity);
+    let other_faction = factions.get(other_entity);
+    if let (Some(this_faction), Some(other_faction)) = (this_faction, other_faction) {
+        if other_faction.name == "player" {
+            let standing = crate::reputation::get_reputation(&this_faction.name);
+            return crate::reputation::reputation_reaction(reaction, standing);
+        } else if this_faction.name == "player" {
+            // The player treats anyone out for their blood as hostile, too.
+            if crate::reputation::get_reputation(&other_faction.name) <= REPUTATION_HOSTILE {
+                return Reaction::Attack;
+            }
+        }
+    }
+    return reaction;
+}
+
+fn base_reaction(
+    this_entity: Entity,
+    other_entity: Entity,
+    factions: &ReadStorage<Faction>,
+    ancestries: &ReadStorage<HasAncestry>,
+    raws: &RawMaster
 ) -> Reaction {
     if let Some(this_ancestry) = ancestries.get(this_entity) {
         if let Some(other_ancestry) = ancestries.get(other_entity) {
@@ -1103,6 +1512,57 @@ fn parse_particle_burst(n: &str) -> SpawnParticleBurst {
     }
 }
 
+/// Turns an ability from the raws into the Ability a mob carries around with it.
+pub fn parse_ability(ability: &MobAbility) -> Ability {
+    let parse_dice = |n: &Option<String>| {
+        parse_damage_string(n.as_ref().expect("Ability is missing its dice"))
+    };
+    let effect = match ability.effect.as_str() {
+        "bolt" => {
+            let (damage_type, dice) = parse_dice(&ability.dice);
+            AbilityEffect::Bolt {
+                damage_type,
+                n_dice: dice.n_dice,
+                sides: dice.die_type,
+                modifier: dice.bonus,
+            }
+        }
+        "breath" => {
+            let (damage_type, dice) = parse_dice(&ability.dice);
+            AbilityEffect::Breath {
+                damage_type,
+                n_dice: dice.n_dice,
+                sides: dice.die_type,
+                modifier: dice.bonus,
+            }
+        }
+        "heal" => {
+            let (_, dice) = parse_dice(&ability.dice);
+            AbilityEffect::Heal { n_dice: dice.n_dice, sides: dice.die_type, modifier: dice.bonus }
+        }
+        "summon" =>
+            AbilityEffect::Summon {
+                id: ability.summon.clone().expect("Summon ability is missing its summon"),
+                count: ability.count.unwrap_or(1),
+            },
+        "blink" => AbilityEffect::Blink,
+        _ => unreachable!("Unrecognised ability effect in raws: {}", ability.effect),
+    };
+    Ability {
+        name: ability.name.clone(),
+        effect,
+        range: ability.range,
+        cooldown: ability.cooldown,
+        mana: ability.mana.unwrap_or(0),
+        colour: if let Some(colour) = &ability.colour {
+            RGB::from_hex(colour).expect("Invalid RGB")
+        } else {
+            RGB::named(WHITE)
+        },
+        ready_in: 0,
+    }
+}
+
 fn parse_damage_string(n: &str) -> (DamageType, DiceType) {
     let tokens: Vec<_> = n.split(';').collect();
     let damage_type = if tokens.len() > 1 {
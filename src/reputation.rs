@@ -0,0 +1,135 @@
+use crate::{ gamelog, raws::{ self, Reaction, RawMaster }, Faction, Position, Viewshed };
+use crate::data::entity::*;
+use crate::data::messages;
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+use std::collections::{ HashMap, HashSet };
+use std::sync::Mutex;
+
+/// Factions that never hold a grudge against the player.
+const UNAFFECTED_FACTIONS: [&str; 2] = ["player", "ally"];
+
+lazy_static! {
+    /// Each faction's standing with the player. Factions that aren't in here are at 0.
+    static ref REPUTATION: Mutex<HashMap<String, i32>> = Mutex::new(HashMap::new());
+}
+
+/// Makes a copy of every faction's standing (FOR SERIALIZATION)
+pub fn clone_reputation() -> HashMap<String, i32> {
+    REPUTATION.lock().unwrap().clone()
+}
+/// Fetches standings into mutex (FOR DESERIALIZATION)
+pub fn restore_reputation(reputation: HashMap<String, i32>) {
+    *REPUTATION.lock().unwrap() = reputation;
+}
+/// Wipes all standings - for starting a new game.
+pub fn clear_reputation() {
+    REPUTATION.lock().unwrap().clear();
+}
+
+/// Returns a faction's standing with the player.
+pub fn get_reputation(faction: &str) -> i32 {
+    *REPUTATION.lock().unwrap().get(faction).unwrap_or(&0)
+}
+
+/// Changes a faction's standing with the player by n, and lets the player know if
+/// it's just turned against them.
+pub fn modify_reputation(faction: &str, n: i32) {
+    if UNAFFECTED_FACTIONS.contains(&faction) {
+        return;
+    }
+    let (before, after) = {
+        let mut reputation = REPUTATION.lock().unwrap();
+        let standing = reputation.entry(faction.to_string()).or_insert(0);
+        let before = *standing;
+        *standing += n;
+        (before, *standing)
+    };
+    if before > REPUTATION_HOSTILE && after <= REPUTATION_HOSTILE {
+        gamelog::Logger
+            ::new()
+            .colour(RED)
+            .append_n(messages::REPUTATION_HOSTILE)
+            .colour(WHITE)
+            .period()
+            .log();
+    } else if before > REPUTATION_WARY && after <= REPUTATION_WARY {
+        gamelog::Logger
+            ::new()
+            .colour(ORANGE)
+            .append_n(messages::REPUTATION_WARY)
+            .colour(WHITE)
+            .period()
+            .log();
+    }
+}
+
+/// Adjusts a reaction to the player by the faction's standing. Factions that would
+/// otherwise leave the player alone flee once wary of them, and attack once hostile.
+pub fn reputation_reaction(reaction: Reaction, standing: i32) -> Reaction {
+    if reaction == Reaction::Attack || standing <= REPUTATION_HOSTILE {
+        return Reaction::Attack;
+    }
+    if standing <= REPUTATION_WARY {
+        return Reaction::Flee;
+    }
+    return reaction;
+}
+
+/// The player attacked target. If it wasn't hostile to begin with, word gets around.
+pub fn on_attacked(ecs: &World, source: Entity, target: Entity) {
+    if source == *ecs.fetch::<Entity>() {
+        witnessed(ecs, target, REPUTATION_ATTACK_PENALTY);
+    }
+}
+
+/// The player killed target. If it wasn't hostile to begin with, word gets around.
+pub fn on_killed(ecs: &World, source: Entity, target: Entity) {
+    if source == *ecs.fetch::<Entity>() {
+        witnessed(ecs, target, REPUTATION_KILL_PENALTY);
+    }
+}
+
+/// Whether target's faction would ignore the player, if not for their reputation. Hunting
+/// things that flee anyway, or fighting back against things that attack, is fair game.
+fn is_innocent(ecs: &World, target: Entity, raws: &RawMaster) -> bool {
+    if let Some(faction) = ecs.read_storage::<Faction>().get(target) {
+        if UNAFFECTED_FACTIONS.contains(&faction.name.as_str()) {
+            return false;
+        }
+        return raws::faction_reaction(&faction.name, "player", raws) == Reaction::Ignore;
+    }
+    return false;
+}
+
+/// The victim's own faction loses the full penalty. Any other faction with a member
+/// who could see the victim loses a share of it.
+fn witnessed(ecs: &World, victim: Entity, penalty: i32) {
+    let victim_faction = {
+        let raws = &raws::RAWS.lock().unwrap();
+        if !is_innocent(ecs, victim, raws) {
+            return;
+        }
+        ecs.read_storage::<Faction>().get(victim).unwrap().name.clone()
+    };
+    let mut witnesses: HashSet<String> = HashSet::new();
+    if let Some(pos) = ecs.read_storage::<Position>().get(victim) {
+        let victim_pos = Point::new(pos.x, pos.y);
+        let player_entity = *ecs.fetch::<Entity>();
+        let entities = ecs.entities();
+        let factions = ecs.read_storage::<Faction>();
+        let viewsheds = ecs.read_storage::<Viewshed>();
+        for (entity, faction, viewshed) in (&entities, &factions, &viewsheds).join() {
+            if entity == player_entity || entity == victim || faction.name == victim_faction {
+                continue;
+            }
+            if viewshed.visible_tiles.contains(&victim_pos) {
+                witnesses.insert(faction.name.clone());
+            }
+        }
+    }
+    modify_reputation(&victim_faction, penalty);
+    for faction in witnesses.iter() {
+        modify_reputation(faction, penalty / REPUTATION_WITNESS_DIVISOR);
+    }
+}
@@ -4,6 +4,10 @@ embedded_resource!(TITLEIMAGE_105_56_BYTES, "../resources/title_image.xp");
 embedded_resource!(WFC_DEMO_IMAGE1, "../resources/wfc-demo1.xp");
 embedded_resource!(WFC_POPULATED, "../resources/wfc-populated.xp");
 
+/// REXPaint maps the wave function collapse builder can take its patterns from. '#' is a
+/// wall, and anything else is floor.
+pub const WFC_SOURCES: &[&str] = &["../resources/wfc-demo1.xp", "../resources/wfc-populated.xp"];
+
 pub struct RexAssets {
     pub menu: XpFile,
 }
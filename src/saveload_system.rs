@@ -36,6 +36,7 @@ pub fn save_game(ecs: &mut World) {
     // Create helper
     let mapcopy = ecs.get_mut::<super::map::Map>().unwrap().clone();
     let dungeon_master = ecs.get_mut::<super::map::MasterDungeonMap>().unwrap().clone();
+    let quest_log = ecs.get_mut::<crate::quests::QuestLog>().unwrap().clone();
     let savehelper = ecs
         .create_entity()
         .with(SerializationHelper { map: mapcopy })
@@ -48,6 +49,8 @@ pub fn save_game(ecs: &mut World) {
             log: crate::gamelog::clone_log(),
             event_counts: crate::gamelog::clone_event_counts(),
             events: crate::gamelog::clone_events(),
+            quests: quest_log,
+            reputation: crate::reputation::clone_reputation(),
         })
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
@@ -63,6 +66,9 @@ pub fn save_game(ecs: &mut World) {
             serializer,
             data,
             AOE,
+            Abilities,
+            Ally,
+            Aquatic,
             ArmourClassBonus,
             Attributes,
             Beatitude,
@@ -75,9 +81,15 @@ pub fn save_game(ecs: &mut World) {
             Clock,
             Confusion,
             Consumable,
+            Container,
+            Corpse,
+            Currency,
             Destructible,
+            Dialogue,
             Digger,
             Door,
+            Eating,
+            Enchantment,
             Energy,
             EntityMoved,
             EntryTrigger,
@@ -85,22 +97,34 @@ pub fn save_game(ecs: &mut World) {
             Equippable,
             Equipped,
             Faction,
+            Fainted,
+            Fleeing,
+            ForSale,
             GrantsSpell,
             GrantsXP,
+            GroupMember,
             HasAncestry,
             HasClass,
             HasDamageModifiers,
             Hidden,
+            Hireling,
             HungerClock,
             IdentifiedBeatitude,
             IdentifiedItem,
             InBackpack,
+            InContainer,
             InflictsDamage,
             IntrinsicChanged,
             Intrinsics,
             Item,
             Key,
             KnownSpells,
+            LastSeen,
+            Leader,
+            LeavesCorpse,
+            LevelHistory,
+            Levitating,
+            Locked,
             LootTable,
             MagicItem,
             MagicMapper,
@@ -111,19 +135,25 @@ pub fn save_game(ecs: &mut World) {
             NaturalAttacks,
             Name,
             ObfuscatedName,
+            OffHandable,
             OtherLevelPosition,
             ParticleLifetime,
             Player,
             Pools,
             Position,
             Prop,
+            ProvidesEnchantArmour,
+            ProvidesEnchantWeapon,
             ProvidesHealing,
             ProvidesIdentify,
             ProvidesNutrition,
             ProvidesRemoveCurse,
+            Purse,
             Quips,
             Ranged,
+            RawItem,
             Renderable,
+            Shopkeeper,
             SingleActivation,
             Skills,
             SpawnParticleBurst,
@@ -131,20 +161,30 @@ pub fn save_game(ecs: &mut World) {
             SpawnParticleSimple,
             Stackable,
             TakingTurn,
+            Tameable,
             Telepath,
             ToHitBonus,
+            Trapdoor,
+            Trapped,
+            TwoHanded,
+            Unique,
             Viewshed,
             Charges,
             WantsToApproach,
             WantsToAssignKey,
+            WantsToBuy,
             WantsToDelete,
             WantsToDropItem,
             WantsToFlee,
             WantsToMelee,
             WantsToPickupItem,
+            WantsToPutInContainer,
             WantsToRemoveItem,
             WantsToRemoveKey,
+            WantsToSell,
+            WantsToTakeFromContainer,
             WantsToUseItem,
+            WeaponSkill,
             SerializationHelper,
             DMSerializationHelper
         );
@@ -201,6 +241,9 @@ pub fn load_game(ecs: &mut World) {
             de,
             d,
             AOE,
+            Abilities,
+            Ally,
+            Aquatic,
             ArmourClassBonus,
             Attributes,
             Beatitude,
@@ -213,9 +256,15 @@ pub fn load_game(ecs: &mut World) {
             Clock,
             Confusion,
             Consumable,
+            Container,
+            Corpse,
+            Currency,
             Destructible,
+            Dialogue,
             Digger,
             Door,
+            Eating,
+            Enchantment,
             Energy,
             EntityMoved,
             EntryTrigger,
@@ -223,22 +272,34 @@ pub fn load_game(ecs: &mut World) {
             Equippable,
             Equipped,
             Faction,
+            Fainted,
+            Fleeing,
+            ForSale,
             GrantsSpell,
             GrantsXP,
+            GroupMember,
             HasAncestry,
             HasClass,
             HasDamageModifiers,
             Hidden,
+            Hireling,
             HungerClock,
             IdentifiedBeatitude,
             IdentifiedItem,
             InBackpack,
+            InContainer,
             InflictsDamage,
             IntrinsicChanged,
             Intrinsics,
             Item,
             Key,
             KnownSpells,
+            LastSeen,
+            Leader,
+            LeavesCorpse,
+            LevelHistory,
+            Levitating,
+            Locked,
             LootTable,
             MagicItem,
             MagicMapper,
@@ -249,19 +310,25 @@ pub fn load_game(ecs: &mut World) {
             NaturalAttacks,
             Name,
             ObfuscatedName,
+            OffHandable,
             OtherLevelPosition,
             ParticleLifetime,
             Player,
             Pools,
             Position,
             Prop,
+            ProvidesEnchantArmour,
+            ProvidesEnchantWeapon,
             ProvidesHealing,
             ProvidesIdentify,
             ProvidesNutrition,
             ProvidesRemoveCurse,
+            Purse,
             Quips,
             Ranged,
+            RawItem,
             Renderable,
+            Shopkeeper,
             SingleActivation,
             Skills,
             SpawnParticleBurst,
@@ -269,20 +336,30 @@ pub fn load_game(ecs: &mut World) {
             SpawnParticleSimple,
             Stackable,
             TakingTurn,
+            Tameable,
             Telepath,
             ToHitBonus,
+            Trapdoor,
+            Trapped,
+            TwoHanded,
+            Unique,
             Viewshed,
             Charges,
             WantsToApproach,
             WantsToAssignKey,
+            WantsToBuy,
             WantsToDelete,
             WantsToDropItem,
             WantsToFlee,
             WantsToMelee,
             WantsToPickupItem,
+            WantsToPutInContainer,
             WantsToRemoveItem,
             WantsToRemoveKey,
+            WantsToSell,
+            WantsToTakeFromContainer,
             WantsToUseItem,
+            WeaponSkill,
             SerializationHelper,
             DMSerializationHelper
         );
@@ -309,6 +386,8 @@ pub fn load_game(ecs: &mut World) {
             crate::gamelog::restore_log(&mut h.log.clone());
             crate::gamelog::restore_event_counter(h.event_counts.clone());
             crate::gamelog::restore_events(h.events.clone());
+            *ecs.write_resource::<crate::quests::QuestLog>() = h.quests.clone();
+            crate::reputation::restore_reputation(h.reputation.clone());
         }
         for (e, _p, pos) in (&entities, &player, &position).join() {
             let mut ppos = ecs.write_resource::<Point>();
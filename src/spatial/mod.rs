@@ -1,4 +1,4 @@
-use crate::{ tile_walkable, Map, RunState };
+use crate::{ tile_passable, Map, RunState };
 use specs::prelude::*;
 use std::sync::Mutex;
 
@@ -42,10 +42,11 @@ pub fn clear() {
 
 /// Iterates through every tile in the map, setting the SpatialMap's
 /// blocked-by-map tuple entry to true wherever a tile is impassable.
+/// Deep water isn't - it can be swum across.
 pub fn populate_blocked_from_map(map: &Map) {
     let mut lock = SPATIAL_MAP.lock().unwrap();
     for (i, tile) in map.tiles.iter().enumerate() {
-        lock.blocked[i].0 = !tile_walkable(*tile);
+        lock.blocked[i].0 = !tile_passable(*tile);
     }
 }
 
@@ -93,6 +94,12 @@ pub fn is_blocked(idx: usize) -> bool {
     return lock.blocked[idx].0 || lock.blocked[idx].1;
 }
 
+/// Returns true if the idx is blocked by an entity, regardless of the map tile.
+pub fn is_blocked_by_entity(idx: usize) -> bool {
+    let lock = SPATIAL_MAP.lock().unwrap();
+    return lock.blocked[idx].1;
+}
+
 /// Calls a function on every entity within a given tile idx.
 pub fn for_each_tile_content<F>(idx: usize, mut f: F) where F: FnMut(Entity) {
     let lock = SPATIAL_MAP.lock().unwrap();
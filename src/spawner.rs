@@ -1,14 +1,28 @@
 use super::{
     random_table::RandomTable,
     raws,
+    Ally,
+    AllyCommand,
     Attribute,
     Attributes,
+    Beatitude,
+    Charges,
+    Chasing,
     Clock,
+    Consumable,
+    Corpse,
+    Enchantment,
     Energy,
     EquipmentChanged,
     Faction,
+    Hireling,
     HungerClock,
     HungerState,
+    InBackpack,
+    InContainer,
+    Item,
+    ItemType,
+    LeavesCorpse,
     Map,
     Mind,
     Name,
@@ -16,13 +30,20 @@ use super::{
     Pool,
     Pools,
     Position,
+    ProvidesNutrition,
+    Purse,
+    RawItem,
     Rect,
     Renderable,
     SerializeMe,
     Skill,
     Skills,
+    Stackable,
+    Tameable,
     tile_walkable,
     Viewshed,
+    WantsToApproach,
+    WantsToFlee,
     BlocksTile,
     Bleeds,
     HasDamageModifiers,
@@ -38,7 +59,7 @@ use std::collections::{ HashMap, HashSet };
 
 /// Spawns the player and returns his/her entity object.
 pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
-    let mut skills = Skills { skills: HashMap::new() };
+    let mut skills = Skills { skills: HashMap::new(), training: HashMap::new() };
     skills.skills.insert(Skill::Melee, 0);
     skills.skills.insert(Skill::Defence, 0);
     skills.skills.insert(Skill::Magic, 0);
@@ -90,6 +111,7 @@ pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
         })
         .with(HasDamageModifiers { modifiers: HashMap::new() })
         .with(Intrinsics { list: HashSet::new() })
+        .with(Purse { gold: 0 })
         .with(EquipmentChanged {}) // To force re-calc of equipment bonuses.
         .with(skills)
         .with(Energy { current: 0, speed: entity::NORMAL_SPEED })
@@ -99,6 +121,97 @@ pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
     return player;
 }
 
+/// Spawns the corpse of a mob at the given position.
+pub fn corpse(ecs: &mut World, x: i32, y: i32, mob: &str, fg: RGB, leaves: &LeavesCorpse) -> Entity {
+    let corpse = Corpse {
+        mob: mob.to_string(),
+        age: if leaves.tainted { entity::CORPSE_ROTTEN_AGE } else { 0 },
+        poisonous: leaves.poisonous,
+        intrinsic: leaves.intrinsic,
+    };
+    let (name, plural) = corpse.name();
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable { glyph: to_cp437('%'), fg, bg: RGB::named(BLACK), render_order: 2 })
+        .with(Name { name, plural })
+        .with(Item { weight: leaves.weight, value: 0.0, category: ItemType::Comestible })
+        .with(ProvidesNutrition {
+            amount: (leaves.weight * entity::CORPSE_NUTRITION_PER_LB) as i32,
+            turns: i32::max(1, (leaves.weight / entity::CORPSE_LBS_EATEN_PER_TURN) as i32),
+        })
+        .with(Consumable {})
+        .with(corpse)
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build()
+}
+
+/// Copies each of the listed components from one entity to another, if the first has them.
+macro_rules! clone_components {
+    ($ecs:expr, $from:expr, $to:expr, $($component:ty),* $(,)?) => {
+        $(
+            {
+                let mut storage = $ecs.write_storage::<$component>();
+                if let Some(c) = storage.get($from).cloned() {
+                    storage.insert($to, c).expect("Unable to clone component");
+                }
+            }
+        )*
+    };
+}
+
+/// Splits a number of items off of a stack, returning the new stack. The new stack is
+/// spawned afresh from the raws, and then given everything about the old one that can
+/// differ between two of the same item. It ends up wherever the old one was, but it
+/// doesn't get an inventory key of its own. Returns None if the item can't be remade.
+pub fn split_stack(ecs: &mut World, item: Entity, quantity: i32) -> Option<Entity> {
+    let id = ecs
+        .read_storage::<RawItem>()
+        .get(item)
+        .map(|r| r.id.clone())?;
+    // Spawned on the floor rather than carried, so nothing gets identified or keyed,
+    // and then moved to wherever the old stack is.
+    let new_stack = raws::spawn_named_item(
+        &raws::RAWS.lock().unwrap(),
+        ecs,
+        &id,
+        None,
+        raws::SpawnType::AtPosition { x: 0, y: 0 }
+    )?;
+    ecs.write_storage::<Position>().remove(new_stack);
+    clone_components!(ecs, item, new_stack, Beatitude, Enchantment, Charges, Position, InContainer);
+    let owner = ecs
+        .read_storage::<InBackpack>()
+        .get(item)
+        .map(|b| b.owner);
+    if let Some(owner) = owner {
+        ecs.write_storage::<InBackpack>()
+            .insert(new_stack, InBackpack { owner })
+            .expect("Unable to insert InBackpack");
+    }
+    let mut stackables = ecs.write_storage::<Stackable>();
+    if let Some(stack) = stackables.get_mut(item) {
+        stack.quantity -= quantity;
+    }
+    stackables.insert(new_stack, Stackable { quantity }).expect("Unable to insert Stackable");
+    return Some(new_stack);
+}
+
+/// Turns a mob into an ally of the owner - it joins the owner's side, forgets whatever it
+/// was doing, and starts following them.
+pub fn make_ally(ecs: &mut World, entity: Entity, owner: Entity) {
+    ecs.write_storage::<Faction>()
+        .insert(entity, Faction { name: "ally".to_string() })
+        .expect("Unable to insert Faction");
+    ecs.write_storage::<Ally>()
+        .insert(entity, Ally { owner, command: AllyCommand::Follow })
+        .expect("Unable to insert Ally");
+    ecs.write_storage::<Tameable>().remove(entity);
+    ecs.write_storage::<Hireling>().remove(entity);
+    ecs.write_storage::<Chasing>().remove(entity);
+    ecs.write_storage::<WantsToApproach>().remove(entity);
+    ecs.write_storage::<WantsToFlee>().remove(entity);
+}
+
 /// Fills a room with stuff!
 pub fn spawn_room(
     map: &Map,
@@ -152,7 +265,7 @@ pub fn spawn_region(
     };
     // Roll on each table, getting an entity + spawn point
     if spawn_mob {
-        let key = mob_table(Some(difficulty)).roll(rng);
+        let key = level_table(map, "mobs", Some(difficulty)).roll(rng);
         let spawn_type = raws::get_mob_spawn_type(&raws::RAWS.lock().unwrap(), &key);
         let roll = raws::get_mob_spawn_amount(rng, &spawn_type, player_level);
         for _i in 0..roll {
@@ -160,13 +273,13 @@ pub fn spawn_region(
         }
     }
     for _i in 0..num_traps {
-        let key = trap_table(Some(difficulty)).roll(rng);
+        let key = level_table(map, "traps", Some(difficulty)).roll(rng);
         entity_to_spawn_list(rng, &mut areas, key, &mut spawn_points);
     }
     for _i in 0..num_items {
         // Player level isn't taken into account for item spawning, to encourage
         // delving deeper to gear up more quickly.
-        let key = get_random_item_category(rng, Some(map.difficulty)).roll(rng);
+        let key = get_random_item_category(rng, map, Some(map.difficulty)).roll(rng);
         entity_to_spawn_list(rng, &mut areas, key, &mut spawn_points);
     }
     // Push entities and their spawn points to map's spawn list
@@ -203,15 +316,17 @@ pub fn spawn_entity(ecs: &mut World, spawn: &(&usize, &String)) {
     let y = (*spawn.0 / width) as i32;
     std::mem::drop(map);
 
+    let raws = &raws::RAWS.lock().unwrap();
     let spawn_result = raws::spawn_named_entity(
-        &raws::RAWS.lock().unwrap(),
+        raws,
         ecs,
         &spawn.1,
         None,
         raws::SpawnType::AtPosition { x, y },
         map_difficulty
     );
-    if spawn_result.is_some() {
+    // Uniques that have already been spawned elsewhere are skipped on purpose.
+    if spawn_result.is_some() || raws::is_unique(raws, &spawn.1) {
         return;
     }
 
@@ -225,7 +340,8 @@ fn item_category_table() -> RandomTable {
         .add("food", 20)
         .add("potion", 16)
         .add("scroll", 16)
-        .add("wand", 4);
+        .add("wand", 4)
+        .add("tool", 4);
 }
 
 fn debug_table() -> RandomTable {
@@ -234,29 +350,28 @@ fn debug_table() -> RandomTable {
 
 fn get_random_item_category(
     rng: &mut RandomNumberGenerator,
+    map: &Map,
     difficulty: Option<i32>
 ) -> RandomTable {
     let item_category = item_category_table().roll(rng);
-    match item_category.as_ref() {
-        "equipment" => {
-            return equipment_table(difficulty);
-        }
-        "food" => {
-            return food_table(difficulty);
-        }
-        "potion" => {
-            return potion_table(difficulty);
-        }
-        "scroll" => {
-            return scroll_table(difficulty);
-        }
-        "wand" => {
-            return wand_table(difficulty);
-        }
+    let table = match item_category.as_ref() {
+        "equipment" => "equipment",
+        "food" => "food",
+        "potion" => "potions",
+        "scroll" => "scrolls",
+        "wand" => "wands",
+        "tool" => "tools",
         _ => {
             return debug_table();
         }
     };
+    return level_table(map, table, difficulty);
+}
+
+/// Locks RAWS, and provides the named table as used on this map - its branch may have
+/// swapped in a table of its own.
+pub fn level_table(map: &Map, table: &str, difficulty: Option<i32>) -> RandomTable {
+    raws::table_for_level(&raws::RAWS.lock().unwrap(), map.id, table, difficulty)
 }
 
 pub fn equipment_table(difficulty: Option<i32>) -> RandomTable {
@@ -279,6 +394,10 @@ pub fn food_table(difficulty: Option<i32>) -> RandomTable {
     raws::table_by_name(&raws::RAWS.lock().unwrap(), "food", difficulty)
 }
 
+pub fn tool_table(difficulty: Option<i32>) -> RandomTable {
+    raws::table_by_name(&raws::RAWS.lock().unwrap(), "tools", difficulty)
+}
+
 /// Locks RAWS, and provides access to master list of all mobs.
 pub fn mob_table(difficulty: Option<i32>) -> RandomTable {
     raws::table_by_name(&raws::RAWS.lock().unwrap(), "mobs", difficulty)
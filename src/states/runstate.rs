@@ -20,6 +20,31 @@ pub enum RunState {
     },
     ShowRemoveCurse,
     ShowIdentify,
+    ShowShop {
+        shopkeeper: Entity,
+        mode: gui::ShopMode,
+    },
+    ShowContainer {
+        container: Entity,
+        mode: gui::ContainerMode,
+    },
+    ShowHire {
+        hireling: Entity,
+    },
+    ShowAllyCommands,
+    ShowDialogue {
+        speaker: Entity,
+        node: usize,
+    },
+    ShowAllyTarget {
+        x: i32,
+        y: i32,
+    },
+    ShowQuantity {
+        item: Entity,
+        amount: i32,
+        action: gui::QuantityAction,
+    },
     ActionWithDirection {
         function: fn(i: i32, j: i32, ecs: &mut World) -> RunState,
     },
@@ -33,7 +58,13 @@ pub enum RunState {
     SaveGame,
     GameOver,
     GoToLevel(i32, TileType),
+    FallToLevel(i32), // Dropped through the floor to the level with this id
     HelpScreen,
+    ShowSkills,
+    ShowQuestLog,
+    ShowLevelUp {
+        attribute: Option<usize>,
+    },
     MagicMapReveal {
         row: i32,
         cursed: bool,
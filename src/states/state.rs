@@ -3,6 +3,7 @@ use bracket_lib::prelude::*;
 use super::runstate::RunState;
 use crate::map::*;
 use crate::hunger_system;
+use crate::corpse_system;
 use crate::particle_system;
 use crate::trigger_system;
 use crate::inventory;
@@ -23,6 +24,8 @@ use crate::camera;
 use crate::saveload_system;
 use crate::morgue;
 use crate::damage_system;
+use crate::quests;
+use crate::reputation;
 
 pub struct State {
     pub ecs: World,
@@ -41,13 +44,14 @@ impl State {
         let map_building_info = level_transition(&mut self.ecs, new_id, dest_tile);
         if let Some(history) = map_building_info {
             self.mapgen_history = history;
-        } else {
-            dungeon::thaw_entities(&mut self.ecs);
         }
+        // A new level has nothing waiting on it, unless something fell there first.
+        dungeon::thaw_entities(&mut self.ecs);
     }
 
     fn run_systems(&mut self) {
         let mut hunger_clock = hunger_system::HungerSystem {};
+        let mut corpse_system = corpse_system::CorpseSystem {};
         let mut particle_system = particle_system::ParticleSpawnSystem {};
 
         // Order is *very* important here, to ensure effects take place in the right order,
@@ -58,7 +62,9 @@ impl State {
         self.run_ai(); //                               Get AI decision-making.
         self.resolve_entity_decisions(); //             Push AI messages of intent to effects queue, and run it.
         hunger_clock.run_now(&self.ecs); //             Tick the hunger clock (on the turn clock's turn)
+        corpse_system.run_now(&self.ecs); //            Rot corpses (on the turn clock's turn)
         particle_system.run_now(&self.ecs); //          Spawn/delete particles (turn independent)
+        quests::update_quests(&mut self.ecs); //        Pay out for any quests that were completed this turn.
         self.ecs.maintain();
     }
 
@@ -70,6 +76,8 @@ impl State {
         let mut item_remove_system = inventory::ItemRemoveSystem {};
         let mut inventory_system = inventory::ItemCollectionSystem {};
         let mut item_id_system = inventory::ItemIdentificationSystem {};
+        let mut shop_system = inventory::ShopSystem {};
+        let mut container_system = inventory::ContainerSystem {};
         let mut key_system = inventory::KeyHandling {};
         let mut melee_system = MeleeCombatSystem {};
         trigger_system.run_now(&self.ecs);
@@ -79,12 +87,43 @@ impl State {
         item_drop_system.run_now(&self.ecs);
         item_remove_system.run_now(&self.ecs);
         item_id_system.run_now(&self.ecs);
+        shop_system.run_now(&self.ecs);
+        container_system.run_now(&self.ecs);
         key_system.run_now(&self.ecs);
         melee_system.run_now(&self.ecs);
 
         effects::run_effects_queue(&mut self.ecs);
     }
 
+    /// Inserts the player's intent to do something with an item (or a stack of items).
+    fn item_intent(&mut self, item: Entity, action: gui::QuantityAction) {
+        let player_entity = *self.ecs.fetch::<Entity>();
+        match action {
+            gui::QuantityAction::Drop => {
+                self.ecs
+                    .write_storage::<WantsToRemoveKey>()
+                    .insert(item, WantsToRemoveKey {})
+                    .expect("Unable to insert WantsToRemoveKey");
+                self.ecs
+                    .write_storage::<WantsToDropItem>()
+                    .insert(player_entity, WantsToDropItem { item })
+                    .expect("Unable to insert intent");
+            }
+            gui::QuantityAction::Sell { shopkeeper } => {
+                self.ecs
+                    .write_storage::<WantsToSell>()
+                    .insert(player_entity, WantsToSell { shopkeeper, item })
+                    .expect("Unable to insert intent");
+            }
+            gui::QuantityAction::Put { container } => {
+                self.ecs
+                    .write_storage::<WantsToPutInContainer>()
+                    .insert(player_entity, WantsToPutInContainer { container, item })
+                    .expect("Unable to insert intent");
+            }
+        }
+    }
+
     fn refresh_indexes(&mut self) {
         let mut mapindex = spatial::MapIndexingSystem {};
         let mut vis = VisibilitySystem {};
@@ -97,35 +136,72 @@ impl State {
         let mut energy = ai::EnergySystem {}; // Figures out who deserves a turn.
         let mut regen_system = ai::RegenSystem {}; // Restores HP on appropriate clock ticks.
         let mut turn_status_system = ai::TurnStatusSystem {}; // Ticks statuses. Should anyone now lose their turn? i.e. confusion
+        let mut eating_system = ai::EatingSystem {}; // Anyone who's busy eating loses their turn to it.
+        let mut swimming_system = ai::SwimmingSystem {}; // Anyone struggling to stay afloat loses their turn to it.
         let mut quip_system = ai::QuipSystem {}; // Quipping is "free". It doesn't use up a turn.
+        let mut morale_system = ai::MoraleSystem {}; // Decides who's fleeing, before anyone picks a fight.
+        let mut flow_fields = ai::FlowFieldSystem {}; // Keeps the shared pathing maps up to date with the terrain.
+        let mut ability_ai = ai::AbilityAI {}; // Spells and special attacks take priority over moving or melee.
         let mut adjacent_ai = ai::AdjacentAI {}; // AdjacentAI -> DefaultAI are all exclusive. If one acts, the entity's turn is over.
         let mut visible_ai = ai::VisibleAI {};
+        let mut ally_ai = ai::AllyAI {}; // Must run after VisibleAI - it decides if allies can act on what they saw.
+        let mut group_ai = ai::GroupAI {}; // Holds groups back until they've gathered, then spreads them out.
         let mut approach_ai = ai::ApproachAI {};
         let mut flee_ai = ai::FleeAI {};
         let mut chase_ai = ai::ChaseAI {};
+        let mut search_ai = ai::SearchAI {}; // Must run after ChaseAI - anyone who lost their target goes looking.
         let mut default_move_ai = ai::DefaultAI {};
         encumbrance_system.run_now(&self.ecs);
         energy.run_now(&self.ecs);
         regen_system.run_now(&self.ecs);
         turn_status_system.run_now(&self.ecs);
+        eating_system.run_now(&self.ecs);
+        swimming_system.run_now(&self.ecs);
         quip_system.run_now(&self.ecs);
+        morale_system.run_now(&self.ecs);
+        flow_fields.run_now(&self.ecs);
+        ability_ai.run_now(&self.ecs);
         adjacent_ai.run_now(&self.ecs);
         visible_ai.run_now(&self.ecs);
+        ally_ai.run_now(&self.ecs);
+        group_ai.run_now(&self.ecs);
         approach_ai.run_now(&self.ecs);
         flee_ai.run_now(&self.ecs);
         chase_ai.run_now(&self.ecs);
+        search_ai.run_now(&self.ecs);
         default_move_ai.run_now(&self.ecs);
     }
 
     fn goto_id(&mut self, id: i32, dest_tile: TileType) {
-        // Freeze curr level
-        dungeon::freeze_entities(&mut self.ecs);
+        // Freeze curr level, bringing along any allies close enough to follow
+        let travelling_allies = dungeon::freeze_entities(&mut self.ecs, true);
         self.generate_world_map(id, dest_tile);
+        dungeon::place_allies(&mut self.ecs, &travelling_allies);
         let mapname = self.ecs.fetch::<Map>().name.clone();
+        let colour = crate::raws::get_level_colour(&crate::raws::RAWS.lock().unwrap(), id);
         gamelog::Logger
             ::new()
             .append("You head to")
-            .colour(rgb_to_u8(get_local_col(id)))
+            .colour(rgb_to_u8(colour))
+            .append_n(&mapname)
+            .colour(WHITE)
+            .period()
+            .log();
+        gamelog::record_event(EVENT::ChangedFloor(mapname));
+    }
+
+    /// Drops the player down to the level with the given id. Nobody follows them down,
+    /// and they land wherever they land, rather than at the foot of the stairs.
+    fn fall_to_id(&mut self, id: i32) {
+        dungeon::freeze_entities(&mut self.ecs, false);
+        self.generate_world_map(id, TileType::Floor);
+        dungeon::place_fallen_player(&mut self.ecs);
+        let mapname = self.ecs.fetch::<Map>().name.clone();
+        let colour = crate::raws::get_level_colour(&crate::raws::RAWS.lock().unwrap(), id);
+        gamelog::Logger
+            ::new()
+            .append("You land in")
+            .colour(rgb_to_u8(colour))
             .append_n(&mapname)
             .colour(WHITE)
             .period()
@@ -151,6 +227,8 @@ impl State {
         }
         // Replace map list
         self.ecs.insert(dungeon::MasterDungeonMap::new());
+        self.ecs.insert(quests::QuestLog::new());
+        reputation::clear_reputation();
         self.generate_world_map(1, TileType::Floor);
 
         gamelog::setup_log();
@@ -228,6 +306,12 @@ impl GameState for State {
                         RunState::ShowIdentify => {
                             new_runstate = RunState::ShowIdentify;
                         }
+                        RunState::ShowLevelUp { attribute } => {
+                            new_runstate = RunState::ShowLevelUp { attribute };
+                        }
+                        RunState::FallToLevel(id) => {
+                            new_runstate = RunState::FallToLevel(id);
+                        }
                         _ => {
                             new_runstate = RunState::Ticking;
                         }
@@ -322,6 +406,11 @@ impl GameState for State {
                                     aoe: 0,
                                 };
                             }
+                        } else if self.ecs.read_storage::<Container>().get(item_entity).is_some() {
+                            new_runstate = RunState::ShowContainer {
+                                container: item_entity,
+                                mode: gui::ContainerMode::Take,
+                            };
                         } else {
                             let mut intent = self.ecs.write_storage::<WantsToUseItem>();
                             intent
@@ -344,17 +433,13 @@ impl GameState for State {
                     gui::ItemMenuResult::NoResponse => {}
                     gui::ItemMenuResult::Selected => {
                         let item_entity = result.1.unwrap();
-                        let mut removekey = self.ecs.write_storage::<WantsToRemoveKey>();
-                        let mut intent = self.ecs.write_storage::<WantsToDropItem>();
-                        removekey
-                            .insert(item_entity, WantsToRemoveKey {})
-                            .expect("Unable to insert WantsToRemoveKey");
-                        intent
-                            .insert(*self.ecs.fetch::<Entity>(), WantsToDropItem {
-                                item: item_entity,
-                            })
-                            .expect("Unable to insert intent");
-                        new_runstate = RunState::Ticking;
+                        let action = gui::QuantityAction::Drop;
+                        if gui::quantity_ecs(&self.ecs, item_entity) > 1 {
+                            new_runstate = RunState::ShowQuantity { item: item_entity, amount: 0, action };
+                        } else {
+                            self.item_intent(item_entity, action);
+                            new_runstate = RunState::Ticking;
+                        }
                     }
                 }
             }
@@ -377,6 +462,109 @@ impl GameState for State {
                     }
                 }
             }
+            RunState::ShowShop { shopkeeper, mode } => {
+                let result = gui::show_shop(self, ctx, shopkeeper, mode);
+                match result.0 {
+                    gui::ShopMenuResult::Cancel => {
+                        new_runstate = RunState::AwaitingInput;
+                    }
+                    gui::ShopMenuResult::NoResponse => {}
+                    gui::ShopMenuResult::SwitchMode => {
+                        let mode = match mode {
+                            gui::ShopMode::Buy => gui::ShopMode::Sell,
+                            gui::ShopMode::Sell => gui::ShopMode::Buy,
+                        };
+                        new_runstate = RunState::ShowShop { shopkeeper, mode };
+                    }
+                    gui::ShopMenuResult::Selected => {
+                        let item = result.1.unwrap();
+                        let player_entity = *self.ecs.fetch::<Entity>();
+                        match mode {
+                            gui::ShopMode::Buy => {
+                                let mut intent = self.ecs.write_storage::<WantsToBuy>();
+                                intent
+                                    .insert(player_entity, WantsToBuy { shopkeeper, item })
+                                    .expect("Unable to insert intent");
+                                new_runstate = RunState::Ticking;
+                            }
+                            gui::ShopMode::Sell => {
+                                let action = gui::QuantityAction::Sell { shopkeeper };
+                                if gui::quantity_ecs(&self.ecs, item) > 1 {
+                                    new_runstate = RunState::ShowQuantity { item, amount: 0, action };
+                                } else {
+                                    self.item_intent(item, action);
+                                    new_runstate = RunState::Ticking;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            RunState::ShowContainer { container, mode } => {
+                let result = gui::show_container(self, ctx, container, mode);
+                match result.0 {
+                    gui::ContainerMenuResult::Cancel => {
+                        new_runstate = RunState::AwaitingInput;
+                    }
+                    gui::ContainerMenuResult::NoResponse => {}
+                    gui::ContainerMenuResult::SwitchMode => {
+                        let mode = match mode {
+                            gui::ContainerMode::Take => gui::ContainerMode::Put,
+                            gui::ContainerMode::Put => gui::ContainerMode::Take,
+                        };
+                        new_runstate = RunState::ShowContainer { container, mode };
+                    }
+                    gui::ContainerMenuResult::Selected => {
+                        let item = result.1.unwrap();
+                        let player_entity = *self.ecs.fetch::<Entity>();
+                        match mode {
+                            gui::ContainerMode::Take => {
+                                let mut intent = self.ecs.write_storage::<WantsToTakeFromContainer>();
+                                intent
+                                    .insert(player_entity, WantsToTakeFromContainer { container, item })
+                                    .expect("Unable to insert intent");
+                                new_runstate = RunState::Ticking;
+                            }
+                            gui::ContainerMode::Put => {
+                                let action = gui::QuantityAction::Put { container };
+                                if gui::quantity_ecs(&self.ecs, item) > 1 {
+                                    new_runstate = RunState::ShowQuantity { item, amount: 0, action };
+                                } else {
+                                    self.item_intent(item, action);
+                                    new_runstate = RunState::Ticking;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            RunState::ShowQuantity { item, amount, action } => {
+                let result = gui::show_quantity(self, ctx, item, amount, action);
+                match result {
+                    gui::QuantityMenuResult::Cancel => {
+                        new_runstate = RunState::AwaitingInput;
+                    }
+                    gui::QuantityMenuResult::NoResponse { amount } => {
+                        new_runstate = RunState::ShowQuantity { item, amount, action };
+                    }
+                    gui::QuantityMenuResult::Selected { amount } => {
+                        // Anything less than the whole stack gets split off into its own stack first.
+                        // If it can't be, nothing happens, rather than acting on the whole stack.
+                        let total = gui::quantity_ecs(&self.ecs, item);
+                        let item = if amount > 0 && amount < total {
+                            spawner::split_stack(&mut self.ecs, item, amount)
+                        } else {
+                            Some(item)
+                        };
+                        if let Some(item) = item {
+                            self.item_intent(item, action);
+                            new_runstate = RunState::Ticking;
+                        } else {
+                            new_runstate = RunState::AwaitingInput;
+                        }
+                    }
+                }
+            }
             RunState::ShowTargeting { x, y, range, item, aoe } => {
                 let result = gui::ranged_target(self, ctx, x, y, range, aoe);
                 match result.0 {
@@ -398,6 +586,84 @@ impl GameState for State {
                     }
                 }
             }
+            RunState::ShowHire { hireling } => {
+                let result = gui::show_hire(&self.ecs, ctx, hireling);
+                match result {
+                    gui::YesNoResult::NoSelection => {}
+                    gui::YesNoResult::No => {
+                        new_runstate = RunState::AwaitingInput;
+                    }
+                    gui::YesNoResult::Yes => {
+                        gui::try_hire(&mut self.ecs, hireling);
+                        new_runstate = RunState::Ticking;
+                    }
+                }
+            }
+            RunState::ShowDialogue { speaker, node } => {
+                let result = gui::show_dialogue(&self.ecs, ctx, speaker, node);
+                match result {
+                    gui::DialogueResult::Cancel => {
+                        new_runstate = RunState::AwaitingInput;
+                    }
+                    gui::DialogueResult::NoResponse => {}
+                    gui::DialogueResult::Selected { option } => {
+                        new_runstate = match
+                            gui::choose_dialogue_option(&mut self.ecs, speaker, node, option)
+                        {
+                            Some(next) => RunState::ShowDialogue { speaker, node: next },
+                            None => RunState::Ticking,
+                        };
+                    }
+                }
+            }
+            RunState::ShowAllyCommands => {
+                let result = gui::show_ally_commands(&self.ecs, ctx);
+                match result {
+                    gui::AllyMenuResult::Cancel => {
+                        new_runstate = RunState::AwaitingInput;
+                    }
+                    gui::AllyMenuResult::NoResponse => {}
+                    gui::AllyMenuResult::Selected { command: AllyCommand::Attack } => {
+                        let (min_x, _max_x, min_y, _max_y, x_offset, y_offset) =
+                            camera::get_screen_bounds(&self.ecs, ctx);
+                        let ppos = self.ecs.fetch::<Point>();
+                        new_runstate = RunState::ShowAllyTarget {
+                            x: ppos.x + x_offset - min_x,
+                            y: ppos.y + y_offset - min_y,
+                        };
+                    }
+                    gui::AllyMenuResult::Selected { command } => {
+                        gui::command_allies(&mut self.ecs, command, None);
+                        new_runstate = RunState::Ticking;
+                    }
+                }
+            }
+            RunState::ShowAllyTarget { x, y } => {
+                let range = crate::data::entity::DEFAULT_VIEWSHED_STANDARD;
+                let result = gui::ranged_target(self, ctx, x, y, range, 0);
+                match result.0 {
+                    gui::TargetResult::Cancel => {
+                        new_runstate = RunState::AwaitingInput;
+                    }
+                    gui::TargetResult::NoResponse { x, y } => {
+                        new_runstate = RunState::ShowAllyTarget { x, y };
+                    }
+                    gui::TargetResult::Selected => {
+                        let target = result.1.and_then(|p| gui::ally_target_at(&self.ecs, p));
+                        if let Some(target) = target {
+                            gui::command_allies(&mut self.ecs, AllyCommand::Attack, Some(target));
+                            new_runstate = RunState::Ticking;
+                        } else {
+                            gamelog::Logger
+                                ::new()
+                                .append(crate::data::messages::ALLY_NOTHING_TO_ATTACK)
+                                .period()
+                                .log();
+                            new_runstate = RunState::AwaitingInput;
+                        }
+                    }
+                }
+            }
             RunState::ShowRemoveCurse => {
                 let result = gui::remove_curse(self, ctx);
                 match result.0 {
@@ -435,6 +701,13 @@ impl GameState for State {
                         {
                             beatitude.known = true;
                         }
+                        if
+                            let Some(enchantment) = self.ecs
+                                .write_storage::<Enchantment>()
+                                .get_mut(item_entity)
+                        {
+                            enchantment.known = true;
+                        }
                         new_runstate = RunState::Ticking;
                     }
                 }
@@ -515,6 +788,11 @@ impl GameState for State {
                 self.mapgen_next_state = Some(RunState::PreRun);
                 new_runstate = RunState::MapGeneration;
             }
+            RunState::FallToLevel(id) => {
+                self.fall_to_id(id);
+                self.mapgen_next_state = Some(RunState::PreRun);
+                new_runstate = RunState::MapGeneration;
+            }
             RunState::HelpScreen => {
                 let result = gui::show_help(ctx);
                 match result {
@@ -525,6 +803,39 @@ impl GameState for State {
                     _ => {}
                 }
             }
+            RunState::ShowLevelUp { attribute } => {
+                let result = gui::show_level_up(self, ctx, attribute);
+                match result {
+                    gui::LevelUpResult::NoResponse { attribute } => {
+                        new_runstate = RunState::ShowLevelUp { attribute };
+                    }
+                    gui::LevelUpResult::Selected { attribute, perk } => {
+                        if gui::apply_level_up(&mut self.ecs, attribute, perk) {
+                            new_runstate = RunState::ShowLevelUp { attribute: None };
+                        } else {
+                            new_runstate = RunState::Ticking;
+                        }
+                    }
+                }
+            }
+            RunState::ShowSkills => {
+                let result = gui::show_skills(&self.ecs, ctx);
+                match result {
+                    gui::YesNoResult::Yes => {
+                        new_runstate = RunState::AwaitingInput;
+                    }
+                    _ => {}
+                }
+            }
+            RunState::ShowQuestLog => {
+                let result = gui::show_quest_log(&self.ecs, ctx);
+                match result {
+                    gui::YesNoResult::Yes => {
+                        new_runstate = RunState::AwaitingInput;
+                    }
+                    _ => {}
+                }
+            }
             RunState::MagicMapReveal { row, cursed } => {
                 let mut map = self.ecs.fetch_mut::<Map>();
 
@@ -2,15 +2,29 @@ use super::{
     effects::{ add_effect, aoe_tiles, EffectType, Targets },
     gamelog,
     gui::renderable_colour,
+    DamageType,
     EntityMoved,
     EntryTrigger,
+    Levitating,
     Map,
     Name,
     Point,
     Position,
     Renderable,
+    Telepath,
+    TileType,
+    Viewshed,
     AOE,
 };
+use crate::data::entity::{ LAVA_DAMAGE_DIE, THIN_FLOOR_ONE_IN };
+use crate::data::messages::{
+    HAZARD_ICE,
+    HAZARD_ICE_OTHER,
+    HAZARD_LAVA,
+    HAZARD_LAVA_OTHER,
+    HAZARD_THIN_FLOOR,
+    HAZARD_THIN_FLOOR_OTHER,
+};
 use bracket_lib::prelude::*;
 use specs::prelude::*;
 
@@ -19,21 +33,107 @@ pub struct TriggerSystem {}
 impl<'a> System<'a> for TriggerSystem {
     #[allow(clippy::type_complexity)]
     type SystemData = (
-        ReadExpect<'a, Map>,
+        WriteExpect<'a, Map>,
         WriteStorage<'a, EntityMoved>,
-        ReadStorage<'a, Position>,
+        WriteStorage<'a, Position>,
         ReadStorage<'a, EntryTrigger>,
         ReadStorage<'a, Name>,
         Entities<'a>,
         ReadStorage<'a, AOE>,
         ReadStorage<'a, Renderable>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadStorage<'a, Levitating>,
+        WriteExpect<'a, Point>,
+        WriteStorage<'a, Viewshed>,
+        WriteStorage<'a, Telepath>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (map, mut entity_moved, position, entry_trigger, names, entities, aoes, renderables) =
-            data;
-        for (entity, mut _entity_moved, pos) in (&entities, &mut entity_moved, &position).join() {
+        let (
+            mut map,
+            mut entity_moved,
+            mut position,
+            entry_trigger,
+            names,
+            entities,
+            aoes,
+            renderables,
+            player_entity,
+            mut rng,
+            levitating,
+            mut player_pos,
+            mut viewsheds,
+            mut telepaths,
+        ) = data;
+        for (entity, moved, pos) in (&entities, &entity_moved, &mut position).join() {
+            // Anything that isn't levitating over them is at the mercy of the floor's hazards.
+            let grounded = levitating.get(entity).is_none();
+            let slid = grounded && slide(&map, entity, pos, moved.from);
             let idx = map.xy_idx(pos.x, pos.y);
+            if slid {
+                if let Some(viewshed) = viewsheds.get_mut(entity) {
+                    viewshed.dirty = true;
+                }
+                if let Some(telepath) = telepaths.get_mut(entity) {
+                    telepath.dirty = true;
+                }
+                if entity == *player_entity {
+                    player_pos.x = pos.x;
+                    player_pos.y = pos.y;
+                    gamelog::Logger::new().append(HAZARD_ICE).log();
+                } else if map.visible_tiles[idx] {
+                    if let Some(name) = names.get(entity) {
+                        gamelog::Logger
+                            ::new()
+                            .append("The")
+                            .colour(renderable_colour(&renderables, entity))
+                            .append(&name.name)
+                            .colour(WHITE)
+                            .append(HAZARD_ICE_OTHER)
+                            .period()
+                            .log();
+                    }
+                }
+            }
+            // Anything that ends up in lava gets burned for it.
+            if map.tiles[idx] == TileType::Lava && grounded {
+                if entity == *player_entity {
+                    gamelog::Logger::new().append(HAZARD_LAVA).log();
+                } else if map.visible_tiles[idx] {
+                    if let Some(name) = names.get(entity) {
+                        gamelog::Logger
+                            ::new()
+                            .append("The")
+                            .colour(renderable_colour(&renderables, entity))
+                            .append(&name.name)
+                            .colour(WHITE)
+                            .append(HAZARD_LAVA_OTHER)
+                            .period()
+                            .log();
+                    }
+                }
+                add_effect(
+                    None,
+                    EffectType::Damage {
+                        amount: rng.roll_dice(2, LAVA_DAMAGE_DIE),
+                        damage_type: DamageType::Fire,
+                    },
+                    Targets::Entity { target: entity }
+                );
+            }
+            // Thin floor might give way under anything heavier than air.
+            if map.tiles[idx] == TileType::ThinFloor && grounded && rng.roll_dice(1, THIN_FLOOR_ONE_IN) == 1 {
+                map.tiles[idx] = TileType::Chasm;
+                if entity == *player_entity {
+                    gamelog::Logger::new().append(HAZARD_THIN_FLOOR).log();
+                } else if map.visible_tiles[idx] {
+                    gamelog::Logger::new().append(HAZARD_THIN_FLOOR_OTHER).log();
+                }
+            }
+            if map.tiles[idx] == TileType::Chasm {
+                add_effect(None, EffectType::Fall, Targets::Entity { target: entity });
+            }
             crate::spatial::for_each_tile_content(idx, |entity_id| {
                 if entity != entity_id {
                     let maybe_trigger = entry_trigger.get(entity_id);
@@ -75,3 +175,30 @@ impl<'a> System<'a> for TriggerSystem {
         entity_moved.clear();
     }
 }
+
+/// Ice keeps anything on it going the way it was headed, until it's off the ice or something's
+/// in the way. Only a step onto a neighbouring tile has a way it was headed. Returns true if
+/// anything slid at all.
+pub fn slide(map: &Map, entity: Entity, pos: &mut Position, from: usize) -> bool {
+    let delta_x = pos.x - (from as i32) % map.width;
+    let delta_y = pos.y - (from as i32) / map.width;
+    if delta_x.abs() > 1 || delta_y.abs() > 1 || (delta_x == 0 && delta_y == 0) {
+        return false;
+    }
+    let mut slid = false;
+    while map.tiles[map.xy_idx(pos.x, pos.y)] == TileType::Ice {
+        let (next_x, next_y) = (pos.x + delta_x, pos.y + delta_y);
+        if next_x < 0 || next_x > map.width - 1 || next_y < 0 || next_y > map.height - 1 {
+            break;
+        }
+        let (here_idx, next_idx) = (map.xy_idx(pos.x, pos.y), map.xy_idx(next_x, next_y));
+        if crate::spatial::is_blocked(next_idx) {
+            break;
+        }
+        pos.x = next_x;
+        pos.y = next_y;
+        crate::spatial::move_entity(entity, here_idx, next_idx);
+        slid = true;
+    }
+    slid
+}
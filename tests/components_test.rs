@@ -45,3 +45,16 @@ fn get_damage_modifier_multiplier() {
     assert_eq!(res_mod, &0.5);
     assert_eq!(immune_mod, &0.0);
 }
+
+#[test]
+fn corpse_rots_with_age() {
+    let mut corpse = Corpse { mob: "rat".to_string(), age: 0, poisonous: false, intrinsic: None };
+    assert_eq!(corpse.rot_state(), RotState::Fresh);
+    assert_eq!(corpse.name().0, "rat corpse");
+    corpse.age = 100;
+    assert_eq!(corpse.rot_state(), RotState::Old);
+    assert_eq!(corpse.name().1, "old rat corpses");
+    corpse.age = 1000;
+    assert_eq!(corpse.rot_state(), RotState::Rotten);
+    assert_eq!(corpse.name().0, "rotten rat corpse");
+}
@@ -0,0 +1,133 @@
+// tests/effects_test.rs
+use bracket_lib::prelude::{ Point, RandomNumberGenerator };
+use rust_rl::effects::{ add_effect, run_effects_queue, EffectType, Targets };
+use rust_rl::*;
+use specs::prelude::*;
+use std::sync::Mutex;
+
+// The effects queue is global, so tests that run it mustn't run at the same time.
+static EFFECTS: Mutex<()> = Mutex::new(());
+
+#[test]
+fn levitating_things_dont_fall() {
+    let _lock = EFFECTS.lock().unwrap();
+    let mut ecs = World::new();
+    ecs.register::<Position>();
+    ecs.register::<OtherLevelPosition>();
+    ecs.register::<Pools>();
+    ecs.register::<Item>();
+    ecs.register::<Levitating>();
+    let map = Map::new(false, 1000, 10, 10, 0, "Test Map", "Test Map", 1);
+    spatial::set_size(map.tiles.len());
+    ecs.insert(map);
+    ecs.insert(MasterDungeonMap::new());
+    ecs.insert(RandomNumberGenerator::seeded(1));
+    let player = ecs.create_entity().build();
+    ecs.insert(player);
+    let item = || Item { weight: 1.0, value: 0.0, category: ItemType::Tool };
+    let floating = ecs.create_entity().with(Position { x: 2, y: 2 }).with(item()).with(Levitating {}).build();
+    let grounded = ecs.create_entity().with(Position { x: 3, y: 3 }).with(item()).build();
+
+    // Trapdoors, chasms, and holes dug through the floor all drop things with a Fall.
+    add_effect(None, EffectType::Fall, Targets::Entity { target: floating });
+    add_effect(None, EffectType::Fall, Targets::Entity { target: grounded });
+    run_effects_queue(&mut ecs);
+
+    let positions = ecs.read_storage::<Position>();
+    let elsewhere = ecs.read_storage::<OtherLevelPosition>();
+    assert!(positions.get(floating).is_some());
+    assert!(elsewhere.get(floating).is_none());
+    assert!(positions.get(grounded).is_none());
+    assert_eq!(elsewhere.get(grounded).map(|p| p.id), Some(1001));
+}
+
+#[test]
+fn falling_things_dont_land_on_each_other() {
+    let _lock = EFFECTS.lock().unwrap();
+    let mut ecs = World::new();
+    ecs.register::<Position>();
+    ecs.register::<OtherLevelPosition>();
+    ecs.register::<Pools>();
+    ecs.register::<Item>();
+    ecs.register::<Levitating>();
+    // The level below only has two tiles to land on.
+    let mut below = Map::new(false, 1001, 10, 10, 0, "Below", "Below", 2);
+    for x in [3, 6] {
+        let idx = below.xy_idx(x, 5);
+        below.tiles[idx] = TileType::Floor;
+    }
+    let mut dm = MasterDungeonMap::new();
+    dm.store_map(&below);
+    let map = Map::new(false, 1000, 10, 10, 0, "Test Map", "Test Map", 1);
+    spatial::set_size(map.tiles.len());
+    ecs.insert(map);
+    ecs.insert(dm);
+    ecs.insert(RandomNumberGenerator::seeded(1));
+    let player = ecs.create_entity().build();
+    ecs.insert(player);
+    let item = || Item { weight: 1.0, value: 0.0, category: ItemType::Tool };
+    let first = ecs.create_entity().with(Position { x: 2, y: 2 }).with(item()).build();
+    let second = ecs.create_entity().with(Position { x: 2, y: 2 }).with(item()).build();
+
+    add_effect(None, EffectType::Fall, Targets::Entity { target: first });
+    add_effect(None, EffectType::Fall, Targets::Entity { target: second });
+    run_effects_queue(&mut ecs);
+
+    let elsewhere = ecs.read_storage::<OtherLevelPosition>();
+    let (first, second) = (elsewhere.get(first).unwrap(), elsewhere.get(second).unwrap());
+    assert_eq!((first.id, second.id), (1001, 1001));
+    assert_eq!(first.y, 5);
+    assert_eq!(second.y, 5);
+    assert_ne!(first.x, second.x);
+}
+
+fn open_map() -> Map {
+    let mut map = Map::new(false, 1, 21, 21, 0, "Test Map", "Test Map", 1);
+    for y in 1..20 {
+        for x in 1..20 {
+            let idx = map.xy_idx(x, y);
+            map.tiles[idx] = TileType::Floor;
+        }
+    }
+    map
+}
+
+#[test]
+fn cones_spread_out_the_way_theyre_aimed() {
+    let map = open_map();
+    let origin = Point::new(10, 10);
+    let range = 4;
+    let mut sizes = Vec::new();
+    for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (-1, 1), (1, -1), (-1, -1)] {
+        let tiles = effects::cone_tiles(&map, origin, Point::new(origin.x + dx, origin.y + dy), range);
+        assert!(!tiles.is_empty());
+        assert!(!tiles.contains(&map.xy_idx(origin.x, origin.y)));
+        // The tile straight ahead is always in it, and the one straight behind never is.
+        assert!(tiles.contains(&map.xy_idx(origin.x + dx, origin.y + dy)));
+        assert!(!tiles.contains(&map.xy_idx(origin.x - dx, origin.y - dy)));
+        for idx in tiles.iter() {
+            let (x, y) = ((*idx as i32) % map.width - origin.x, (*idx as i32) / map.width - origin.y);
+            assert!(x * x + y * y <= range * range);
+            assert!(x * dx + y * dy > 0);
+        }
+        sizes.push(tiles.len());
+    }
+    // Every cardinal cone is the same size, and so is every diagonal one.
+    assert!(sizes[..4].iter().all(|size| *size == sizes[0]));
+    assert!(sizes[4..].iter().all(|size| *size == sizes[4]));
+}
+
+#[test]
+fn cones_stop_at_the_edge_of_the_map() {
+    let map = open_map();
+    let origin = Point::new(1, 10);
+    assert!(effects::cone_tiles(&map, origin, Point::new(0, 10), 4).is_empty());
+    let tiles = effects::cone_tiles(&map, origin, Point::new(2, 10), 4);
+    assert!(!tiles.is_empty());
+    for idx in tiles.iter() {
+        let (x, y) = ((*idx as i32) % map.width, (*idx as i32) / map.width);
+        assert!(x >= 1 && x <= map.width - 2 && y >= 1 && y <= map.height - 2);
+    }
+    // A cone aimed at its own origin has nowhere to go.
+    assert!(effects::cone_tiles(&map, origin, origin, 4).is_empty());
+}
@@ -41,3 +41,15 @@ fn cloning_events() {
     let cloned_events = clone_events();
     assert_eq!(EVENTS.lock().unwrap().clone(), cloned_events);
 }
+
+#[test]
+fn counting_kills_by_name() {
+    let _lock = SINGLE_THREAD.lock();
+    clear_events();
+    record_event(EVENT::Killed("jackal".to_string()));
+    record_event(EVENT::Killed("jackal".to_string()));
+    record_event(EVENT::Killed("rat".to_string()));
+    assert_eq!(get_event_count(EVENT::COUNT_KILLED), 3);
+    assert_eq!(get_event_count(EVENT::count_killed("jackal")), 2);
+    assert_eq!(get_event_count(EVENT::count_killed("rat")), 1);
+}
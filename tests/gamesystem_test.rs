@@ -0,0 +1,71 @@
+// tests/gamesystem_test.rs
+use bracket_lib::prelude::RandomNumberGenerator;
+use rust_rl::components::{ Skill, Skills, BUC };
+use rust_rl::data::entity::{ SELL_PRICE_DIVISOR, SKILL_MAX_LEVEL, SKILL_TRAINING_PER_LEVEL };
+use rust_rl::gamesystem::{ buy_price, roll_enchantment, sell_price, skill_training_needed, train_skill };
+use std::collections::HashMap;
+
+fn untrained() -> Skills {
+    Skills { skills: HashMap::new(), training: HashMap::new() }
+}
+
+#[test]
+fn training_needed_grows_with_level() {
+    assert_eq!(skill_training_needed(0), SKILL_TRAINING_PER_LEVEL);
+    assert_eq!(skill_training_needed(2), SKILL_TRAINING_PER_LEVEL * 3);
+    assert!(skill_training_needed(3) > skill_training_needed(2));
+}
+
+#[test]
+fn weapon_skills_advance_with_use() {
+    let mut skills = untrained();
+    for _i in 0..skill_training_needed(0) - 1 {
+        assert!(!train_skill(Skill::LongBlade, &mut skills));
+    }
+    assert!(train_skill(Skill::LongBlade, &mut skills));
+    assert_eq!(skills.skills[&Skill::LongBlade], 1);
+    assert_eq!(skills.training[&Skill::LongBlade], 0);
+}
+
+#[test]
+fn only_weapon_skills_train_and_only_so_far() {
+    let mut skills = untrained();
+    assert!(!train_skill(Skill::Melee, &mut skills));
+    assert!(skills.training.get(&Skill::Melee).is_none());
+    skills.skills.insert(Skill::Axe, SKILL_MAX_LEVEL);
+    for _i in 0..skill_training_needed(SKILL_MAX_LEVEL) {
+        assert!(!train_skill(Skill::Axe, &mut skills));
+    }
+    assert_eq!(skills.skills[&Skill::Axe], SKILL_MAX_LEVEL);
+}
+
+#[test]
+fn prices_scale_with_beatitude_and_charisma() {
+    assert_eq!(buy_price(100.0, &BUC::Uncursed, 12), 100);
+    assert_eq!(buy_price(100.0, &BUC::Blessed, 12), 125);
+    assert_eq!(buy_price(100.0, &BUC::Cursed, 12), 50);
+    assert_eq!(buy_price(100.0, &BUC::Uncursed, 3), 200);
+    assert_eq!(buy_price(100.0, &BUC::Uncursed, 20), 50);
+    assert!(buy_price(100.0, &BUC::Uncursed, 8) > buy_price(100.0, &BUC::Uncursed, 16));
+    assert_eq!(sell_price(100.0, &BUC::Uncursed), 100 / SELL_PRICE_DIVISOR);
+    assert!(sell_price(100.0, &BUC::Blessed) > sell_price(100.0, &BUC::Cursed));
+    // Nothing is ever free, however worthless.
+    assert_eq!(buy_price(0.1, &BUC::Cursed, 20), 1);
+    assert_eq!(sell_price(0.0, &BUC::Cursed), 1);
+}
+
+#[test]
+fn enchantments_roll_within_bounds() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let (mut uncursed_enchanted, mut blessed_enchanted) = (false, false);
+    for _i in 0..500 {
+        assert!((-3..=-1).contains(&roll_enchantment(&mut rng, &BUC::Cursed)));
+        let uncursed = roll_enchantment(&mut rng, &BUC::Uncursed);
+        assert!((0..=1).contains(&uncursed));
+        uncursed_enchanted |= uncursed == 1;
+        let blessed = roll_enchantment(&mut rng, &BUC::Blessed);
+        assert!((0..=3).contains(&blessed));
+        blessed_enchanted |= blessed > 0;
+    }
+    assert!(uncursed_enchanted && blessed_enchanted);
+}
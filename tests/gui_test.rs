@@ -0,0 +1,25 @@
+// tests/gui_test.rs
+use rust_rl::components::{ LevelUpRecord, Perk };
+use rust_rl::data::entity::LEVELS_PER_ATTRIBUTE_POINT;
+use rust_rl::gui::{ describe_level_up, grants_attribute_point };
+
+#[test]
+fn attribute_points_every_few_levels() {
+    assert!(!grants_attribute_point(1));
+    assert!(!grants_attribute_point(LEVELS_PER_ATTRIBUTE_POINT - 1));
+    assert!(grants_attribute_point(LEVELS_PER_ATTRIBUTE_POINT));
+    assert!(!grants_attribute_point(LEVELS_PER_ATTRIBUTE_POINT + 1));
+    assert!(grants_attribute_point(LEVELS_PER_ATTRIBUTE_POINT * 2));
+}
+
+#[test]
+fn describing_level_ups() {
+    let perk_only = LevelUpRecord { level: 3, perk: Perk::Toughness, attribute: None };
+    assert_eq!(describe_level_up(&perk_only), "Took toughness at level 3");
+    let with_attribute = LevelUpRecord {
+        level: 4,
+        perk: Perk::Toughness,
+        attribute: Some("strength".to_string()),
+    };
+    assert_eq!(describe_level_up(&with_attribute), "Took toughness at level 4, and raised strength");
+}
@@ -0,0 +1,86 @@
+// tests/inventory_test.rs
+use bracket_lib::prelude::RandomNumberGenerator;
+use rust_rl::*;
+use specs::prelude::*;
+use specs::saveload::{ SimpleMarker, SimpleMarkerAllocator };
+
+fn setup() -> World {
+    raws::load_raws();
+    let mut ecs = World::new();
+    ecs.register::<SimpleMarker<SerializeMe>>();
+    ecs.register::<Name>();
+    ecs.register::<Item>();
+    ecs.register::<Currency>();
+    ecs.register::<Position>();
+    ecs.register::<InBackpack>();
+    ecs.register::<InContainer>();
+    ecs.register::<WantsToAssignKey>();
+    ecs.register::<WantsToRemoveKey>();
+    ecs.register::<WantsToDropItem>();
+    ecs.register::<WantsToPickupItem>();
+    ecs.register::<EquipmentChanged>();
+    ecs.register::<Renderable>();
+    ecs.register::<Beatitude>();
+    ecs.register::<Enchantment>();
+    ecs.register::<Charges>();
+    ecs.register::<Consumable>();
+    ecs.register::<Destructible>();
+    ecs.register::<Stackable>();
+    ecs.register::<RawItem>();
+    ecs.register::<ProvidesHealing>();
+    ecs.register::<MagicItem>();
+    ecs.register::<ObfuscatedName>();
+    ecs.register::<Purse>();
+    ecs.register::<ForSale>();
+    ecs.register::<Faction>();
+    ecs.register::<HasAncestry>();
+    ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
+    ecs.insert(RandomNumberGenerator::seeded(1));
+    ecs.insert(map::MasterDungeonMap::new());
+    let player = ecs.create_entity().build();
+    ecs.insert(player);
+    ecs
+}
+
+fn potions(ecs: &mut World, quantity: i32, pos: raws::SpawnType) -> Entity {
+    let stack = raws
+        ::spawn_named_item(&raws::RAWS.lock().unwrap(), ecs, "potion_health", Some(BUC::Uncursed), pos)
+        .unwrap();
+    ecs.write_storage::<Stackable>().insert(stack, Stackable { quantity }).unwrap();
+    stack
+}
+
+#[test]
+fn dropped_stacks_merge_with_the_same_on_the_floor() {
+    let mut ecs = setup();
+    let dropper = ecs.create_entity().with(Position { x: 3, y: 3 }).build();
+    let on_floor = potions(&mut ecs, 2, raws::SpawnType::AtPosition { x: 3, y: 3 });
+    let elsewhere = potions(&mut ecs, 4, raws::SpawnType::AtPosition { x: 5, y: 5 });
+    let carried = potions(&mut ecs, 3, raws::SpawnType::Carried { by: dropper });
+    ecs.write_storage::<WantsToDropItem>().insert(dropper, WantsToDropItem { item: carried }).unwrap();
+
+    inventory::ItemDropSystem {}.run_now(&ecs);
+    ecs.maintain();
+
+    assert!(!ecs.is_alive(on_floor));
+    assert!(ecs.is_alive(elsewhere));
+    assert_eq!(ecs.read_storage::<Stackable>().get(carried).unwrap().quantity, 5);
+    assert!(ecs.read_storage::<InBackpack>().get(carried).is_none());
+}
+
+#[test]
+fn picked_up_stacks_merge_into_a_monsters_backpack() {
+    let mut ecs = setup();
+    let collector = ecs.create_entity().with(Position { x: 3, y: 3 }).build();
+    let carried = potions(&mut ecs, 3, raws::SpawnType::Carried { by: collector });
+    let on_floor = potions(&mut ecs, 2, raws::SpawnType::AtPosition { x: 3, y: 3 });
+    ecs.write_storage::<WantsToPickupItem>()
+        .insert(collector, WantsToPickupItem { collected_by: collector, item: on_floor })
+        .unwrap();
+
+    inventory::ItemCollectionSystem {}.run_now(&ecs);
+    ecs.maintain();
+
+    assert!(!ecs.is_alive(on_floor));
+    assert_eq!(ecs.read_storage::<Stackable>().get(carried).unwrap().quantity, 5);
+}
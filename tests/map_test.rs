@@ -48,6 +48,84 @@ fn tiletype_with_var_equality() {
     assert_eq!(tile2, tile3);
 }
 
+#[test]
+fn deep_water_is_swimmable() {
+    assert!(!tile_walkable(TileType::DeepWater));
+    assert!(tile_passable(TileType::DeepWater));
+    assert!(tile_deep(TileType::DeepWater));
+    assert!(!tile_deep(TileType::ShallowWater));
+    assert!(!tile_passable(TileType::Wall));
+    assert!(tile_cost(TileType::DeepWater) > tile_cost(TileType::ShallowWater));
+}
+
+#[test]
+fn hazards_are_avoided() {
+    for tile in [TileType::Lava, TileType::Chasm] {
+        assert!(tile_hazardous(tile));
+        assert!(!tile_walkable(tile));
+        assert!(!tile_passable(tile));
+    }
+    assert!(tile_walkable(TileType::Ice) && !tile_hazardous(TileType::Ice));
+    assert!(tile_walkable(TileType::ThinFloor) && !tile_hazardous(TileType::ThinFloor));
+    assert!(tile_cost(TileType::Ice) > tile_cost(TileType::Floor));
+}
+
+#[test]
+fn falling_lands_on_solid_ground() {
+    let mut rng = bracket_lib::prelude::RandomNumberGenerator::seeded(1);
+    let mut map = Map::new(false, 1, 8, 8, 0, "Test Map", "Test Map", 1);
+    assert_eq!(dungeon::landing_spot(&map, &HashSet::new(), &mut rng), None);
+    let (floor, thin_floor, occupied) = (map.xy_idx(2, 2), map.xy_idx(3, 3), map.xy_idx(4, 4));
+    map.tiles[floor] = TileType::Floor;
+    map.tiles[thin_floor] = TileType::ThinFloor;
+    map.tiles[occupied] = TileType::Floor;
+    let blocked: HashSet<usize> = [occupied].into_iter().collect();
+    for _i in 0..10 {
+        assert_eq!(dungeon::landing_spot(&map, &blocked, &mut rng), Some(floor));
+    }
+}
+
+#[test]
+fn separate_regions_get_joined_up() {
+    let mut map = Map::new(false, 1, 30, 20, 0, "Test Map", "Test Map", 1);
+    // Two 5x5 rooms with nothing between them, and a pocket too small to keep.
+    for (x1, y1) in [(2, 2), (20, 12)] {
+        for y in y1..y1 + 5 {
+            for x in x1..x1 + 5 {
+                let idx = map.xy_idx(x, y);
+                map.tiles[idx] = TileType::Floor;
+            }
+        }
+    }
+    let pocket = map.xy_idx(25, 3);
+    map.tiles[pocket] = TileType::Floor;
+
+    rust_rl::map_builders::connect_regions(&mut map);
+
+    assert_eq!(map.tiles[pocket], TileType::Wall);
+    let start = map.xy_idx(2, 2);
+    let mut reached: HashSet<usize> = [start].into_iter().collect();
+    let mut open = vec![start];
+    while let Some(idx) = open.pop() {
+        let (x, y) = ((idx as i32) % map.width, (idx as i32) / map.width);
+        for (nx, ny) in [(x, y - 1), (x, y + 1), (x - 1, y), (x + 1, y)] {
+            if nx < 0 || ny < 0 || nx >= map.width || ny >= map.height {
+                continue;
+            }
+            let next = map.xy_idx(nx, ny);
+            if map.tiles[next] != TileType::Wall && reached.insert(next) {
+                open.push(next);
+            }
+        }
+    }
+    for (idx, tile) in map.tiles.iter().enumerate() {
+        if *tile != TileType::Wall {
+            assert!(reached.contains(&idx), "tile {} can't be reached", idx);
+        }
+    }
+    assert!(reached.contains(&map.xy_idx(24, 16)));
+}
+
 fn init_maps_for_tests() -> (MasterDungeonMap, Map, Map) {
     let dm = MasterDungeonMap::new();
     let (overmap, difficulty, name, short_name, depth) = (false, 0, "Test Map", "Test Map", 0);
@@ -83,3 +161,39 @@ fn map_loading() {
     assert_eq!(loaded_map2.width, map2.width);
     assert_ne!(loaded_map2.width, map1.width);
 }
+
+#[test]
+fn uniques_spawn_once() {
+    let (mut dm, _map1, _map2) = init_maps_for_tests();
+    assert_eq!(dm.unique_state("test_unique"), None);
+    assert!(dm.spawn_unique("test_unique"));
+    assert_eq!(dm.unique_state("test_unique"), Some(UniqueState::Alive));
+    assert!(!dm.spawn_unique("test_unique"));
+    dm.slay_unique("test_unique", 1003);
+    assert_eq!(dm.unique_state("test_unique"), Some(UniqueState::Slain { map_id: 1003 }));
+    assert!(!dm.spawn_unique("test_unique"));
+    assert_eq!(dm.uniques_slain(), vec![("test_unique".to_string(), 1003)]);
+}
+
+#[test]
+fn branch_levels() {
+    let branch: rust_rl::raws::Branch = serde_json
+        ::from_str(
+            r#"{
+                "id": "test_branch", "name": "the test branch", "short_name": "T",
+                "first_id": 100, "last_id": 109, "depth": 3, "builder": "rooms",
+                "difficulty": { "base": 2, "per_level": 2 }, "dead_end": true,
+                "bosses": { "interval": 4, "mobs": ["test_boss"] }
+            }"#
+        )
+        .unwrap();
+    assert!(!branch.contains(99));
+    assert!(branch.contains(100) && branch.contains(109));
+    assert!(!branch.contains(110));
+    assert_eq!(branch.depth_of(102), 5);
+    assert_eq!(branch.difficulty_of(102), 6);
+    assert_eq!(branch.boss_for(103), Some("test_boss"));
+    assert_eq!(branch.boss_for(107), None);
+    assert!(branch.is_dead_end(109) && !branch.is_dead_end(108));
+    assert_eq!(branch.spawn_table("mobs"), "mobs");
+}
@@ -2,3 +2,10 @@
 mod map_test;
 mod gamelog_test;
 mod components_test;
+mod gui_test;
+mod spawner_test;
+mod reputation_test;
+mod gamesystem_test;
+mod effects_test;
+mod inventory_test;
+mod trigger_test;
@@ -0,0 +1,20 @@
+// tests/reputation_test.rs
+use rust_rl::data::entity::{ REPUTATION_HOSTILE, REPUTATION_WARY };
+use rust_rl::raws::Reaction;
+use rust_rl::reputation::reputation_reaction;
+
+#[test]
+fn good_standing_changes_nothing() {
+    assert!(reputation_reaction(Reaction::Ignore, 0) == Reaction::Ignore);
+    assert!(reputation_reaction(Reaction::Flee, 0) == Reaction::Flee);
+    assert!(reputation_reaction(Reaction::Attack, 100) == Reaction::Attack);
+    assert!(reputation_reaction(Reaction::Ignore, REPUTATION_WARY + 1) == Reaction::Ignore);
+}
+
+#[test]
+fn bad_standing_sours_reactions() {
+    assert!(reputation_reaction(Reaction::Ignore, REPUTATION_WARY) == Reaction::Flee);
+    assert!(reputation_reaction(Reaction::Ignore, REPUTATION_HOSTILE + 1) == Reaction::Flee);
+    assert!(reputation_reaction(Reaction::Ignore, REPUTATION_HOSTILE) == Reaction::Attack);
+    assert!(reputation_reaction(Reaction::Flee, REPUTATION_HOSTILE) == Reaction::Attack);
+}
@@ -0,0 +1,162 @@
+// tests/spawner_test.rs
+use bracket_lib::prelude::RandomNumberGenerator;
+use rust_rl::*;
+use specs::prelude::*;
+use specs::saveload::{ SimpleMarker, SimpleMarkerAllocator };
+
+fn has<T: Component>(ecs: &World, entity: Entity) -> bool {
+    ecs.read_storage::<T>().get(entity).is_some()
+}
+
+#[test]
+fn split_stacks_match_the_original() {
+    raws::load_raws();
+    let mut ecs = World::new();
+    ecs.register::<SimpleMarker<SerializeMe>>();
+    ecs.register::<Name>();
+    ecs.register::<Item>();
+    ecs.register::<Currency>();
+    ecs.register::<Position>();
+    ecs.register::<InBackpack>();
+    ecs.register::<InContainer>();
+    ecs.register::<WantsToAssignKey>();
+    ecs.register::<Renderable>();
+    ecs.register::<Beatitude>();
+    ecs.register::<Enchantment>();
+    ecs.register::<Charges>();
+    ecs.register::<Consumable>();
+    ecs.register::<Destructible>();
+    ecs.register::<Stackable>();
+    ecs.register::<RawItem>();
+    ecs.register::<ProvidesHealing>();
+    ecs.register::<MagicItem>();
+    ecs.register::<ObfuscatedName>();
+    ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
+    ecs.insert(RandomNumberGenerator::seeded(1));
+    ecs.insert(map::MasterDungeonMap::new());
+    let player = ecs.create_entity().build();
+    ecs.insert(player);
+    let carrier = ecs.create_entity().build();
+    let stack = raws
+        ::spawn_named_item(
+            &raws::RAWS.lock().unwrap(),
+            &mut ecs,
+            "potion_health",
+            Some(BUC::Blessed),
+            raws::SpawnType::Carried { by: carrier }
+        )
+        .unwrap();
+    ecs.write_storage::<Stackable>().insert(stack, Stackable { quantity: 5 }).unwrap();
+    ecs.write_storage::<Beatitude>().insert(stack, Beatitude { buc: BUC::Blessed, known: true }).unwrap();
+
+    let split = spawner::split_stack(&mut ecs, stack, 2).unwrap();
+    assert_ne!(split, stack);
+    assert_eq!(ecs.read_storage::<Stackable>().get(stack).unwrap().quantity, 3);
+    assert_eq!(ecs.read_storage::<Stackable>().get(split).unwrap().quantity, 2);
+    assert_eq!(ecs.read_storage::<InBackpack>().get(split).unwrap().owner, carrier);
+    assert_eq!(ecs.read_storage::<Beatitude>().get(split), ecs.read_storage::<Beatitude>().get(stack));
+    assert_eq!(
+        ecs.read_storage::<ObfuscatedName>().get(split).map(|n| n.name.clone()),
+        ecs.read_storage::<ObfuscatedName>().get(stack).map(|n| n.name.clone())
+    );
+    assert!(!has::<WantsToAssignKey>(&ecs, split));
+    for (original, copy) in [
+        (has::<Name>(&ecs, stack), has::<Name>(&ecs, split)),
+        (has::<Item>(&ecs, stack), has::<Item>(&ecs, split)),
+        (has::<Position>(&ecs, stack), has::<Position>(&ecs, split)),
+        (has::<InBackpack>(&ecs, stack), has::<InBackpack>(&ecs, split)),
+        (has::<InContainer>(&ecs, stack), has::<InContainer>(&ecs, split)),
+        (has::<Renderable>(&ecs, stack), has::<Renderable>(&ecs, split)),
+        (has::<Enchantment>(&ecs, stack), has::<Enchantment>(&ecs, split)),
+        (has::<Charges>(&ecs, stack), has::<Charges>(&ecs, split)),
+        (has::<Consumable>(&ecs, stack), has::<Consumable>(&ecs, split)),
+        (has::<Destructible>(&ecs, stack), has::<Destructible>(&ecs, split)),
+        (has::<ProvidesHealing>(&ecs, stack), has::<ProvidesHealing>(&ecs, split)),
+        (has::<MagicItem>(&ecs, stack), has::<MagicItem>(&ecs, split)),
+        (has::<ObfuscatedName>(&ecs, stack), has::<ObfuscatedName>(&ecs, split)),
+    ] {
+        assert_eq!(original, copy);
+    }
+}
+
+#[test]
+fn stacks_that_cant_be_remade_arent_split() {
+    raws::load_raws();
+    let mut ecs = World::new();
+    ecs.register::<Name>();
+    ecs.register::<RawItem>();
+    ecs.register::<Stackable>();
+    let stack = ecs
+        .create_entity()
+        .with(Name { name: "mystery".to_string(), plural: "mysteries".to_string() })
+        .with(Stackable { quantity: 5 })
+        .build();
+
+    assert!(spawner::split_stack(&mut ecs, stack, 2).is_none());
+    assert_eq!(ecs.read_storage::<Stackable>().get(stack).unwrap().quantity, 5);
+}
+
+fn ability(effect: &str, dice: Option<&str>) -> raws::MobAbility {
+    raws::MobAbility {
+        name: "test".to_string(),
+        effect: effect.to_string(),
+        range: 6,
+        cooldown: 4,
+        mana: None,
+        dice: dice.map(|d| d.to_string()),
+        summon: Some("goblin".to_string()),
+        count: None,
+        colour: None,
+    }
+}
+
+#[test]
+fn abilities_parse_from_raws() {
+    match raws::parse_ability(&ability("bolt", Some("2d4+1;magic"))).effect {
+        AbilityEffect::Bolt { damage_type, n_dice, sides, modifier } => {
+            assert_eq!(damage_type, DamageType::Magic);
+            assert_eq!((n_dice, sides, modifier), (2, 4, 1));
+        }
+        other => panic!("Expected a bolt, got {:?}", other),
+    }
+    match raws::parse_ability(&ability("breath", Some("3d6;fire"))).effect {
+        AbilityEffect::Breath { damage_type, n_dice, sides, .. } => {
+            assert_eq!(damage_type, DamageType::Fire);
+            assert_eq!((n_dice, sides), (3, 6));
+        }
+        other => panic!("Expected a breath, got {:?}", other),
+    }
+    // Damage without a type is physical.
+    match raws::parse_ability(&ability("bolt", Some("1d8"))).effect {
+        AbilityEffect::Bolt { damage_type, .. } => assert_eq!(damage_type, DamageType::Physical),
+        other => panic!("Expected a bolt, got {:?}", other),
+    }
+    match raws::parse_ability(&ability("summon", None)).effect {
+        AbilityEffect::Summon { id, count } => {
+            assert_eq!(id, "goblin");
+            assert_eq!(count, 1);
+        }
+        other => panic!("Expected a summon, got {:?}", other),
+    }
+    let blink = raws::parse_ability(&ability("blink", None));
+    assert!(matches!(blink.effect, AbilityEffect::Blink));
+    assert_eq!((blink.range, blink.cooldown, blink.mana, blink.ready_in), (6, 4, 0, 0));
+}
+
+#[test]
+#[should_panic]
+fn unknown_ability_effects_dont_parse() {
+    raws::parse_ability(&ability("teleport", None));
+}
+
+#[test]
+#[should_panic]
+fn unknown_ability_damage_types_dont_parse() {
+    raws::parse_ability(&ability("bolt", Some("2d4;acid")));
+}
+
+#[test]
+#[should_panic]
+fn damaging_abilities_need_dice() {
+    raws::parse_ability(&ability("breath", None));
+}
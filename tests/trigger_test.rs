@@ -0,0 +1,46 @@
+// tests/trigger_test.rs
+use bracket_lib::prelude::{ Point, RandomNumberGenerator };
+use rust_rl::*;
+use specs::prelude::*;
+
+#[test]
+fn anything_stepping_onto_ice_slides() {
+    let mut ecs = World::new();
+    ecs.register::<EntityMoved>();
+    ecs.register::<Position>();
+    ecs.register::<EntryTrigger>();
+    ecs.register::<Name>();
+    ecs.register::<AOE>();
+    ecs.register::<Renderable>();
+    ecs.register::<Levitating>();
+    ecs.register::<Viewshed>();
+    ecs.register::<Telepath>();
+    // A corridor along y = 5, with a patch of ice from x = 3 to x = 5.
+    let mut map = Map::new(false, 1, 10, 10, 0, "Test Map", "Test Map", 1);
+    for x in 1..9 {
+        let idx = map.xy_idx(x, 5);
+        map.tiles[idx] = if (3..=5).contains(&x) { TileType::Ice } else { TileType::Floor };
+    }
+    spatial::populate_blocked_from_map(&map);
+    let from = map.xy_idx(2, 5);
+    ecs.insert(map);
+    ecs.insert(RandomNumberGenerator::seeded(1));
+    ecs.insert(Point::new(0, 0));
+    let player = ecs.create_entity().build();
+    ecs.insert(player);
+    let monster = ecs.create_entity().with(Position { x: 3, y: 5 }).with(EntityMoved { from }).build();
+    let floating = ecs
+        .create_entity()
+        .with(Position { x: 3, y: 5 })
+        .with(EntityMoved { from })
+        .with(Levitating {})
+        .build();
+
+    trigger_system::TriggerSystem {}.run_now(&ecs);
+
+    let positions = ecs.read_storage::<Position>();
+    let monster_pos = positions.get(monster).unwrap();
+    assert_eq!((monster_pos.x, monster_pos.y), (6, 5));
+    let floating_pos = positions.get(floating).unwrap();
+    assert_eq!((floating_pos.x, floating_pos.y), (3, 5));
+}